@@ -1,7 +1,12 @@
 use std::sync::Arc;
 
-use bevy_ecs::{schedule::Schedule, world::World};
-use glam::vec3;
+use bevy_ecs::{
+    entity::Entity,
+    query::{With, Without},
+    schedule::{IntoSystemConfigs, Schedule},
+    world::World,
+};
+use glam::{Mat4, Vec3, Vec4Swizzles, vec3, vec4};
 use log::trace;
 use rand::Rng;
 use wgpu::{CommandBuffer, TextureFormat};
@@ -9,29 +14,63 @@ use wgpu::{CommandBuffer, TextureFormat};
 use crate::{
     ecs::{
         components::{
-            gpu_bindings::model_bindings::ModelBindings,
-            materials::unlit_diffuse_material::UnlitDiffuseMaterial,
-            rotate_component::RotateComponent, transform::Transform,
+            camera::Camera, dolly_zoom::DollyZoom, drop_target::DropTarget,
+            gpu_bindings::camera_bindings::CameraBindings,
+            materials::unlit_diffuse_material::UnlitDiffuseMaterial, mesh_collider::MeshCollider,
+            mesh_filter::BasicMeshFilter, persistent::Persistent,
+            projection_blend::ProjectionBlend, rotate_component::RotateComponent,
+            transform::Transform, visible::Visible,
         },
-        entity_bundles::camera_bundle::CameraBundle,
+        entity_bundles::{camera_bundle::CameraBundle, mesh_bundle::MeshSpawner},
         resources::{
             apc_resources::{ApcPlatform, ApcQueue},
+            bloom_settings::BloomSettings,
+            clipboard_resources::ClipboardPlatform,
+            depth_prepass::DepthPrepass,
             http_resources::HttpPlatform,
             input::Input,
+            model_uniform_arena::ModelUniformArena,
+            post_process_settings::PostProcessSettings,
+            render_size_limit::RenderSizeLimit,
             screen_parameters::ScreenParameters,
+            spatial_grid::SpatialGrid,
             time::Time,
+            timers::Timers,
+            window_title::WindowTitle,
         },
+        scene::Scene,
         systems::{
+            billboard_system::billboard_system,
+            camera_shake_system::{camera_shake_system, remove_camera_shake_system},
+            camera_transition_system::{dolly_zoom_system, projection_blend_system},
+            fly_camera_system::update_fly_camera_system,
+            lod_system::lod_system,
+            orbit_camera_system::update_orbit_camera_system,
             rotate_transform_system::rotate_transform_system,
+            smooth_follow_system::smooth_follow_system,
+            timers_system::timers_system,
+            tween_system::tween_system,
             update_camera_system::{update_camera_bindings, update_camera_system},
             update_input_system::update_input_system,
+            update_line_bindings_system::update_line_bindings_system,
             update_model_bindings_system::update_model_bindings_system,
+            update_particles_system::{
+                update_particle_bindings_system, update_particle_emitter_system,
+            },
+            update_spatial_grid_system::update_spatial_grid_system,
         },
     },
-    gpu_resources, include_texture,
-    render::root_renderer::RootRenderer,
-    traits::{apc_traits::ApcHandler, http_traits::HttpRequester},
-    utils::primitives,
+    gpu_resources::{
+        self, layouts::model_uniform_layout::ModelUniformLayout, render_resources::RenderResources,
+    },
+    include_texture,
+    render::root_renderer::{CustomPass, RootRenderer},
+    traits::{
+        apc_traits::{Apc, ApcCallback, ApcHandler, HeadlessApcHandler},
+        clipboard_traits::{ClipboardHandler, HeadlessClipboardHandler},
+        http_traits::{HeadlessHttpRequester, HttpRequester},
+    },
+    utils::{primitives, profiling::profile_scope, texture::Texture},
 };
 
 pub struct Core {
@@ -55,44 +94,27 @@ impl Core {
         queue: Arc<wgpu::Queue>,
         apc_handler: Arc<dyn ApcHandler>,
         http_requester: Arc<dyn HttpRequester>,
+        clipboard_handler: Arc<dyn ClipboardHandler>,
         render_width: u32,
         render_height: u32,
         texture_format: TextureFormat,
     ) -> Self {
-        let mut world = World::new();
-        gpu_resources::initialize_gpu_resources(
-            &mut world,
+        let mut core = Self::new_empty(
             device.clone(),
             queue.clone(),
+            apc_handler,
+            http_requester,
+            clipboard_handler,
+            render_width,
+            render_height,
             texture_format,
         );
 
-        world.insert_resource(Input::new());
-        world.insert_resource(Time::new());
-        world.insert_resource(ScreenParameters::new(render_width, render_height));
-        world.insert_resource(ApcQueue::new());
-        world.insert_resource(ApcPlatform {
-            platform: apc_handler,
-        });
-        world.insert_resource(HttpPlatform {
-            requester: http_requester,
-        });
-
-        let camera_bundle = CameraBundle::new(
-            &world,
-            vec3(0.0, 10.0, -10.0),
-            vec3(0.0, 0.0, 0.0),
-            vec3(0.0, 1.0, 0.0),
-        );
-
-        world.spawn(camera_bundle);
-        let root_renderer = RootRenderer::new(&mut world, render_width, render_height);
-
         // spawn a cube
         let texture = include_texture!("assets/textures/handsome.jpg", &device, &queue);
 
         let mut rng = rand::thread_rng();
-        for _ in 0..100 {
+        for i in 0..100 {
             let mut cube_transform = Transform::from_translation(vec3(0.0, 0.0, 0.0));
             //create random location within a 5x5x5 cube
             cube_transform.translation.x = rng.gen_range(-5.0..5.0);
@@ -100,8 +122,6 @@ impl Core {
             cube_transform.translation.z = rng.gen_range(-5.0..5.0);
 
             let cube_mesh_filter = primitives::create_cube(&device, rng.gen_range(0.5..1.0), 1);
-            let cube_model_bindings = ModelBindings::new(&world, &device, &mut cube_transform);
-            let cube_material = UnlitDiffuseMaterial::new(&world, &texture);
             let cube_rotate_component = RotateComponent {
                 rotate_axis: vec3(
                     rng.gen_range(-1.0..1.0),
@@ -111,26 +131,116 @@ impl Core {
                 rotate_speed: rng.gen_range(0.5..3.0),
             };
 
-            world.spawn((
-                cube_transform,
-                cube_mesh_filter,
-                cube_model_bindings,
-                cube_material,
-                cube_rotate_component,
-            ));
+            let cube = core
+                .spawn_mesh(cube_mesh_filter, &texture)
+                .transform(cube_transform)
+                .spawn();
+            core.world.entity_mut(cube).insert(cube_rotate_component);
+            if i == 0 {
+                core.world.entity_mut(cube).insert(DropTarget);
+            }
         }
 
+        core
+    }
+
+    /// Creates a core without a window surface or any demo content, for tests and servers.
+    /// `texture_format` is the format of the offscreen target passed to `render`.
+    /// Asynchronous procedure calls and HTTP requests are unavailable and fail immediately,
+    /// and the clipboard always reads as empty.
+    pub fn new_headless(
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+        render_width: u32,
+        render_height: u32,
+        texture_format: TextureFormat,
+    ) -> Self {
+        Self::new_empty(
+            device,
+            queue,
+            Arc::new(HeadlessApcHandler),
+            Arc::new(HeadlessHttpRequester),
+            Arc::new(HeadlessClipboardHandler),
+            render_width,
+            render_height,
+            texture_format,
+        )
+    }
+
+    /// Sets up the resources, camera, renderer and schedules shared by every core
+    fn new_empty(
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+        apc_handler: Arc<dyn ApcHandler>,
+        http_requester: Arc<dyn HttpRequester>,
+        clipboard_handler: Arc<dyn ClipboardHandler>,
+        render_width: u32,
+        render_height: u32,
+        texture_format: TextureFormat,
+    ) -> Self {
+        let mut world = World::new();
+        gpu_resources::initialize_gpu_resources(&mut world, device.clone(), queue, texture_format);
+
+        let model_arena = ModelUniformArena::new(&device, world.resource::<ModelUniformLayout>());
+        world.insert_resource(model_arena);
+
+        Self::insert_scene_resources(&mut world);
+        world.insert_resource(RenderSizeLimit::default());
+
+        let (render_width, render_height) = RenderSizeLimit::default().clamp(
+            world.resource::<RenderResources>().max_texture_dimension_2d,
+            render_width,
+            render_height,
+        );
+        world.insert_resource(ScreenParameters::new(render_width, render_height));
+        world.insert_resource(ApcQueue::new());
+        world.insert_resource(ApcPlatform {
+            platform: apc_handler,
+        });
+        world.insert_resource(HttpPlatform {
+            requester: http_requester,
+        });
+        world.insert_resource(ClipboardPlatform {
+            handler: clipboard_handler,
+        });
+
+        Self::spawn_default_camera(&mut world);
+        let root_renderer = RootRenderer::new(&mut world, render_width, render_height);
+
         let mut early_update_schedule = Schedule::default();
         let mut update_schedule = Schedule::default();
         let mut late_update_schedule = Schedule::default();
         let mut pre_render_schedule = Schedule::default();
 
+        early_update_schedule.add_systems(timers_system);
         early_update_schedule.add_systems(update_camera_system);
+        early_update_schedule.add_systems(remove_camera_shake_system);
         update_schedule.add_systems(rotate_transform_system);
+        update_schedule.add_systems(tween_system::<Transform>);
+        update_schedule.add_systems(tween_system::<DollyZoom>);
+        update_schedule.add_systems(tween_system::<ProjectionBlend>);
+        update_schedule.add_systems(dolly_zoom_system.after(tween_system::<DollyZoom>));
+        update_schedule.add_systems(projection_blend_system.after(tween_system::<ProjectionBlend>));
+        update_schedule.add_systems(update_particle_emitter_system);
+        // camera controllers only act on cameras given their component
+        update_schedule.add_systems(update_orbit_camera_system);
+        update_schedule.add_systems(update_fly_camera_system);
+        // followers move after everything they might follow has
+        late_update_schedule.add_systems(smooth_follow_system);
+        late_update_schedule.add_systems(camera_shake_system.after(smooth_follow_system));
+        late_update_schedule.add_systems(lod_system.after(smooth_follow_system));
         late_update_schedule.add_systems(update_input_system);
 
         pre_render_schedule.add_systems(update_camera_bindings);
         pre_render_schedule.add_systems(update_model_bindings_system);
+        pre_render_schedule.add_systems(
+            billboard_system
+                .after(update_camera_bindings)
+                .before(update_model_bindings_system),
+        );
+        pre_render_schedule.add_systems(update_particle_bindings_system);
+        pre_render_schedule.add_systems(update_line_bindings_system);
+        pre_render_schedule.add_systems(update_spatial_grid_system.after(billboard_system));
 
         Self {
             world,
@@ -142,22 +252,145 @@ impl Core {
         }
     }
 
+    /// Resources holding logical state, put back to their defaults by `reset`.
+    /// GPU layouts, pipelines and platform handlers are not part of this.
+    fn insert_scene_resources(world: &mut World) {
+        world.insert_resource(Input::new());
+        world.insert_resource(Time::new());
+        world.insert_resource(Timers::new());
+        world.insert_resource(DepthPrepass::default());
+        world.insert_resource(PostProcessSettings::default());
+        world.insert_resource(BloomSettings::default());
+        world.insert_resource(SpatialGrid::default());
+        world.insert_resource(WindowTitle::default());
+    }
+
+    fn spawn_default_camera(world: &mut World) {
+        let camera_bundle = CameraBundle::new(
+            world,
+            vec3(0.0, 10.0, -10.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        );
+
+        world.spawn(camera_bundle);
+    }
+
+    /// Despawns every entity except cameras and those tagged `Persistent`,
+    /// and drops state that referred to them such as the spatial grid and pending timers
+    pub fn clear_scene(&mut self) {
+        let doomed: Vec<Entity> = self
+            .world
+            .query_filtered::<Entity, (Without<Camera>, Without<Persistent>)>()
+            .iter(&self.world)
+            .collect();
+        for entity in doomed {
+            self.world.despawn(entity);
+        }
+
+        self.world.resource_mut::<SpatialGrid>().clear();
+        self.world.insert_resource(Timers::new());
+    }
+
+    /// Returns the world to the state of a freshly created empty core: every entity is despawned,
+    /// the default camera is respawned and settings, input and time are reset.
+    /// The device, queue, pipelines and render targets are kept.
+    pub fn reset(&mut self) {
+        self.world.clear_entities();
+        Self::insert_scene_resources(&mut self.world);
+        Self::spawn_default_camera(&mut self.world);
+    }
+
     pub fn resize(&mut self, width: u32, height: u32) {
         trace!("resize");
 
+        let (width, height) = self.clamp_render_size(width, height);
+
         self.world
             .get_resource_mut::<ScreenParameters>()
             .unwrap()
             .set_size(width, height);
 
-        let render_resources = &self
-            .world
-            .get_resource::<crate::gpu_resources::render_resources::RenderResources>()
-            .unwrap();
+        self.root_renderer.set_size(&self.world, width, height);
+    }
 
-        let device = &render_resources.device;
+    /// Sets the display's DPI scale, physical pixels per logical pixel
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.world
+            .resource_mut::<ScreenParameters>()
+            .set_scale_factor(scale_factor);
+    }
 
-        self.root_renderer.set_size(device, width, height);
+    /// The display's DPI scale last set with `set_scale_factor`
+    pub fn scale_factor(&self) -> f64 {
+        self.world.resource::<ScreenParameters>().scale_factor
+    }
+
+    /// The largest width or height the render targets and surface may have
+    pub fn max_render_dimension(&self) -> u32 {
+        self.world.resource::<RenderSizeLimit>().max_dimension(
+            self.world
+                .resource::<RenderResources>()
+                .max_texture_dimension_2d,
+        )
+    }
+
+    /// Clamps a requested render size to the device limit and any user override
+    pub fn clamp_render_size(&self, width: u32, height: u32) -> (u32, u32) {
+        self.world.resource::<RenderSizeLimit>().clamp(
+            self.world
+                .resource::<RenderResources>()
+                .max_texture_dimension_2d,
+            width,
+            height,
+        )
+    }
+
+    /// Lowers the maximum render size below the device limit, or removes the override with `None`.
+    /// Takes effect on the next resize.
+    pub fn set_max_render_dimension(&mut self, max_dimension: Option<u32>) {
+        self.world.resource_mut::<RenderSizeLimit>().max_dimension = max_dimension;
+    }
+
+    /// Enables or disables the depth-only pre-pass for opaque geometry
+    pub fn set_depth_prepass(&mut self, enabled: bool) {
+        self.world.resource_mut::<DepthPrepass>().0 = enabled;
+    }
+
+    /// Sets the tonemapping operator and exposure, or turns tonemapping off
+    pub fn set_post_process_settings(&mut self, settings: PostProcessSettings) {
+        *self.world.resource_mut::<PostProcessSettings>() = settings;
+    }
+
+    /// Returns the text on the system clipboard, or `None` if there is no text or no clipboard
+    pub fn clipboard_get(&self) -> Option<String> {
+        self.world
+            .resource::<ClipboardPlatform>()
+            .handler
+            .clipboard_get()
+    }
+
+    /// Copies `text` to the system clipboard, if there is one
+    pub fn clipboard_set(&self, text: &str) {
+        self.world
+            .resource::<ClipboardPlatform>()
+            .handler
+            .clipboard_set(text);
+    }
+
+    /// Requests a new window title, applied by the windowing layer after the next update
+    pub fn set_window_title(&mut self, title: impl Into<String>) {
+        self.world.resource_mut::<WindowTitle>().pending = Some(title.into());
+    }
+
+    /// Takes the window title requested since the last call, if any
+    pub fn take_window_title(&mut self) -> Option<String> {
+        self.world.resource_mut::<WindowTitle>().pending.take()
+    }
+
+    /// Sets the bloom threshold, intensity and radius. An intensity of zero disables bloom.
+    pub fn set_bloom_settings(&mut self, settings: BloomSettings) {
+        *self.world.resource_mut::<BloomSettings>() = settings;
     }
 
     pub fn update(&mut self, delta_time: f32) {
@@ -178,9 +411,18 @@ impl Core {
             });
 
         // run the schedules
-        self.early_update_schedule.run(&mut self.world);
-        self.update_schedule.run(&mut self.world);
-        self.late_update_schedule.run(&mut self.world);
+        {
+            profile_scope!("early_update", entities = self.world.entities().len());
+            self.early_update_schedule.run(&mut self.world);
+        }
+        {
+            profile_scope!("update", entities = self.world.entities().len());
+            self.update_schedule.run(&mut self.world);
+        }
+        {
+            profile_scope!("late_update", entities = self.world.entities().len());
+            self.late_update_schedule.run(&mut self.world);
+        }
     }
 
     /// Render the current state of the World
@@ -188,10 +430,212 @@ impl Core {
     /// render the current state into the given texture view
     pub fn render(&mut self, texture_view: &wgpu::TextureView) -> CommandBuffer {
         trace!("render");
-        self.pre_render_schedule.run(&mut self.world);
+        {
+            profile_scope!("pre_render", entities = self.world.entities().len());
+            self.pre_render_schedule.run(&mut self.world);
+        }
+        profile_scope!("render");
         self.root_renderer.render(&self.world, texture_view)
     }
 
+    /// Reconstructs the world position of the geometry visible at pixel (`x`, `y`)
+    /// by reading back the depth buffer of the last rendered frame.
+    /// Returns `None` if nothing was drawn at that pixel.
+    pub fn world_position_at_pixel(&mut self, x: u32, y: u32) -> Option<Vec3> {
+        let depth = self.root_renderer.read_depth(&self.world, x, y)?;
+
+        let (camera, camera_bindings) = self
+            .world
+            .query::<(&Camera, &CameraBindings)>()
+            .get_single(&self.world)
+            .ok()?;
+
+        // anything still at the depth clear value is background
+        if camera.depth_direction().is_background(depth) {
+            return None;
+        }
+
+        let view_proj = camera_bindings.view_proj();
+
+        let (ndc_x, ndc_y) = self.pixel_to_ndc(x, y);
+        let world_position = view_proj.inverse() * vec4(ndc_x, ndc_y, depth, 1.0);
+        Some(world_position.xyz() / world_position.w)
+    }
+
+    /// Starts building a textured mesh entity drawn by the unlit diffuse renderer.
+    /// The model bindings are created when `spawn` is called on the returned builder.
+    pub fn spawn_mesh(
+        &mut self,
+        mesh_filter: BasicMeshFilter,
+        texture: &Texture,
+    ) -> MeshSpawner<'_> {
+        MeshSpawner::new(&mut self.world, mesh_filter, texture)
+    }
+
+    /// Shows or hides an entity in every sub-renderer without despawning it
+    pub fn set_visible(&mut self, entity: Entity, visible: bool) {
+        if let Some(mut entity) = self.world.get_entity_mut(entity) {
+            entity.insert(Visible(visible));
+        }
+    }
+
+    /// Returns the closest entity with a `MeshCollider` under pixel (`x`, `y`)
+    pub fn pick(&mut self, x: u32, y: u32) -> Option<Entity> {
+        let (camera, camera_bindings) = self
+            .world
+            .query::<(&Camera, &CameraBindings)>()
+            .get_single(&self.world)
+            .ok()?;
+
+        // unproject the pixel onto the near and far planes to build the ray
+        let inverse_view_proj = camera_bindings.view_proj().inverse();
+        let (near_depth, far_depth) = if camera.reversed_depth {
+            (1.0, 0.0)
+        } else {
+            (0.0, 1.0)
+        };
+        let (ndc_x, ndc_y) = self.pixel_to_ndc(x, y);
+        let near = inverse_view_proj.project_point3(vec3(ndc_x, ndc_y, near_depth));
+        let far = inverse_view_proj.project_point3(vec3(ndc_x, ndc_y, far_depth));
+        let direction = (far - near).normalize_or_zero();
+
+        // only entities whose bounds the ray passes through need the per triangle test
+        let candidates = self
+            .world
+            .resource::<SpatialGrid>()
+            .query_ray(near, direction);
+
+        let mut collider_query = self.world.query::<(&Transform, &MeshCollider)>();
+        candidates
+            .into_iter()
+            .filter_map(|entity| {
+                let (transform, collider) = collider_query.get(&self.world, entity).ok()?;
+                let model_matrix = Mat4::from_scale_rotation_translation(
+                    transform.scale,
+                    transform.rotation,
+                    transform.translation,
+                );
+                let distance = collider.intersect(near, direction, model_matrix)?;
+                Some((entity, distance))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entity, _)| entity)
+    }
+
+    /// Converts a pixel to normalized device coordinates, sampling the pixel center
+    fn pixel_to_ndc(&self, x: u32, y: u32) -> (f32, f32) {
+        let (width, height) = self.root_renderer.render_size();
+        let ndc_x = (x as f32 + 0.5) / width as f32 * 2.0 - 1.0;
+        let ndc_y = 1.0 - (y as f32 + 0.5) / height as f32 * 2.0;
+        (ndc_x, ndc_y)
+    }
+
+    /// Saves the entities in the world to a JSON scene file.
+    /// Meshes and materials can't be serialized yet and are left out, and entities with other
+    /// components the scene can't rebuild fail the save.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_scene(&mut self, path: impl AsRef<std::path::Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let json_content = Scene::from_world(&mut self.world)?.to_json_string()?;
+        std::fs::write(path, json_content)
+            .map_err(|e| format!("Failed to write scene {}: {}", path.display(), e))
+    }
+
+    /// Replaces the entities in the world with the ones in a JSON scene file.
+    /// Meshes and other entities the scene can't rebuild are kept as is.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_scene(&mut self, path: impl AsRef<std::path::Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let json_content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read scene {}: {}", path.display(), e))?;
+        Scene::from_json_str(&json_content)?.apply_to_world(&mut self.world);
+        Ok(())
+    }
+
+    /// Loads a file from disk, e.g. one dropped onto the window.
+    /// Images (png, jpg) replace the texture of the entity tagged `DropTarget`.
+    /// Models such as OBJ aren't loaded yet and are skipped like any other unsupported file.
+    /// The file is read on its own thread through an APC and applied once the read completes.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_file(&mut self, path: std::path::PathBuf) {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("png" | "jpg" | "jpeg") => {}
+            _ => {
+                log::warn!("Unsupported file type, ignoring {}", path.display());
+                return;
+            }
+        }
+
+        let apc = Apc {
+            future: Box::pin(async move {
+                let bytes = read_file(path.clone()).await;
+                let callback: ApcCallback = Box::new(move |world: &mut World| {
+                    let bytes = match bytes {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            log::error!("Failed to read {}: {}", path.display(), e);
+                            return;
+                        }
+                    };
+                    Self::apply_dropped_texture(world, &path, &bytes);
+                });
+                callback
+            }),
+        };
+
+        let sender = self.world.resource::<ApcQueue>().sender.clone();
+        self.world
+            .resource::<ApcPlatform>()
+            .platform
+            .spawn_apc(apc, sender);
+    }
+
+    /// Swaps `bytes`, decoded as an image, into the material of the entity tagged `DropTarget`
+    #[cfg(not(target_arch = "wasm32"))]
+    fn apply_dropped_texture(world: &mut World, path: &std::path::Path, bytes: &[u8]) {
+        let Some((target, blend_mode, cull_mode, alpha_cutout)) = world
+            .query_filtered::<(Entity, &UnlitDiffuseMaterial), With<DropTarget>>()
+            .iter(world)
+            .next()
+            .map(|(entity, material)| {
+                (
+                    entity,
+                    material.blend_mode,
+                    material.cull_mode,
+                    material.alpha_cutout,
+                )
+            })
+        else {
+            log::warn!("No DropTarget to apply {} to", path.display());
+            return;
+        };
+
+        let render_resources = world.resource::<RenderResources>();
+        let texture = match Texture::new_from_bytes(
+            &render_resources.device,
+            &render_resources.queue,
+            bytes,
+            None,
+        ) {
+            Ok(texture) => texture,
+            Err(e) => {
+                log::error!("Failed to decode {}: {}", path.display(), e);
+                return;
+            }
+        };
+
+        let replacement = UnlitDiffuseMaterial::new(world, &texture)
+            .with_blend_mode(blend_mode)
+            .with_cull_mode(cull_mode)
+            .with_alpha_cutout(alpha_cutout);
+        world.entity_mut(target).insert(replacement);
+    }
+
     pub fn key_down(&mut self, key_code: winit::keyboard::KeyCode) {
         self.world
             .get_resource_mut::<Input>()
@@ -236,12 +680,28 @@ impl Core {
             .release();
     }
 
+    /// Scrolls by a delta in pixels. Deltas from several events in one frame are accumulated.
     pub fn mouse_scroll(&mut self, delta_x: f64, delta_y: f64) {
         self.world
             .get_resource_mut::<Input>()
             .unwrap()
             .mouse
-            .set_scroll(delta_x, delta_y);
+            .add_scroll(delta_x, delta_y);
+    }
+
+    /// The device everything is rendered with, for creating custom GPU resources
+    pub fn device(&self) -> &Arc<wgpu::Device> {
+        &self.world.resource::<RenderResources>().device
+    }
+
+    /// The queue frames are submitted to, for uploads or one-off GPU commands
+    pub fn queue(&self) -> &Arc<wgpu::Queue> {
+        &self.world.resource::<RenderResources>().queue
+    }
+
+    /// Registers a pass run every frame after the main pass, see `CustomPass`
+    pub fn add_custom_pass(&mut self, pass: CustomPass) {
+        self.root_renderer.add_custom_pass(pass);
     }
 
     pub fn get_root_renderer(&self) -> &RootRenderer {
@@ -252,3 +712,389 @@ impl Core {
         &mut self.root_renderer
     }
 }
+
+/// Reads a file on a thread of its own, so the APC runtime awaiting it isn't blocked
+#[cfg(not(target_arch = "wasm32"))]
+async fn read_file(path: std::path::PathBuf) -> std::io::Result<Vec<u8>> {
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(std::fs::read(path));
+    });
+    receiver
+        .await
+        .unwrap_or_else(|_| Err(std::io::Error::other("file read thread stopped")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{
+        ecs::components::{
+            gpu_bindings::model_bindings::ModelBindings,
+            materials::multi_texture_material::MultiTextureMaterial,
+        },
+        gpu_resources::pipelines::{
+            blend_mode::BlendMode, fallback::catch_validation_error,
+            multi_texture_pipeline::MULTI_TEXTURE_SLOTS,
+        },
+        utils::{test_device::headless_core, texture::TextureBuilder},
+    };
+
+    const SIZE: u32 = 8;
+    /// Rows copied out of a texture must be padded to this many bytes
+    const PADDED_ROW_BYTES: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+    /// A cube, a persistent entity and a pending timer next to the default camera
+    fn populate_scene(core: &mut Core) -> (Entity, Entity, Entity) {
+        let camera = core
+            .world
+            .query_filtered::<Entity, With<Camera>>()
+            .single(&core.world);
+        let cube = primitives::create_cube(core.device(), 1.0, 1);
+        let cube = core.world.spawn((Transform::default(), cube)).id();
+        let persistent = core.world.spawn((Transform::default(), Persistent)).id();
+        core.world.resource_mut::<Timers>().after(1.0, |_| {});
+        (camera, cube, persistent)
+    }
+
+    fn renders_cleanly(core: &mut Core) -> bool {
+        let device = core.device().clone();
+        let target = TextureBuilder::new(&device)
+            .size(SIZE, SIZE)
+            .render_target(1)
+            .build()
+            .unwrap();
+        let (_, error) = catch_validation_error(&device, || {
+            core.update(0.016);
+            let commands = core.render(&target.view);
+            core.queue().submit([commands]);
+        });
+        error.is_none()
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn clear_scene_keeps_the_camera_and_persistent_entities() {
+        let mut core = headless_core(SIZE, SIZE);
+        let (camera, cube, persistent) = populate_scene(&mut core);
+
+        core.clear_scene();
+
+        assert!(core.world.get_entity(camera).is_some());
+        assert!(core.world.get_entity(persistent).is_some());
+        assert!(core.world.get_entity(cube).is_none());
+        assert_eq!(core.world.entities().len(), 2);
+        assert!(core.world.resource::<Timers>().is_empty());
+        // the pipelines and layouts are still there to draw with
+        assert!(renders_cleanly(&mut core));
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn reset_leaves_only_a_fresh_camera() {
+        let mut core = headless_core(SIZE, SIZE);
+        let (camera, cube, persistent) = populate_scene(&mut core);
+
+        core.reset();
+
+        for entity in [camera, cube, persistent] {
+            assert!(core.world.get_entity(entity).is_none());
+        }
+        let cameras = core.world.query::<&Camera>().iter(&core.world).count();
+        assert_eq!(cameras, 1);
+        assert_eq!(core.world.entities().len(), 1);
+        assert!(core.world.resource::<Timers>().is_empty());
+        assert!(renders_cleanly(&mut core));
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn headless_core_updates_and_renders_offscreen() {
+        let mut core = headless_core(64, 32);
+
+        // the camera is there, the demo cubes aren't
+        let cameras = core.world.query::<&Camera>().iter(&core.world).count();
+        let meshes = core
+            .world
+            .query::<&BasicMeshFilter>()
+            .iter(&core.world)
+            .count();
+        assert_eq!((cameras, meshes), (1, 0));
+
+        let device = core.device().clone();
+        let target = TextureBuilder::new(&device)
+            .size(64, 32)
+            .render_target(1)
+            .build()
+            .unwrap();
+        let (_, error) = catch_validation_error(&device, || {
+            core.update(0.016);
+            let commands = core.render(&target.view);
+            core.queue().submit([commands]);
+        });
+        assert!(error.is_none(), "headless frame failed: {:?}", error);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn depth_outside_the_render_target_reads_as_none() {
+        let core = headless_core(8, 4);
+        let renderer = core.get_root_renderer();
+
+        assert_eq!(renderer.read_depth(&core.world, 8, 0), None);
+        assert_eq!(renderer.read_depth(&core.world, 0, 4), None);
+        assert!(renderer.read_depth(&core.world, 7, 3).is_some());
+    }
+
+    /// A small solid-color image encoded as PNG, like one dropped onto the window
+    fn png_bytes(color: [u8; 4]) -> Vec<u8> {
+        let image = image::RgbaImage::from_pixel(4, 2, image::Rgba(color));
+        let mut bytes = Vec::new();
+        image::DynamicImage::ImageRgba8(image)
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageOutputFormat::Png,
+            )
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn dropped_png_replaces_the_drop_target_texture() {
+        let mut core = headless_core(SIZE, SIZE);
+        let texture = Texture::new_from_bytes(
+            core.device(),
+            core.queue(),
+            &png_bytes([255, 0, 0, 255]),
+            None,
+        )
+        .unwrap();
+        let material = |world: &World| {
+            UnlitDiffuseMaterial::new(world, &texture).with_blend_mode(BlendMode::Alpha)
+        };
+        let target = core.world.spawn((material(&core.world), DropTarget)).id();
+        let other = core.world.spawn(material(&core.world)).id();
+        let bind_group_of = |world: &World, entity: Entity| {
+            world
+                .get::<UnlitDiffuseMaterial>(entity)
+                .unwrap()
+                .bind_group
+                .global_id()
+        };
+        let (target_before, other_before) = (
+            bind_group_of(&core.world, target),
+            bind_group_of(&core.world, other),
+        );
+
+        let path = std::path::Path::new("dropped.png");
+        Core::apply_dropped_texture(&mut core.world, path, &png_bytes([255, 0, 0, 255]));
+
+        assert_ne!(bind_group_of(&core.world, target), target_before);
+        assert_eq!(bind_group_of(&core.world, other), other_before);
+        let replacement = core.world.get::<UnlitDiffuseMaterial>(target).unwrap();
+        assert_eq!(replacement.blend_mode, BlendMode::Alpha);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn non_image_files_are_rejected() {
+        let mut core = headless_core(SIZE, SIZE);
+        let texture = Texture::new_from_bytes(
+            core.device(),
+            core.queue(),
+            &png_bytes([255, 0, 0, 255]),
+            None,
+        )
+        .unwrap();
+        let material = UnlitDiffuseMaterial::new(&core.world, &texture);
+        let target = core.world.spawn((material, DropTarget)).id();
+        let before = core
+            .world
+            .get::<UnlitDiffuseMaterial>(target)
+            .unwrap()
+            .bind_group
+            .global_id();
+
+        // skipped by extension before anything is read
+        core.load_file(std::path::PathBuf::from("notes.txt"));
+        // an image extension on bytes that don't decode
+        let path = std::path::Path::new("notes.png");
+        Core::apply_dropped_texture(&mut core.world, path, b"not an image");
+        core.update(0.016);
+
+        let after = core
+            .world
+            .get::<UnlitDiffuseMaterial>(target)
+            .unwrap()
+            .bind_group
+            .global_id();
+        assert_eq!(after, before);
+    }
+
+    /// Updates and renders one frame into a SIZE x SIZE Rgba8Unorm target and reads it back,
+    /// with each row padded to `PADDED_ROW_BYTES`
+    fn render_frame(core: &mut Core) -> Vec<u8> {
+        let (device, queue) = (core.device().clone(), core.queue().clone());
+        let output = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let output_view = output.create_view(&Default::default());
+
+        core.update(1.0 / 60.0);
+        let commands = core.render(&output_view);
+        queue.submit([commands]);
+
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (PADDED_ROW_BYTES * SIZE) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.copy_texture_to_buffer(
+            output.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(PADDED_ROW_BYTES),
+                    rows_per_image: None,
+                },
+            },
+            output.size(),
+        );
+        queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        device.poll(wgpu::Maintain::Wait);
+        slice.get_mapped_range().to_vec()
+    }
+
+    /// Byte offset of the center pixel in a frame read back by `render_frame`
+    const CENTER: usize = ((SIZE / 2) * PADDED_ROW_BYTES + (SIZE / 2) * 4) as usize;
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn custom_pass_runs_every_frame() {
+        let mut core = headless_core(SIZE, SIZE);
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let pass_runs = runs.clone();
+        core.add_custom_pass(Box::new(
+            move |_world: &World,
+                  encoder: &mut wgpu::CommandEncoder,
+                  hdr_view: &wgpu::TextureView| {
+                pass_runs.fetch_add(1, Ordering::Relaxed);
+                // the headless scene is empty, so only this pass can light up the frame
+                encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Custom Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: hdr_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+            },
+        ));
+
+        let mut texels = Vec::new();
+        for frame in 1..=3 {
+            texels = render_frame(&mut core);
+            assert_eq!(runs.load(Ordering::Relaxed), frame);
+        }
+
+        // the white the pass cleared to survives tonemapping
+        assert!(
+            texels[CENTER] > 128,
+            "center pixel is {} after the custom pass",
+            texels[CENTER]
+        );
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn depth_prepass_renders_the_same_frame() {
+        let mut core = headless_core(SIZE, SIZE);
+        let (device, queue) = (core.device().clone(), core.queue().clone());
+
+        // a green cube between the default camera and a red one, both covering the center
+        let red =
+            Texture::new_from_bytes(&device, &queue, &png_bytes([255, 0, 0, 255]), None).unwrap();
+        let green =
+            Texture::new_from_bytes(&device, &queue, &png_bytes([0, 255, 0, 255]), None).unwrap();
+        core.spawn_mesh(primitives::create_cube(&device, 4.0, 1), &red)
+            .spawn();
+        core.spawn_mesh(primitives::create_cube(&device, 2.0, 1), &green)
+            .transform(Transform::from_translation(vec3(0.0, 5.0, -5.0)))
+            .spawn();
+
+        let without_prepass = render_frame(&mut core);
+        core.set_depth_prepass(true);
+        let with_prepass = render_frame(&mut core);
+
+        // the near cube wins the depth test either way. Had the main pass cleared the
+        // pre-pass depth, the equal test would reject every opaque fragment and leave it black
+        let center = &without_prepass[CENTER..CENTER + 4];
+        assert!(center[1] > 128 && center[0] < 64, "center is {:?}", center);
+        assert_eq!(with_prepass, without_prepass);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn multi_texture_material_binds_every_slot() {
+        let mut core = headless_core(SIZE, SIZE);
+        let (device, queue) = (core.device().clone(), core.queue().clone());
+        let solid = |color: [u8; 4]| {
+            Texture::new_from_bytes(&device, &queue, &png_bytes(color), None).unwrap()
+        };
+        let (diffuse, detail) = (solid([255, 255, 255, 255]), solid([0, 255, 0, 255]));
+
+        // the mask slot decides whether the green detail slot tints the white diffuse
+        let mut center_with_mask = |mask: &Texture| {
+            let textures: [&Texture; MULTI_TEXTURE_SLOTS] = [&diffuse, &detail, mask];
+            let material = MultiTextureMaterial::new(&core.world, &textures);
+            let mut transform = Transform::from_translation(Vec3::ZERO);
+            let model_bindings = ModelBindings::new(&mut core.world, &device, &mut transform);
+            let mesh_filter = primitives::create_cube(&device, 4.0, 1);
+            let cube = core
+                .world
+                .spawn((transform, mesh_filter, model_bindings, material))
+                .id();
+
+            let (texels, error) = catch_validation_error(&device, || render_frame(&mut core));
+            assert!(error.is_none(), "multi texture frame failed: {:?}", error);
+            core.world.despawn(cube);
+            texels[CENTER..CENTER + 4].to_vec()
+        };
+
+        let masked = center_with_mask(&solid([255, 255, 255, 255]));
+        assert!(masked[1] > 128 && masked[0] < 64, "masked is {:?}", masked);
+        let unmasked = center_with_mask(&solid([0, 0, 0, 255]));
+        assert!(
+            unmasked[0] > 128 && unmasked[1] > 128,
+            "unmasked is {:?}",
+            unmasked
+        );
+    }
+}
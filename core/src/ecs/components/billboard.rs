@@ -0,0 +1,17 @@
+use bevy_ecs::component::Component;
+
+/// How a `Billboard` turns to face the camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BillboardMode {
+    /// Faces the camera on every axis
+    #[default]
+    Spherical,
+    /// Only yaws around the world Y axis, staying upright (e.g. trees, signposts)
+    Cylindrical,
+}
+
+/// Rotates the entity's `Transform` every frame so its forward axis points at the camera
+#[derive(Component, Default)]
+pub struct Billboard {
+    pub mode: BillboardMode,
+}
@@ -1,17 +1,34 @@
 use bevy_ecs::prelude::*;
 use glam::Mat4;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ecs::resources::screen_parameters::ScreenParameters,
+    gpu_resources::pipelines::depth_direction::DepthDirection, utils::scissor::ScissorRect,
+};
 
 use super::transform::Transform;
 
 /// Enum defining the projection type
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ProjectionType {
     Perspective,
     Orthographic,
 }
 
+/// Where world space (0, 0) sits on screen for a pixel perfect orthographic camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PixelOrigin {
+    /// Origin in the middle of the screen with +Y up
+    Center,
+    /// Origin at the top left corner with +Y down, like window and mouse coordinates
+    TopLeft,
+    /// Origin at the bottom left corner with +Y up
+    BottomLeft,
+}
+
 /// A camera component supporting both perspective and orthographic projections
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Camera {
     /// Type of projection to use
     pub projection_type: ProjectionType,
@@ -36,7 +53,17 @@ pub struct Camera {
     /// The size (height) of the orthographic view
     pub ortho_size: f32,
 
+    /// When set, the orthographic view spans exactly the screen's pixels so one world unit
+    /// is one pixel. `ortho_size` is kept equal to the screen height on resize.
+    #[serde(default)]
+    pub pixel_origin: Option<PixelOrigin>,
+
+    /// Clips this camera's rendering to a region of the target, e.g. for a minimap border
+    #[serde(default)]
+    pub scissor: Option<ScissorRect>,
+
     // Cached projection matrix
+    #[serde(skip)]
     projection_matrix: Option<Mat4>,
 }
 
@@ -51,6 +78,8 @@ impl Default for Camera {
             infinite_projection: false,
             reversed_depth: false,
             ortho_size: 10.0,
+            pixel_origin: None,
+            scissor: None,
             projection_matrix: None,
         }
     }
@@ -68,6 +97,8 @@ impl Camera {
             infinite_projection: false,
             reversed_depth: false,
             ortho_size: 10.0, // Default, not used in perspective
+            pixel_origin: None,
+            scissor: None,
             projection_matrix: None,
         }
     }
@@ -83,10 +114,36 @@ impl Camera {
             infinite_projection: false,
             reversed_depth: false,
             ortho_size: size,
+            pixel_origin: None,
+            scissor: None,
             projection_matrix: None,
         }
     }
 
+    /// Creates an orthographic camera where one world unit is one pixel, for 2D and UI overlays.
+    /// Place it at the world origin; `origin` picks where (0, 0) lands on screen.
+    pub fn new_pixel_perfect(screen_parameters: &ScreenParameters, origin: PixelOrigin) -> Self {
+        let width = screen_parameters.width.max(1) as f32;
+        let height = screen_parameters.height.max(1) as f32;
+
+        // the near plane sits behind the camera so sprites at z = 0 aren't clipped
+        Self {
+            pixel_origin: Some(origin),
+            ..Self::new_orthographic(height, width / height, -1000.0, 1000.0)
+        }
+    }
+
+    /// Keeps a pixel perfect camera spanning the screen after a resize
+    pub fn fit_to_screen(&mut self, width: u32, height: u32) {
+        let height = height.max(1) as f32;
+        let aspect_ratio = width.max(1) as f32 / height;
+        if self.ortho_size != height || self.aspect_ratio != aspect_ratio {
+            self.ortho_size = height;
+            self.aspect_ratio = aspect_ratio;
+            self.projection_matrix = None;
+        }
+    }
+
     /// Sets the aspect ratio and marks the projection matrix as dirty
     pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
         self.aspect_ratio = aspect_ratio;
@@ -128,66 +185,190 @@ impl Camera {
         if let Some(projection_matrix) = self.projection_matrix {
             projection_matrix
         } else {
-            let projection_matrix = match self.projection_type {
-                ProjectionType::Perspective => {
-                    match (self.infinite_projection, self.reversed_depth) {
-                        (true, false) => {
-                            Mat4::perspective_infinite_lh(self.fov, self.aspect_ratio, self.near)
-                        }
-
-                        (true, true) => Mat4::perspective_infinite_reverse_lh(
-                            self.fov,
-                            self.aspect_ratio,
-                            self.near,
-                        ),
-                        (false, false) => {
-                            Mat4::perspective_lh(self.fov, self.aspect_ratio, self.near, self.far)
-                        }
-                        (false, true) => {
-                            Mat4::perspective_lh(self.fov, self.aspect_ratio, self.far, self.near)
-                        }
-                    }
+            let projection_matrix = self.projection_matrix_for(self.projection_type);
+            self.projection_matrix = Some(projection_matrix);
+            projection_matrix
+        }
+    }
+
+    /// The projection matrix this camera would have with the given projection type
+    pub fn projection_matrix_for(&self, projection_type: ProjectionType) -> Mat4 {
+        match projection_type {
+            ProjectionType::Perspective => match (self.infinite_projection, self.reversed_depth) {
+                (true, false) => {
+                    Mat4::perspective_infinite_lh(self.fov, self.aspect_ratio, self.near)
+                }
+
+                (true, true) => {
+                    Mat4::perspective_infinite_reverse_lh(self.fov, self.aspect_ratio, self.near)
+                }
+                (false, false) => {
+                    Mat4::perspective_lh(self.fov, self.aspect_ratio, self.near, self.far)
                 }
-                ProjectionType::Orthographic => {
-                    // Calculate orthographic dimensions
-                    let height = self.ortho_size;
-                    let width = height * self.aspect_ratio;
-
-                    // Create orthographic projection matrix
-                    if self.reversed_depth {
-                        // Reversed depth for orthographic
-                        Mat4::orthographic_lh(
-                            -width / 2.0,
-                            width / 2.0,
-                            -height / 2.0,
-                            height / 2.0,
-                            self.far,
-                            self.near, // Swap near and far for reversed depth
-                        )
-                    } else {
-                        Mat4::orthographic_lh(
-                            -width / 2.0,
-                            width / 2.0,
-                            -height / 2.0,
-                            height / 2.0,
-                            self.near,
-                            self.far,
-                        )
+                (false, true) => {
+                    Mat4::perspective_lh(self.fov, self.aspect_ratio, self.far, self.near)
+                }
+            },
+            ProjectionType::Orthographic => {
+                // Calculate orthographic dimensions
+                let height = self.ortho_size;
+                let width = height * self.aspect_ratio;
+
+                // left, right, bottom and top of the view volume
+                let (left, right, bottom, top) = match self.pixel_origin {
+                    None | Some(PixelOrigin::Center) => {
+                        (-width / 2.0, width / 2.0, -height / 2.0, height / 2.0)
                     }
+                    Some(PixelOrigin::TopLeft) => (0.0, width, height, 0.0),
+                    Some(PixelOrigin::BottomLeft) => (0.0, width, 0.0, height),
+                };
+
+                // Create orthographic projection matrix
+                if self.reversed_depth {
+                    // Reversed depth for orthographic
+                    Mat4::orthographic_lh(
+                        left, right, bottom, top, self.far,
+                        self.near, // Swap near and far for reversed depth
+                    )
+                } else {
+                    Mat4::orthographic_lh(left, right, bottom, top, self.near, self.far)
                 }
-            };
-            self.projection_matrix = Some(projection_matrix);
-            projection_matrix
+            }
         }
     }
 
+    /// Blends the current projection towards `target` by `t` (0..1), interpolating the matrices.
+    /// Used for smooth orthographic/perspective transitions; the blend lasts until a parameter
+    /// changes, so it has to be applied every frame while transitioning.
+    pub fn set_blended_projection(&mut self, target: ProjectionType, t: f32) {
+        let from = self.projection_matrix_for(self.projection_type);
+        let to = self.projection_matrix_for(target);
+        let t = t.clamp(0.0, 1.0);
+        self.projection_matrix = Some(from * (1.0 - t) + to * t);
+    }
+
+    /// Vertical field of view at which a subject `subject_size` tall fills the view
+    /// from `distance` away
+    pub fn fov_for_distance(subject_size: f32, distance: f32) -> f32 {
+        2.0 * (subject_size / (2.0 * distance.max(f32::EPSILON))).atan()
+    }
+
+    /// Distance at which a subject `subject_size` tall fills a view with the vertical `fov`
+    pub fn distance_for_fov(subject_size: f32, fov: f32) -> f32 {
+        subject_size / (2.0 * (fov / 2.0).tan())
+    }
+
+    /// Height of the perspective view volume `distance` in front of the camera.
+    /// Using it as `ortho_size` makes an orthographic switch keep things at that depth in place.
+    pub fn ortho_size_at_distance(&self, distance: f32) -> f32 {
+        2.0 * distance * (self.fov / 2.0).tan()
+    }
+
     /// Creates a combined view-projection matrix
     pub fn view_projection_matrix(&mut self, transform: &mut Transform) -> Mat4 {
         let view = transform.get_trs_matrix();
         self.get_projection_matrix() * view
     }
 
+    /// Sets the region of the render target this camera draws into, or `None` for all of it
+    pub fn set_scissor(&mut self, scissor: Option<ScissorRect>) {
+        self.scissor = scissor;
+    }
+
     pub fn needs_update(&self) -> bool {
         self.projection_matrix.is_none()
     }
+
+    /// Which end of the depth range the projection maps the near plane to
+    pub fn depth_direction(&self) -> DepthDirection {
+        DepthDirection::from_reversed(self.reversed_depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::*;
+
+    /// Whether a fragment at `depth` passes `compare` against `stored`
+    fn passes(compare: wgpu::CompareFunction, depth: f32, stored: f32) -> bool {
+        match compare {
+            wgpu::CompareFunction::Less => depth < stored,
+            wgpu::CompareFunction::Greater => depth > stored,
+            _ => unreachable!("cameras only test with Less or Greater"),
+        }
+    }
+
+    #[test]
+    fn near_geometry_occludes_far_geometry_for_every_depth_mode() {
+        for (infinite_projection, reversed_depth) in
+            [(false, false), (false, true), (true, false), (true, true)]
+        {
+            let camera = Camera {
+                infinite_projection,
+                reversed_depth,
+                ..Default::default()
+            };
+            let projection = camera.projection_matrix_for(ProjectionType::Perspective);
+            let near_depth = projection.project_point3(Vec3::new(0.0, 0.0, 1.0)).z;
+            let far_depth = projection.project_point3(Vec3::new(0.0, 0.0, 100.0)).z;
+
+            let direction = camera.depth_direction();
+            let compare = direction.compare();
+            let label = (infinite_projection, reversed_depth);
+            assert!(
+                passes(compare, far_depth, direction.clear_value()),
+                "{:?}",
+                label
+            );
+            assert!(passes(compare, near_depth, far_depth), "{:?}", label);
+            assert!(!passes(compare, far_depth, near_depth), "{:?}", label);
+        }
+    }
+
+    fn project_pixel(camera: &mut Camera, x: f32, y: f32) -> Vec3 {
+        camera
+            .get_projection_matrix()
+            .project_point3(Vec3::new(x, y, 0.0))
+    }
+
+    #[test]
+    fn pixel_perfect_camera_maps_pixels_to_the_screen_corners() {
+        let screen_parameters = ScreenParameters::new(800, 600);
+
+        let mut top_left = Camera::new_pixel_perfect(&screen_parameters, PixelOrigin::TopLeft);
+        assert!(
+            project_pixel(&mut top_left, 0.0, 0.0).abs_diff_eq(Vec3::new(-1.0, 1.0, 0.5), 1e-6)
+        );
+        assert!(
+            project_pixel(&mut top_left, 800.0, 600.0).abs_diff_eq(Vec3::new(1.0, -1.0, 0.5), 1e-6)
+        );
+
+        let mut bottom_left =
+            Camera::new_pixel_perfect(&screen_parameters, PixelOrigin::BottomLeft);
+        assert!(
+            project_pixel(&mut bottom_left, 0.0, 0.0).abs_diff_eq(Vec3::new(-1.0, -1.0, 0.5), 1e-6)
+        );
+
+        let mut center = Camera::new_pixel_perfect(&screen_parameters, PixelOrigin::Center);
+        assert!(
+            project_pixel(&mut center, 400.0, 300.0).abs_diff_eq(Vec3::new(1.0, 1.0, 0.5), 1e-6)
+        );
+    }
+
+    #[test]
+    fn pixel_perfect_camera_keeps_one_unit_per_pixel_after_resize() {
+        let screen_parameters = ScreenParameters::new(800, 600);
+        let mut camera = Camera::new_pixel_perfect(&screen_parameters, PixelOrigin::TopLeft);
+        camera.get_projection_matrix();
+
+        camera.fit_to_screen(1024, 768);
+        assert!(camera.needs_update());
+        let one_pixel = project_pixel(&mut camera, 1.0, 0.0) - project_pixel(&mut camera, 0.0, 0.0);
+        assert!((one_pixel.x - 2.0 / 1024.0).abs() < 1e-6);
+        assert!(
+            project_pixel(&mut camera, 1024.0, 768.0).abs_diff_eq(Vec3::new(1.0, -1.0, 0.5), 1e-6)
+        );
+    }
 }
@@ -0,0 +1,56 @@
+use bevy_ecs::component::Component;
+use glam::{Quat, Vec3};
+
+/// Shakes the entity's `Transform`, usually the camera's, for impact feedback.
+/// The shake strength is `trauma` squared, so small hits barely shake while big ones are violent.
+/// The offset is removed again before the next update, so controllers move the unshaken transform.
+#[derive(Component, Clone, Copy)]
+pub struct CameraShake {
+    /// Current shake amount from 0 to 1, add to it with `add_trauma`
+    pub trauma: f32,
+    /// How much trauma is lost per second
+    pub decay: f32,
+    /// Largest positional offset at full trauma, in world units
+    pub max_offset: Vec3,
+    /// Largest yaw, pitch and roll at full trauma, in radians
+    pub max_rotation: Vec3,
+    /// How fast the noise driving the shake is sampled
+    pub frequency: f32,
+    /// The offset applied this frame, undone before the next update
+    pub(crate) applied_translation: Vec3,
+    pub(crate) applied_rotation: Quat,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            decay: 1.0,
+            max_offset: Vec3::splat(0.5),
+            max_rotation: Vec3::new(0.05, 0.05, 0.1),
+            frequency: 15.0,
+            applied_translation: Vec3::ZERO,
+            applied_rotation: Quat::IDENTITY,
+        }
+    }
+}
+
+impl CameraShake {
+    pub fn new(trauma: f32, decay: f32) -> Self {
+        Self {
+            trauma: trauma.clamp(0.0, 1.0),
+            decay,
+            ..Default::default()
+        }
+    }
+
+    /// Adds trauma from a new impact, capped at 1
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// How strongly to shake this frame
+    pub fn shake(&self) -> f32 {
+        self.trauma * self.trauma
+    }
+}
@@ -0,0 +1,48 @@
+use bevy_ecs::component::Component;
+use glam::Vec3;
+
+use crate::ecs::components::{camera::Camera, tween::Tweenable};
+
+/// Keeps a subject the same size on screen while the camera moves towards or away from it,
+/// by narrowing or widening the field of view to match. The camera keeps its orientation and
+/// is placed `distance` behind `subject` along its forward axis.
+/// Animate it with a `Tween<DollyZoom>` between two distances for the classic effect.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct DollyZoom {
+    /// World space point the camera keeps framed
+    pub subject: Vec3,
+    /// Height of the subject in world units, which always spans the full view height
+    pub subject_size: f32,
+    /// Distance from the camera to the subject
+    pub distance: f32,
+}
+
+impl DollyZoom {
+    pub fn new(subject: Vec3, subject_size: f32, distance: f32) -> Self {
+        Self {
+            subject,
+            subject_size,
+            distance,
+        }
+    }
+
+    /// Copy of this dolly zoom at another distance, e.g. the end of a tween
+    pub fn at_distance(&self, distance: f32) -> Self {
+        Self { distance, ..*self }
+    }
+
+    /// Field of view that keeps the subject's size at the current distance
+    pub fn fov(&self) -> f32 {
+        Camera::fov_for_distance(self.subject_size, self.distance)
+    }
+}
+
+impl Tweenable for DollyZoom {
+    fn interpolate(start: &Self, end: &Self, t: f32) -> Self {
+        Self {
+            subject: start.subject.lerp(end.subject, t),
+            subject_size: start.subject_size + (end.subject_size - start.subject_size) * t,
+            distance: start.distance + (end.distance - start.distance) * t,
+        }
+    }
+}
@@ -0,0 +1,6 @@
+use bevy_ecs::component::Component;
+
+/// Marks the entity whose texture is replaced by an image dropped onto the window,
+/// see `Core::load_file`. If several are tagged only the first is changed.
+#[derive(Component, Default)]
+pub struct DropTarget;
@@ -0,0 +1,76 @@
+use bevy_ecs::component::Component;
+use glam::{EulerRot, Quat};
+use serde::{Deserialize, Serialize};
+use winit::event::MouseButton;
+
+use super::transform::Transform;
+
+/// First person controls for the entity's `Transform`, usually the camera's.
+/// WASD moves along the view, E and Q move up and down and the mouse turns it.
+/// While disabled it reads no input at all, so other controls can use the same keys.
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
+pub struct FlyCameraController {
+    pub enabled: bool,
+    /// World units moved per second
+    pub move_speed: f32,
+    /// Radians turned per pixel the mouse moves
+    pub look_speed: f32,
+    /// Button to hold for mouse look, `None` looks whenever the mouse moves
+    pub look_button: Option<MouseButton>,
+    /// Angle around the world Y axis in radians, 0 looks down +Z
+    pub yaw: f32,
+    /// Angle below the horizon in radians, kept within +-89 degrees
+    pub pitch: f32,
+}
+
+impl Default for FlyCameraController {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            move_speed: 5.0,
+            look_speed: 0.003,
+            look_button: Some(MouseButton::Right),
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
+}
+
+impl FlyCameraController {
+    pub fn new(move_speed: f32) -> Self {
+        Self {
+            move_speed,
+            ..Default::default()
+        }
+    }
+
+    /// Starts looking the way `transform` already faces
+    pub fn from_transform(transform: &Transform) -> Self {
+        let forward = transform.forward();
+        let mut controller = Self {
+            yaw: forward.x.atan2(forward.z),
+            ..Default::default()
+        };
+        controller.look(0.0, (-forward.y).clamp(-1.0, 1.0).asin());
+        controller
+    }
+
+    pub fn with_look_button(mut self, look_button: Option<MouseButton>) -> Self {
+        self.look_button = look_button;
+        self
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn look(&mut self, delta_yaw: f32, delta_pitch: f32) {
+        let pitch_limit = 89.0_f32.to_radians();
+        self.yaw += delta_yaw;
+        self.pitch = (self.pitch + delta_pitch).clamp(-pitch_limit, pitch_limit);
+    }
+
+    pub fn rotation(&self) -> Quat {
+        Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0)
+    }
+}
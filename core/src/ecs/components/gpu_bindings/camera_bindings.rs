@@ -1,9 +1,13 @@
 use bevy_ecs::{component::Component, world::World};
+use glam::{Vec2, Vec3};
 use wgpu::Queue;
 use wgpu::util::DeviceExt;
 
 use crate::{
-    ecs::components::{camera::Camera, transform::Transform},
+    ecs::{
+        components::{camera::Camera, transform::Transform},
+        resources::screen_parameters::ScreenParameters,
+    },
     gpu_resources::{
         layouts::camera_uniform_layout::CameraUniformLayout,
         render_resources::RenderResources,
@@ -23,7 +27,12 @@ impl CameraBindings {
         let camera_bind_group_layout = world.get_resource::<CameraUniformLayout>().unwrap();
         let device = &world.get_resource::<RenderResources>().unwrap().device;
 
-        let gpu_camera = GpuCamera::from_camera_and_transform(camera, transform);
+        let viewport_size = world
+            .get_resource::<ScreenParameters>()
+            .unwrap()
+            .size_vec2();
+
+        let gpu_camera = GpuCamera::from_camera_and_transform(camera, transform, viewport_size);
 
         let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Camera Buffer"),
@@ -40,8 +49,25 @@ impl CameraBindings {
         }
     }
 
-    pub fn update(&mut self, queue: &Queue, camera: &mut Camera, transform: &mut Transform) {
-        if self.gpu_camera.update_view_proj(camera, transform) {
+    /// The view-projection matrix last uploaded to the GPU
+    pub fn view_proj(&self) -> glam::Mat4 {
+        self.gpu_camera.view_proj
+    }
+
+    /// World space position of the camera last uploaded to the GPU
+    pub fn position(&self) -> Vec3 {
+        self.gpu_camera.view.inverse().w_axis.truncate()
+    }
+
+    pub fn update(
+        &mut self,
+        queue: &Queue,
+        camera: &mut Camera,
+        transform: &mut Transform,
+        viewport_size: Vec2,
+    ) {
+        let viewport_changed = self.gpu_camera.update_viewport(viewport_size);
+        if self.gpu_camera.update_view_proj(camera, transform) || viewport_changed {
             queue.write_buffer(&self.buffer, 0, &self.gpu_camera.as_buffer());
         }
     }
@@ -0,0 +1,73 @@
+use bevy_ecs::component::Component;
+
+use crate::{
+    ecs::components::lines::Lines,
+    gpu_resources::types::line_instance::LineInstance,
+    utils::buffer::{Buffer, BufferBuilder},
+};
+
+/// GPU instance buffer for a `Lines` component
+#[derive(Component, Debug)]
+pub struct LineBindings {
+    instance_buffer: Buffer<LineInstance>,
+    instances: Vec<LineInstance>,
+    instance_count: u32,
+}
+
+impl LineBindings {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, lines: &Lines) -> Self {
+        let mut bindings = Self {
+            instance_buffer: Self::create_instance_buffer(device, lines.len()),
+            instances: Vec::with_capacity(lines.len()),
+            instance_count: 0,
+        };
+        bindings.update(device, queue, lines);
+        bindings
+    }
+
+    fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> Buffer<LineInstance> {
+        BufferBuilder::new(device)
+            .size(capacity.max(1))
+            .usage(wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST)
+            .label("Line Instance Buffer")
+            .build()
+            .expect("Failed to create line instance buffer")
+    }
+
+    /// Uploads the lines, growing the buffer if needed
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, lines: &Lines) {
+        self.instances.clear();
+        self.instances.extend(lines.iter().map(|line| LineInstance {
+            start_width: line.start.extend(line.width),
+            end_feather: line.end.extend(lines.feather),
+            color: line.color,
+        }));
+
+        if self.instances.len() > self.instance_buffer.length {
+            self.instance_buffer =
+                Self::create_instance_buffer(device, self.instances.len().next_power_of_two());
+        }
+
+        if !self.instances.is_empty() {
+            self.instance_buffer.update_all(queue, &self.instances);
+        }
+        self.instance_count = self.instances.len() as u32;
+    }
+
+    pub fn instance_count(&self) -> u32 {
+        self.instance_count
+    }
+
+    pub fn draw<'w, 'a>(&'w self, render_pass: &mut wgpu::RenderPass<'a>)
+    where
+        'w: 'a,
+    {
+        if self.instance_count == 0 {
+            return;
+        }
+
+        // Quad corners are generated from the vertex index, so only instances are bound
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice());
+        render_pass.draw(0..6, 0..self.instance_count);
+    }
+}
@@ -1,2 +1,4 @@
 pub mod camera_bindings;
+pub mod line_bindings;
 pub mod model_bindings;
+pub mod particle_bindings;
@@ -1,9 +1,9 @@
 use bevy_ecs::component::Component;
-use bevy_ecs::world::World;
-use wgpu::Queue;
-use wgpu::util::DeviceExt;
+use bevy_ecs::world::{Mut, World};
+use glam::Vec3;
 
 use crate::ecs::components::transform::Transform;
+use crate::ecs::resources::model_uniform_arena::{ModelSlot, ModelUniformArena};
 
 use crate::gpu_resources::{
     layouts::model_uniform_layout::ModelUniformLayout, types::gpu_model::GpuModel,
@@ -11,36 +11,42 @@ use crate::gpu_resources::{
 
 use crate::gpu_resources::types::gpu_type_macros::GpuUniformType;
 
+/// An entity's slot in the shared `ModelUniformArena`. Bind the arena's bind group with
+/// `offset()` as the dynamic offset to draw with this model.
 #[derive(Component, Debug)]
 pub struct ModelBindings {
-    pub bind_group: wgpu::BindGroup,
-    buffer: wgpu::Buffer,
+    slot: ModelSlot,
     gpu_model: GpuModel,
 }
 
 impl ModelBindings {
-    pub fn new(world: &World, device: &wgpu::Device, transform: &mut Transform) -> Self {
-        let model_bind_group_layout = world.get_resource::<ModelUniformLayout>().unwrap();
+    pub fn new(world: &mut World, device: &wgpu::Device, transform: &mut Transform) -> Self {
         let gpu_model = GpuModel::from_transform(transform);
 
-        let model_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Model Buffer"),
-            contents: &gpu_model.as_buffer(),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
+        world.resource_scope(|world, mut arena: Mut<ModelUniformArena>| {
+            let layout = world.resource::<ModelUniformLayout>();
+            let slot = arena.allocate(device, layout);
+            arena.write(&slot, &gpu_model.as_buffer());
 
-        let model_bind_group = model_bind_group_layout.create_bind_group(device, &model_buffer);
+            Self { slot, gpu_model }
+        })
+    }
 
-        Self {
-            bind_group: model_bind_group,
-            buffer: model_buffer,
-            gpu_model,
-        }
+    /// Dynamic offset of this model's uniform within the arena buffer
+    pub fn offset(&self) -> u32 {
+        self.slot.offset()
+    }
+
+    /// World space position last uploaded to the GPU
+    pub fn position(&self) -> Vec3 {
+        self.gpu_model.model.w_axis.truncate()
     }
 
-    pub fn update(&mut self, queue: &Queue, transform: &mut Transform) {
+    /// Stores the new model matrix in the arena if the transform changed.
+    /// It reaches the GPU on the arena's next `flush`.
+    pub fn update(&mut self, arena: &mut ModelUniformArena, transform: &mut Transform) {
         if self.gpu_model.update_model(transform) {
-            queue.write_buffer(&self.buffer, 0, &self.gpu_model.as_buffer());
+            arena.write(&self.slot, &self.gpu_model.as_buffer());
         }
     }
 }
@@ -0,0 +1,75 @@
+use bevy_ecs::component::Component;
+
+use crate::{
+    ecs::components::particle_emitter::ParticleEmitter,
+    gpu_resources::types::particle_instance::ParticleInstance,
+    utils::buffer::{Buffer, BufferBuilder},
+};
+
+/// GPU instance buffer for a `ParticleEmitter`
+#[derive(Component, Debug)]
+pub struct ParticleBindings {
+    instance_buffer: Buffer<ParticleInstance>,
+    instances: Vec<ParticleInstance>,
+    instance_count: u32,
+}
+
+impl ParticleBindings {
+    pub fn new(device: &wgpu::Device, emitter: &ParticleEmitter) -> Self {
+        Self {
+            instance_buffer: Self::create_instance_buffer(device, emitter.capacity()),
+            instances: Vec::with_capacity(emitter.capacity()),
+            instance_count: 0,
+        }
+    }
+
+    fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> Buffer<ParticleInstance> {
+        BufferBuilder::new(device)
+            .size(capacity.max(1))
+            .usage(wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST)
+            .label("Particle Instance Buffer")
+            .build()
+            .expect("Failed to create particle instance buffer")
+    }
+
+    /// Uploads the live particles of the emitter, growing the buffer if needed
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        emitter: &ParticleEmitter,
+    ) {
+        self.instances.clear();
+        self.instances
+            .extend(emitter.live_particles().map(|particle| ParticleInstance {
+                position_size: particle.position.extend(emitter.size_at(particle)),
+                color: emitter.color_at(particle),
+            }));
+
+        if self.instances.len() > self.instance_buffer.length {
+            self.instance_buffer = Self::create_instance_buffer(device, emitter.capacity());
+        }
+
+        if !self.instances.is_empty() {
+            self.instance_buffer.update_all(queue, &self.instances);
+        }
+        self.instance_count = self.instances.len() as u32;
+    }
+
+    pub fn instance_count(&self) -> u32 {
+        self.instance_count
+    }
+
+    pub fn draw<'w, 'a>(&'w self, render_pass: &mut wgpu::RenderPass<'a>)
+    where
+        'w: 'a,
+    {
+        if self.instance_count == 0 {
+            return;
+        }
+
+        // Quad corners are generated from the vertex index, so only instances are bound
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice());
+        render_pass.draw(0..6, 0..self.instance_count);
+    }
+}
@@ -0,0 +1,92 @@
+use bevy_ecs::component::Component;
+use glam::{Vec3, Vec4};
+
+/// Default width of the antialiased edge in pixels
+pub const DEFAULT_LINE_FEATHER: f32 = 1.0;
+
+/// A single world space line segment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Line {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub color: Vec4,
+    /// Width in pixels, independent of the distance to the camera
+    pub width: f32,
+}
+
+impl Line {
+    pub fn new(start: Vec3, end: Vec3, color: Vec4, width: f32) -> Self {
+        Self {
+            start,
+            end,
+            color,
+            width,
+        }
+    }
+}
+
+/// A set of world space lines drawn as screen space quads.
+/// Pair with `LineBindings` to upload the lines to the GPU.
+#[derive(Component, Debug, Clone)]
+pub struct Lines {
+    lines: Vec<Line>,
+    /// Width of the alpha falloff at the edges in pixels, 0 draws hard edges
+    pub feather: f32,
+}
+
+impl Default for Lines {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lines {
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            feather: DEFAULT_LINE_FEATHER,
+        }
+    }
+
+    /// Turns the soft edges on or off
+    pub fn with_antialiasing(mut self, antialiased: bool) -> Self {
+        self.feather = if antialiased {
+            DEFAULT_LINE_FEATHER
+        } else {
+            0.0
+        };
+        self
+    }
+
+    pub fn push(&mut self, line: Line) {
+        self.lines.push(line);
+    }
+
+    /// Adds a segment from `start` to `end`
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Vec4, width: f32) {
+        self.push(Line::new(start, end, color, width));
+    }
+
+    /// Adds a segment between each pair of consecutive points
+    pub fn polyline(&mut self, points: &[Vec3], color: Vec4, width: f32) {
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1], color, width);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Line> {
+        self.lines.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
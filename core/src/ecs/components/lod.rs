@@ -0,0 +1,118 @@
+use bevy_ecs::component::Component;
+
+use super::mesh_filter::BasicMeshFilter;
+
+/// A coarser mesh used from `min_distance` away from the camera
+pub struct LodLevel {
+    pub min_distance: f32,
+    /// `None` while this level is active and its mesh is on the entity
+    mesh: Option<BasicMeshFilter>,
+}
+
+/// Swaps the entity's `BasicMeshFilter` for simpler meshes as it moves away from the camera.
+/// The mesh the entity spawns with is level 0; coarser levels are added with `with_level`.
+/// Sub-renderers keep drawing the entity's `BasicMeshFilter` and don't need to know about LOD.
+#[derive(Component)]
+pub struct Lod {
+    levels: Vec<LodLevel>,
+    active: usize,
+    /// How far past a threshold the camera has to move before the level changes,
+    /// so objects sitting on a boundary don't flicker between meshes
+    pub hysteresis: f32,
+}
+
+impl Lod {
+    pub fn new(hysteresis: f32) -> Self {
+        Self {
+            levels: vec![LodLevel {
+                min_distance: 0.0,
+                mesh: None,
+            }],
+            active: 0,
+            hysteresis,
+        }
+    }
+
+    /// Adds a coarser level. Levels must be added in order of increasing distance.
+    pub fn with_level(mut self, min_distance: f32, mesh: BasicMeshFilter) -> Self {
+        self.levels.push(LodLevel {
+            min_distance,
+            mesh: Some(mesh),
+        });
+        self
+    }
+
+    /// Index of the level currently on the entity, 0 being the most detailed
+    pub fn active_level(&self) -> usize {
+        self.active
+    }
+
+    /// The level to use at `distance` from the camera, starting from the active one
+    pub fn select_level(&self, distance: f32) -> usize {
+        let mut level = self.active;
+
+        while level + 1 < self.levels.len()
+            && distance > self.levels[level + 1].min_distance + self.hysteresis
+        {
+            level += 1;
+        }
+        while level > 0 && distance < self.levels[level].min_distance - self.hysteresis {
+            level -= 1;
+        }
+
+        level
+    }
+
+    /// Makes `level` active, trading meshes with the one currently on the entity
+    pub fn switch_to(&mut self, level: usize, mesh_filter: &mut BasicMeshFilter) {
+        if level == self.active || level >= self.levels.len() {
+            return;
+        }
+
+        let Some(mut mesh) = self.levels[level].mesh.take() else {
+            return;
+        };
+        std::mem::swap(&mut mesh, mesh_filter);
+        self.levels[self.active].mesh = Some(mesh);
+        self.active = level;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Levels from 0, 10 and 20 units with 1 unit of hysteresis. Meshes aren't needed to select.
+    fn lod_at(active: usize) -> Lod {
+        let levels = [0.0, 10.0, 20.0]
+            .into_iter()
+            .map(|min_distance| LodLevel {
+                min_distance,
+                mesh: None,
+            })
+            .collect();
+        Lod {
+            levels,
+            active,
+            hysteresis: 1.0,
+        }
+    }
+
+    #[test]
+    fn level_only_coarsens_past_the_hysteresis() {
+        let lod = lod_at(0);
+
+        assert_eq!(lod.select_level(10.5), 0);
+        assert_eq!(lod.select_level(11.5), 1);
+        assert_eq!(lod.select_level(25.0), 2);
+    }
+
+    #[test]
+    fn level_only_refines_past_the_hysteresis() {
+        let lod = lod_at(1);
+
+        assert_eq!(lod.select_level(9.5), 1);
+        assert_eq!(lod.select_level(8.5), 0);
+        assert_eq!(lod.select_level(20.5), 1);
+    }
+}
@@ -1 +1,2 @@
+pub mod multi_texture_material;
 pub mod unlit_diffuse_material;
@@ -0,0 +1,32 @@
+use bevy_ecs::{component::Component, world::World};
+
+use crate::{
+    gpu_resources::{
+        layouts::texture_uniform_layout::TextureUniformLayout, render_resources::RenderResources,
+    },
+    utils::texture::Texture,
+};
+
+/// A material binding `N` textures through `TextureUniformLayout<N>`.
+/// Slot i is bound at binding 2i with its sampler at 2i + 1, in the order given to `new`.
+/// `MultiTextureMaterial<3>` is drawn by the multi texture pipeline as diffuse, detail and mask.
+#[derive(Component)]
+pub struct MultiTextureMaterial<const N: usize> {
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl<const N: usize> MultiTextureMaterial<N> {
+    pub fn new(world: &World, textures: &[&Texture; N]) -> Self {
+        let render_resources: &RenderResources = world.get_resource::<RenderResources>().unwrap();
+
+        let texture_uniform_layout: &TextureUniformLayout<N> = world
+            .get_resource::<TextureUniformLayout<N>>()
+            .expect("No TextureUniformLayout for this many textures");
+
+        let device = &render_resources.device;
+
+        let bind_group = texture_uniform_layout.create_complete_bind_group(device, textures);
+
+        Self { bind_group }
+    }
+}
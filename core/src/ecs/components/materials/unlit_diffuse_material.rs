@@ -2,7 +2,9 @@ use bevy_ecs::{component::Component, world::World};
 
 use crate::{
     gpu_resources::{
-        layouts::texture_uniform_layout::TextureUniformLayout, render_resources::RenderResources,
+        layouts::texture_uniform_layout::TextureUniformLayout,
+        pipelines::{blend_mode::BlendMode, cull_mode::CullMode},
+        render_resources::RenderResources,
     },
     utils::texture::Texture,
 };
@@ -10,6 +12,10 @@ use crate::{
 #[derive(Component)]
 pub struct UnlitDiffuseMaterial {
     pub bind_group: wgpu::BindGroup,
+    pub blend_mode: BlendMode,
+    pub cull_mode: CullMode,
+    /// Whether texels with alpha below `ALPHA_CUTOFF` are discarded, for foliage and fences
+    pub alpha_cutout: bool,
 }
 
 impl UnlitDiffuseMaterial {
@@ -23,6 +29,28 @@ impl UnlitDiffuseMaterial {
 
         let bind_group = texture_uniform_layout.create_complete_bind_group(device, &[texture]);
 
-        Self { bind_group }
+        Self {
+            bind_group,
+            blend_mode: BlendMode::default(),
+            cull_mode: CullMode::default(),
+            alpha_cutout: false,
+        }
+    }
+
+    pub fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    /// `CullMode::None` makes the material double-sided
+    pub fn with_cull_mode(mut self, cull_mode: CullMode) -> Self {
+        self.cull_mode = cull_mode;
+        self
+    }
+
+    /// Only applies to `BlendMode::Opaque`, blended materials already use the texture's alpha
+    pub fn with_alpha_cutout(mut self, alpha_cutout: bool) -> Self {
+        self.alpha_cutout = alpha_cutout;
+        self
     }
 }
@@ -0,0 +1,65 @@
+use bevy_ecs::component::Component;
+use glam::{Mat4, Vec3};
+
+use crate::{
+    gpu_resources::types::basic_vertex::BasicVertex,
+    utils::{
+        aabb::Aabb,
+        raycast::{ray_mesh_intersect, ray_sphere_intersect},
+    },
+};
+
+/// A CPU copy of a mesh's triangles so it can be hit by rays, e.g. for `Core::pick`.
+/// A bounding sphere is kept as a broad phase so most misses skip the per triangle test.
+#[derive(Component)]
+pub struct MeshCollider {
+    vertices: Vec<BasicVertex>,
+    indices: Vec<u32>,
+    /// Center of the bounding sphere in model space
+    bounds_center: Vec3,
+    /// Radius of the bounding sphere in model space
+    bounds_radius: f32,
+    /// Bounding box in model space
+    local_bounds: Aabb,
+}
+
+impl MeshCollider {
+    pub fn new(vertices: &[BasicVertex], indices: &[u32]) -> Self {
+        let local_bounds = Aabb::from_points(vertices.iter().map(|vertex| vertex.position))
+            .unwrap_or(Aabb::new(Vec3::ZERO, Vec3::ZERO));
+        let bounds_center = local_bounds.center();
+        let bounds_radius = vertices
+            .iter()
+            .map(|vertex| vertex.position.distance(bounds_center))
+            .fold(0.0, f32::max);
+
+        Self {
+            vertices: vertices.to_vec(),
+            indices: indices.to_vec(),
+            bounds_center,
+            bounds_radius,
+            local_bounds,
+        }
+    }
+
+    /// Bounding box of the mesh in model space
+    pub fn local_bounds(&self) -> Aabb {
+        self.local_bounds
+    }
+
+    /// Distance along the world space ray to the nearest triangle hit
+    pub fn intersect(&self, origin: Vec3, direction: Vec3, model_matrix: Mat4) -> Option<f32> {
+        let (scale, _, _) = model_matrix.to_scale_rotation_translation();
+        let world_center = model_matrix.transform_point3(self.bounds_center);
+        let world_radius = self.bounds_radius * scale.abs().max_element();
+
+        ray_sphere_intersect(origin, direction, world_center, world_radius)?;
+        ray_mesh_intersect(
+            origin,
+            direction,
+            &self.vertices,
+            &self.indices,
+            model_matrix,
+        )
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{
-    gpu_resources::types::basic_vertex::BasicVertex,
-    utils::buffer::{Buffer, BufferBuilder},
+    gpu_resources::types::{basic_vertex::BasicVertex, color_vertex::ColorVertex},
+    utils::buffer::{Buffer, BufferBuilder, DrawIndexedIndirectArgs},
 };
 use bevy_ecs::component::Component;
 use bytemuck::{Pod, Zeroable};
+use glam::{Mat3, Mat4};
 
 /// Trait to associate index types with their corresponding wgpu::IndexFormat
 pub trait IndexType: Pod + Zeroable {
@@ -20,7 +21,118 @@ impl IndexType for u32 {
 
 #[derive(Component)]
 pub struct BasicMeshFilter {
-    pub filter: MeshFilter<BasicVertex, u32>,
+    pub filter: CompactMeshFilter<BasicVertex>,
+}
+
+impl BasicMeshFilter {
+    pub fn new(device: &wgpu::Device, vertices: &[BasicVertex], indices: &[u32]) -> Self {
+        Self {
+            filter: CompactMeshFilter::new(device, vertices, indices),
+        }
+    }
+
+    /// Bakes several meshes into a single mesh so they can be drawn with one call.
+    /// Each source's vertices are moved into the space of its matrix, so with world
+    /// matrices the result should be drawn with an identity model transform.
+    /// Sources are given as vertex and index slices since a `MeshFilter` only keeps GPU buffers.
+    pub fn merge(device: &wgpu::Device, meshes: &[(&[BasicVertex], &[u32], Mat4)]) -> Self {
+        let (vertices, indices) = merge_mesh_data(meshes);
+        Self::new(device, &vertices, &indices)
+    }
+}
+
+/// A mesh whose vertices carry a color instead of texture coordinates, drawn by the vertex color pipeline
+#[derive(Component)]
+pub struct ColorMeshFilter {
+    pub filter: CompactMeshFilter<ColorVertex>,
+}
+
+impl ColorMeshFilter {
+    pub fn new(device: &wgpu::Device, vertices: &[ColorVertex], indices: &[u32]) -> Self {
+        Self {
+            filter: CompactMeshFilter::new(device, vertices, indices),
+        }
+    }
+}
+
+/// A mesh filter that uses 16 bit indices when every vertex can be addressed by one,
+/// halving the index buffer of small meshes, and falls back to 32 bit indices otherwise
+pub enum CompactMeshFilter<V: Pod + Zeroable> {
+    U16(MeshFilter<V, u16>),
+    U32(MeshFilter<V, u32>),
+}
+
+impl<V: Pod + Zeroable> CompactMeshFilter<V> {
+    pub fn new(device: &wgpu::Device, vertices: &[V], indices: &[u32]) -> Self {
+        if vertices.len() <= u16::MAX as usize {
+            let indices: Vec<u16> = indices.iter().map(|&index| index as u16).collect();
+            CompactMeshFilter::U16(MeshFilter::new(device, vertices, &indices))
+        } else {
+            CompactMeshFilter::U32(MeshFilter::new(device, vertices, indices))
+        }
+    }
+
+    pub fn index_format(&self) -> wgpu::IndexFormat {
+        match self {
+            CompactMeshFilter::U16(filter) => filter.index_format,
+            CompactMeshFilter::U32(filter) => filter.index_format,
+        }
+    }
+
+    pub fn index_count(&self) -> u32 {
+        match self {
+            CompactMeshFilter::U16(filter) => filter.index_count,
+            CompactMeshFilter::U32(filter) => filter.index_count,
+        }
+    }
+
+    pub fn draw<'w, 'a>(&'w self, render_pass: &mut wgpu::RenderPass<'a>)
+    where
+        'w: 'a,
+    {
+        match self {
+            CompactMeshFilter::U16(filter) => filter.draw(render_pass),
+            CompactMeshFilter::U32(filter) => filter.draw(render_pass),
+        }
+    }
+
+    pub fn draw_instanced<'w, 'a>(
+        &'w self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        instance_count: u32,
+    ) where
+        'w: 'a,
+    {
+        match self {
+            CompactMeshFilter::U16(filter) => filter.draw_instanced(render_pass, instance_count),
+            CompactMeshFilter::U32(filter) => filter.draw_instanced(render_pass, instance_count),
+        }
+    }
+
+    pub fn indirect_args(&self, instance_count: u32) -> DrawIndexedIndirectArgs {
+        match self {
+            CompactMeshFilter::U16(filter) => filter.indirect_args(instance_count),
+            CompactMeshFilter::U32(filter) => filter.indirect_args(instance_count),
+        }
+    }
+
+    pub fn draw_indirect<'w, 'a>(
+        &'w self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        indirect_buffer: &'w Buffer<DrawIndexedIndirectArgs>,
+        offset: u64,
+    ) where
+        'w: 'a,
+    {
+        match self {
+            CompactMeshFilter::U16(filter) => {
+                filter.draw_indirect(render_pass, indirect_buffer, offset)
+            }
+            CompactMeshFilter::U32(filter) => {
+                filter.draw_indirect(render_pass, indirect_buffer, offset)
+            }
+        }
+    }
 }
 
 pub struct MeshFilter<V: Pod + Zeroable, I: IndexType> {
@@ -70,4 +182,245 @@ impl<V: Pod + Zeroable, I: IndexType> MeshFilter<V, I> {
         render_pass.set_index_buffer(self.index_buffer.slice(), self.index_format);
         render_pass.draw_indexed(0..self.index_count, 0, 0..instance_count);
     }
+
+    /// Draw arguments covering the whole mesh, used to seed an indirect buffer
+    pub fn indirect_args(&self, instance_count: u32) -> DrawIndexedIndirectArgs {
+        DrawIndexedIndirectArgs {
+            index_count: self.index_count,
+            instance_count,
+            ..Default::default()
+        }
+    }
+
+    /// Draws using arguments read from `indirect_buffer` at `offset` bytes,
+    /// which lets a compute pass decide the index and instance counts
+    pub fn draw_indirect<'w, 'a>(
+        &'w self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        indirect_buffer: &'w Buffer<DrawIndexedIndirectArgs>,
+        offset: u64,
+    ) where
+        'w: 'a,
+    {
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice());
+        render_pass.set_index_buffer(self.index_buffer.slice(), self.index_format);
+        render_pass.draw_indexed_indirect(&indirect_buffer.buffer, offset);
+    }
+}
+
+/// Concatenates meshes into one vertex and index list, transforming each mesh's
+/// positions by its matrix and offsetting its indices past the previous meshes' vertices
+pub fn merge_mesh_data(meshes: &[(&[BasicVertex], &[u32], Mat4)]) -> (Vec<BasicVertex>, Vec<u32>) {
+    let vertex_count = meshes.iter().map(|(vertices, _, _)| vertices.len()).sum();
+    let index_count = meshes.iter().map(|(_, indices, _)| indices.len()).sum();
+
+    let mut merged_vertices = Vec::with_capacity(vertex_count);
+    let mut merged_indices = Vec::with_capacity(index_count);
+
+    for (vertices, indices, matrix) in meshes {
+        let base_index = merged_vertices.len() as u32;
+        // the inverse transpose keeps normals perpendicular under non-uniform scale
+        let normal_matrix = Mat3::from_mat4(*matrix).inverse().transpose();
+        // a mirroring matrix flips which way the bitangent points
+        let handedness = matrix.determinant().signum();
+
+        merged_vertices.extend(vertices.iter().map(|vertex| {
+            BasicVertex {
+                position: matrix.transform_point3(vertex.position),
+                tex_coords: vertex.tex_coords,
+                normal: (normal_matrix * vertex.normal).normalize_or_zero(),
+                tangent: matrix
+                    .transform_vector3(vertex.tangent.truncate())
+                    .normalize_or_zero()
+                    .extend(vertex.tangent.w * handedness),
+            }
+        }));
+        merged_indices.extend(indices.iter().map(|index| index + base_index));
+    }
+
+    (merged_vertices, merged_indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec2, Vec3, Vec4};
+
+    use super::*;
+    use crate::gpu_resources::pipelines::fallback::catch_validation_error;
+    use crate::utils::{primitives, test_device::request_device};
+
+    /// Draws the mesh's positions in white
+    const SHADER: &str = "
+        @vertex
+        fn vs_main(@location(0) position: vec3<f32>) -> @builtin(position) vec4<f32> {
+            return vec4<f32>(position, 1.0);
+        }
+
+        @fragment
+        fn fs_main() -> @location(0) vec4<f32> {
+            return vec4<f32>(1.0);
+        }
+    ";
+
+    fn quad() -> (Vec<BasicVertex>, Vec<u32>) {
+        let vertices = [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)]
+            .into_iter()
+            .map(|(x, y)| BasicVertex {
+                position: Vec3::new(x, y, 0.0),
+                tex_coords: Vec2::new(x + 0.5, 0.5 - y),
+                normal: Vec3::NEG_Z,
+                tangent: Vec4::new(1.0, 0.0, 0.0, 1.0),
+            })
+            .collect();
+        (vertices, vec![0, 1, 2, 0, 2, 3])
+    }
+
+    #[test]
+    fn merging_two_translated_quads_moves_vertices_and_offsets_indices() {
+        let (vertices, indices) = quad();
+        let left = Mat4::from_translation(Vec3::new(-2.0, 0.0, 0.0));
+        let right = Mat4::from_translation(Vec3::new(2.0, 1.0, 0.0));
+
+        let (merged_vertices, merged_indices) =
+            merge_mesh_data(&[(&vertices, &indices, left), (&vertices, &indices, right)]);
+
+        assert_eq!(merged_vertices.len(), 8);
+        assert_eq!(merged_indices, vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7]);
+        for (i, vertex) in vertices.iter().enumerate() {
+            let (left_vertex, right_vertex) = (merged_vertices[i], merged_vertices[i + 4]);
+            assert_eq!(
+                left_vertex.position,
+                vertex.position + Vec3::new(-2.0, 0.0, 0.0)
+            );
+            assert_eq!(
+                right_vertex.position,
+                vertex.position + Vec3::new(2.0, 1.0, 0.0)
+            );
+            // translation leaves everything but the position alone
+            assert_eq!(left_vertex.normal, vertex.normal);
+            assert_eq!(right_vertex.tangent, vertex.tangent);
+            assert_eq!(right_vertex.tex_coords, vertex.tex_coords);
+        }
+    }
+
+    #[test]
+    fn mirrored_mesh_keeps_normals_unit_length_and_flips_handedness() {
+        let (vertices, indices) = quad();
+        let mirror = Mat4::from_scale(Vec3::new(-2.0, 1.0, 1.0));
+
+        let (merged_vertices, _) = merge_mesh_data(&[(&vertices, &indices, mirror)]);
+
+        for vertex in merged_vertices {
+            assert!((vertex.normal.length() - 1.0).abs() < 1e-6);
+            assert_eq!(vertex.tangent, Vec4::new(-1.0, 0.0, 0.0, -1.0));
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn generated_meshes_use_16_bit_indices_while_every_vertex_fits() {
+        let (device, _queue) = request_device();
+
+        let cube = primitives::create_cube(&device, 1.0, 1);
+        assert_eq!(cube.filter.index_format(), wgpu::IndexFormat::Uint16);
+        assert_eq!(cube.filter.index_count(), 36);
+        let CompactMeshFilter::U16(filter) = &cube.filter else {
+            panic!("a cube got 32 bit indices");
+        };
+        assert_eq!(filter.index_buffer.size, 36 * 2);
+
+        // 257 x 257 vertices is more than 16 bit indices can address
+        let plane = primitives::create_plane(&device, 1.0, 1.0, 256, 256);
+        assert_eq!(plane.filter.index_format(), wgpu::IndexFormat::Uint32);
+        assert_eq!(plane.filter.index_count(), 256 * 256 * 6);
+
+        // u16::MAX vertices are addressed by 0..u16::MAX, one more needs 32 bits
+        let (vertices, indices) = quad();
+        let largest = vec![vertices[0]; u16::MAX as usize];
+        let largest = BasicMeshFilter::new(&device, &largest, &indices);
+        assert_eq!(largest.filter.index_format(), wgpu::IndexFormat::Uint16);
+        let too_many = vec![vertices[0]; u16::MAX as usize + 1];
+        let too_many = BasicMeshFilter::new(&device, &too_many, &indices);
+        assert_eq!(too_many.filter.index_format(), wgpu::IndexFormat::Uint32);
+        assert_eq!(too_many.filter.index_count(), 6);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn indirect_draw_reads_the_mesh_index_count() {
+        let (device, queue) = request_device();
+        let (vertices, indices) = quad();
+        let mesh = BasicMeshFilter::new(&device, &vertices, &indices);
+        let args = mesh.filter.indirect_args(1);
+        assert_eq!(args.index_count, 6);
+        let indirect_buffer = Buffer::new_indirect(&device, &[args], "Indirect Test Buffer");
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[BasicVertex::vertex_layout()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::TextureFormat::Rgba8Unorm.into())],
+                compilation_options: Default::default(),
+            }),
+            primitive: Default::default(),
+            depth_stencil: None,
+            multisample: Default::default(),
+            multiview: None,
+        });
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = target.create_view(&Default::default());
+
+        let ((), error) = catch_validation_error(&device, || {
+            let mut encoder = device.create_command_encoder(&Default::default());
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &view,
+                        resolve_target: None,
+                        ops: Default::default(),
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                render_pass.set_pipeline(&pipeline);
+                mesh.filter.draw_indirect(
+                    &mut render_pass,
+                    &indirect_buffer,
+                    Buffer::indirect_offset(0),
+                );
+            }
+            queue.submit([encoder.finish()]);
+        });
+        assert!(
+            error.is_none(),
+            "indirect draw failed validation: {error:?}"
+        );
+    }
 }
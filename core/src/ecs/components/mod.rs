@@ -1,6 +1,23 @@
+pub mod billboard;
 pub mod camera;
+pub mod camera_shake;
+pub mod dolly_zoom;
+pub mod drop_target;
+pub mod fly_camera_controller;
 pub mod gpu_bindings;
+pub mod lines;
+pub mod lod;
 pub mod materials;
+pub mod mesh_collider;
 pub mod mesh_filter;
+pub mod occlusion_culled;
+pub mod orbit_camera_controller;
+pub mod particle_emitter;
+pub mod persistent;
+pub mod projection_blend;
+pub mod render_order;
 pub mod rotate_component;
+pub mod smooth_follow;
 pub mod transform;
+pub mod tween;
+pub mod visible;
@@ -0,0 +1,8 @@
+use bevy_ecs::component::Component;
+
+/// Opts an unlit entity into occlusion culling.
+/// Each frame its draw is wrapped in an occlusion query, and while the last resolved query
+/// saw no samples only a cheap depth test of it is drawn until it becomes visible again.
+/// Only worth it for meshes that are expensive to shade and often hidden behind others.
+#[derive(Component, Default)]
+pub struct OcclusionCulled;
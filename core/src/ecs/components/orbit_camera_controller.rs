@@ -0,0 +1,119 @@
+use bevy_ecs::component::Component;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use winit::event::MouseButton;
+
+/// Orbits the entity's `Transform`, usually the camera's, around `target`.
+/// Dragging `rotate_button` turns around the target, dragging `pan_button` moves the target
+/// across the view and scrolling zooms in and out.
+#[derive(Component, Clone, Copy, Serialize, Deserialize)]
+pub struct OrbitCameraController {
+    pub target: Vec3,
+    pub distance: f32,
+    /// Angle around the world Y axis in radians, 0 looks down +Z
+    pub yaw: f32,
+    /// Angle above the target's horizon in radians
+    pub pitch: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    /// Kept inside +-90 degrees so the camera never flips over the pole
+    pub min_pitch: f32,
+    pub max_pitch: f32,
+    /// Radians turned per pixel dragged
+    pub rotate_speed: f32,
+    /// Fraction of the view crossed per pixel dragged, scaled by distance
+    pub pan_speed: f32,
+    /// How much a scroll step scales the distance
+    pub zoom_speed: f32,
+    pub rotate_button: MouseButton,
+    pub pan_button: MouseButton,
+    /// Cursor position the current drag was last applied at
+    #[serde(skip)]
+    pub(crate) drag_anchor: Option<(f64, f64)>,
+}
+
+impl Default for OrbitCameraController {
+    fn default() -> Self {
+        let pitch_limit = 89.0_f32.to_radians();
+        Self {
+            target: Vec3::ZERO,
+            distance: 10.0,
+            yaw: 0.0,
+            pitch: 0.0,
+            min_distance: 1.0,
+            max_distance: 100.0,
+            min_pitch: -pitch_limit,
+            max_pitch: pitch_limit,
+            rotate_speed: 0.005,
+            pan_speed: 0.001,
+            zoom_speed: 0.1,
+            rotate_button: MouseButton::Left,
+            pan_button: MouseButton::Middle,
+            drag_anchor: None,
+        }
+    }
+}
+
+impl OrbitCameraController {
+    pub fn new(target: Vec3, distance: f32) -> Self {
+        Self {
+            target,
+            distance,
+            ..Default::default()
+        }
+    }
+
+    /// Starts orbiting `target` from wherever `position` already is
+    pub fn from_position(position: Vec3, target: Vec3) -> Self {
+        let offset = position - target;
+        let distance = offset.length();
+        let mut controller = Self::new(target, distance);
+        if distance > f32::EPSILON {
+            controller.pitch = (offset.y / distance).asin();
+            controller.yaw = offset.x.atan2(-offset.z);
+        }
+        controller.clamp();
+        controller
+    }
+
+    pub fn with_distance_limits(mut self, min_distance: f32, max_distance: f32) -> Self {
+        self.min_distance = min_distance;
+        self.max_distance = max_distance;
+        self.clamp();
+        self
+    }
+
+    /// Limits in radians, clamped to just inside +-90 degrees
+    pub fn with_pitch_limits(mut self, min_pitch: f32, max_pitch: f32) -> Self {
+        let pitch_limit = 89.0_f32.to_radians();
+        self.min_pitch = min_pitch.max(-pitch_limit);
+        self.max_pitch = max_pitch.min(pitch_limit);
+        self.clamp();
+        self
+    }
+
+    pub fn rotate(&mut self, delta_yaw: f32, delta_pitch: f32) {
+        self.yaw += delta_yaw;
+        self.pitch += delta_pitch;
+        self.clamp();
+    }
+
+    /// Scales the distance by `factor`, below 1 moves closer
+    pub fn zoom(&mut self, factor: f32) {
+        self.distance *= factor;
+        self.clamp();
+    }
+
+    pub fn clamp(&mut self) {
+        self.distance = self.distance.clamp(self.min_distance, self.max_distance);
+        self.pitch = self.pitch.clamp(self.min_pitch, self.max_pitch);
+    }
+
+    /// Where the camera sits for the current target, distance, yaw and pitch
+    pub fn position(&self) -> Vec3 {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let direction = Vec3::new(cos_pitch * sin_yaw, sin_pitch, -cos_pitch * cos_yaw);
+        self.target + direction * self.distance
+    }
+}
@@ -0,0 +1,206 @@
+use bevy_ecs::component::Component;
+use glam::{Vec3, Vec4};
+use rand::Rng;
+
+/// A single simulated particle
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Particle {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    /// Seconds since the particle was spawned
+    pub age: f32,
+    pub alive: bool,
+}
+
+/// Emits and simulates particles on the CPU.
+/// Particles are simulated in world space starting at the entity's `Transform`.
+/// The pool is sized to `spawn_rate * lifetime` so dead particles are recycled
+/// instead of allocating new ones.
+#[derive(Component)]
+pub struct ParticleEmitter {
+    /// Particles spawned per second
+    pub spawn_rate: f32,
+    /// Seconds a particle lives before being recycled
+    pub lifetime: f32,
+    /// Velocity every particle starts with
+    pub initial_velocity: Vec3,
+    /// Magnitude of the random velocity added to each particle
+    pub spread: f32,
+    /// Constant acceleration applied to every particle (e.g. gravity)
+    pub acceleration: Vec3,
+    /// Quad size at spawn
+    pub start_size: f32,
+    /// Quad size at the end of the particle's life
+    pub end_size: f32,
+    /// Color at spawn
+    pub start_color: Vec4,
+    /// Color at the end of the particle's life
+    pub end_color: Vec4,
+    /// Whether new particles are spawned. Live particles keep simulating when disabled.
+    pub emitting: bool,
+
+    particles: Vec<Particle>,
+    /// Indices of the dead particles, so spawning doesn't search the pool
+    free_slots: Vec<usize>,
+    spawn_accumulator: f32,
+}
+
+impl ParticleEmitter {
+    pub fn new(spawn_rate: f32, lifetime: f32, initial_velocity: Vec3, spread: f32) -> Self {
+        let capacity = (spawn_rate * lifetime).ceil() as usize + 1;
+
+        Self {
+            spawn_rate,
+            lifetime,
+            initial_velocity,
+            spread,
+            acceleration: Vec3::ZERO,
+            start_size: 0.25,
+            end_size: 0.25,
+            start_color: Vec4::ONE,
+            end_color: Vec4::new(1.0, 1.0, 1.0, 0.0),
+            emitting: true,
+            particles: vec![Particle::default(); capacity],
+            free_slots: (0..capacity).rev().collect(),
+            spawn_accumulator: 0.0,
+        }
+    }
+
+    /// Maximum number of particles that can be alive at once
+    pub fn capacity(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Number of particles currently alive
+    pub fn live_count(&self) -> usize {
+        self.particles.iter().filter(|p| p.alive).count()
+    }
+
+    /// Iterates over the particles currently alive
+    pub fn live_particles(&self) -> impl Iterator<Item = &Particle> {
+        self.particles.iter().filter(|p| p.alive)
+    }
+
+    /// Normalized age (0 at spawn, 1 at death) of a particle
+    pub fn life_fraction(&self, particle: &Particle) -> f32 {
+        (particle.age / self.lifetime).clamp(0.0, 1.0)
+    }
+
+    /// Color of a particle interpolated over its life
+    pub fn color_at(&self, particle: &Particle) -> Vec4 {
+        self.start_color
+            .lerp(self.end_color, self.life_fraction(particle))
+    }
+
+    /// Size of a particle interpolated over its life
+    pub fn size_at(&self, particle: &Particle) -> f32 {
+        let t = self.life_fraction(particle);
+        self.start_size + (self.end_size - self.start_size) * t
+    }
+
+    /// Advances the simulation by `delta_time` seconds, spawning new particles at `origin`
+    pub fn update(&mut self, delta_time: f32, origin: Vec3) {
+        // Age and move the existing particles, killing the expired ones
+        for (index, particle) in self.particles.iter_mut().enumerate() {
+            if !particle.alive {
+                continue;
+            }
+
+            particle.age += delta_time;
+            if particle.age >= self.lifetime {
+                particle.alive = false;
+                self.free_slots.push(index);
+                continue;
+            }
+
+            particle.velocity += self.acceleration * delta_time;
+            particle.position += particle.velocity * delta_time;
+        }
+
+        if !self.emitting {
+            self.spawn_accumulator = 0.0;
+            return;
+        }
+
+        // Grow the pool if the rate or lifetime changed since construction
+        let required_capacity = (self.spawn_rate * self.lifetime).ceil() as usize + 1;
+        if required_capacity > self.particles.len() {
+            self.free_slots
+                .extend((self.particles.len()..required_capacity).rev());
+            self.particles
+                .resize(required_capacity, Particle::default());
+        }
+
+        self.spawn_accumulator += self.spawn_rate * delta_time;
+
+        let mut rng = rand::thread_rng();
+        while self.spawn_accumulator >= 1.0 {
+            self.spawn_accumulator -= 1.0;
+
+            let Some(index) = self.free_slots.pop() else {
+                // The pool is full; drop the owed spawns rather than allocate, or they'd
+                // all come out in one burst once slots free up
+                self.spawn_accumulator = self.spawn_accumulator.fract();
+                break;
+            };
+
+            let jitter = Vec3::new(
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+                rng.gen_range(-1.0..1.0),
+            )
+            .normalize_or_zero()
+                * self.spread;
+
+            self.particles[index] = Particle {
+                position: origin,
+                velocity: self.initial_velocity + jitter,
+                age: 0.0,
+                alive: true,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn live_count_settles_at_rate_times_lifetime() {
+        let mut emitter = ParticleEmitter::new(20.0, 2.0, Vec3::Y, 0.5);
+        for _ in 0..300 {
+            emitter.update(1.0 / 60.0, Vec3::ZERO);
+        }
+
+        let expected = 20.0 * 2.0;
+        let live_count = emitter.live_count() as f32;
+        assert!(
+            (live_count - expected).abs() <= 2.0,
+            "{} live particles, expected about {}",
+            live_count,
+            expected
+        );
+    }
+
+    #[test]
+    fn no_particles_spawn_while_not_emitting() {
+        let mut emitter = ParticleEmitter::new(20.0, 2.0, Vec3::Y, 0.5);
+        emitter.emitting = false;
+        emitter.update(1.0, Vec3::ZERO);
+        assert_eq!(emitter.live_count(), 0);
+    }
+
+    #[test]
+    fn spawns_owed_while_full_are_dropped() {
+        let mut emitter = ParticleEmitter::new(10.0, 1.0, Vec3::Y, 0.5);
+
+        // a long frame owes far more spawns than the pool holds
+        emitter.update(5.0, Vec3::ZERO);
+        assert_eq!(emitter.live_count(), emitter.capacity());
+
+        // everything expires, and only this second's 10 spawns come out
+        emitter.update(1.0, Vec3::ZERO);
+        assert_eq!(emitter.live_count(), 10);
+    }
+}
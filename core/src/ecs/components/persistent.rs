@@ -0,0 +1,6 @@
+use bevy_ecs::component::Component;
+
+/// Keeps an entity alive through `Core::clear_scene`, e.g. a UI root or a player that
+/// carries over between demos. Cameras are always kept.
+#[derive(Component, Default)]
+pub struct Persistent;
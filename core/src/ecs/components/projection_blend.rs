@@ -0,0 +1,45 @@
+use bevy_ecs::component::Component;
+
+use crate::{
+    ecs::components::{
+        camera::ProjectionType,
+        tween::{Tween, Tweenable},
+    },
+    utils::easing::Easing,
+};
+
+/// Blends the camera's projection matrix towards `target`, for smooth switches between
+/// perspective and orthographic. Once `t` reaches 1 the camera's `projection_type` is set to
+/// `target` and the component is removed.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct ProjectionBlend {
+    pub target: ProjectionType,
+    /// 0 is the camera's current projection, 1 is `target`
+    pub t: f32,
+}
+
+impl ProjectionBlend {
+    pub fn new(target: ProjectionType) -> Self {
+        Self { target, t: 0.0 }
+    }
+
+    /// The blend and the tween animating it over `duration` seconds, to insert on the camera
+    pub fn tween(
+        target: ProjectionType,
+        duration: f32,
+        easing: Easing,
+    ) -> (ProjectionBlend, Tween<ProjectionBlend>) {
+        let start = Self::new(target);
+        let end = Self { target, t: 1.0 };
+        (start, Tween::new(start, end, duration, easing))
+    }
+}
+
+impl Tweenable for ProjectionBlend {
+    fn interpolate(start: &Self, end: &Self, t: f32) -> Self {
+        Self {
+            target: end.target,
+            t: start.t + (end.t - start.t) * t,
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use bevy_ecs::component::Component;
+use serde::{Deserialize, Serialize};
+
+/// Explicit draw order, lower values are drawn first.
+/// Sub-renderers sort by it before distance to the camera, within each blend mode batch,
+/// so opaque geometry still draws before blended geometry. It only changes the order of
+/// draws, so depth testing still applies. Entities without the component use order 0.
+#[derive(
+    Component, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct RenderOrder(pub i32);
+
+impl RenderOrder {
+    /// The order of an entity with this optional component
+    pub fn of(render_order: Option<&RenderOrder>) -> i32 {
+        render_order.map_or(0, |render_order| render_order.0)
+    }
+}
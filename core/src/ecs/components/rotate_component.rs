@@ -1,6 +1,7 @@
 use bevy_ecs::component::Component;
+use serde::{Deserialize, Serialize};
 
-#[derive(Component, Default)]
+#[derive(Component, Default, Clone, Copy, Serialize, Deserialize)]
 pub struct RotateComponent {
     pub rotate_axis: glam::Vec3,
     pub rotate_speed: f32,
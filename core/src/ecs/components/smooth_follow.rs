@@ -0,0 +1,34 @@
+use bevy_ecs::{component::Component, entity::Entity};
+use glam::Vec3;
+
+/// Moves the entity's `Transform` towards `target`'s translation plus `offset`, lagging behind it.
+/// Higher `stiffness` catches up faster; the approach is the same at any frame rate.
+#[derive(Component, Clone, Copy)]
+pub struct SmoothFollow {
+    pub target: Entity,
+    pub offset: Vec3,
+    pub stiffness: f32,
+    /// Whether to also turn to face the target, e.g. for a third person camera
+    pub look_at: bool,
+}
+
+impl SmoothFollow {
+    pub fn new(target: Entity, offset: Vec3, stiffness: f32) -> Self {
+        Self {
+            target,
+            offset,
+            stiffness,
+            look_at: false,
+        }
+    }
+
+    pub fn with_look_at(mut self, look_at: bool) -> Self {
+        self.look_at = look_at;
+        self
+    }
+
+    /// Fraction of the remaining distance to cover over `delta_time` seconds
+    pub fn blend_factor(&self, delta_time: f32) -> f32 {
+        1.0 - (-self.stiffness * delta_time).exp()
+    }
+}
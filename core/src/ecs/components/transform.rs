@@ -1,14 +1,16 @@
 use bevy_ecs::component::Component;
 use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
 
 use crate::utils::degrees_and_radians::{Deg, Rad};
 
 /// Simple transform component to pair with the camera
-#[derive(Component, Default)]
+#[derive(Component, Default, Clone, Copy, Serialize, Deserialize)]
 pub struct Transform {
     pub translation: Vec3,
     pub rotation: Quat,
     pub scale: Vec3,
+    #[serde(skip)]
     matrix: Option<glam::Mat4>,
 }
 
@@ -31,6 +33,16 @@ impl Transform {
         }
     }
 
+    /// Interpolates translation and scale linearly and rotation spherically.
+    /// `t` of 0 returns `self` and 1 returns `other`.
+    pub fn lerp(&self, other: &Transform, t: f32) -> Self {
+        Self::from_trs(
+            self.translation.lerp(other.translation, t),
+            self.rotation.slerp(other.rotation, t),
+            self.scale.lerp(other.scale, t),
+        )
+    }
+
     pub fn look_at(&mut self, target: Vec3, up: Vec3) {
         // Calculate forard direction (z-axis)
         let forward = (target - self.translation).normalize();
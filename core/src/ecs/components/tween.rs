@@ -0,0 +1,126 @@
+use bevy_ecs::component::Component;
+
+use crate::{ecs::components::transform::Transform, utils::easing::Easing};
+
+/// Values that a `Tween` can interpolate between
+pub trait Tweenable: Component + Clone {
+    /// Interpolates from `start` to `end`, where `t` is the eased factor
+    fn interpolate(start: &Self, end: &Self, t: f32) -> Self;
+}
+
+impl Tweenable for Transform {
+    fn interpolate(start: &Self, end: &Self, t: f32) -> Self {
+        start.lerp(end, t)
+    }
+}
+
+/// What a `Tween` does once it reaches its duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TweenMode {
+    /// Stops at `end`, removes the tween and adds a `TweenFinished` marker
+    #[default]
+    Once,
+    /// Jumps back to `start` and plays again
+    Loop,
+    /// Plays back and forth between `start` and `end`
+    PingPong,
+}
+
+/// Animates the entity's `T` component from `start` to `end` over `duration` seconds
+#[derive(Component)]
+pub struct Tween<T: Tweenable> {
+    pub start: T,
+    pub end: T,
+    /// Seconds one pass takes
+    pub duration: f32,
+    /// Seconds into the current pass
+    pub elapsed: f32,
+    pub easing: Easing,
+    pub mode: TweenMode,
+    /// Whether a ping-pong tween is currently playing from `end` back to `start`
+    pub reversed: bool,
+}
+
+impl<T: Tweenable> Tween<T> {
+    pub fn new(start: T, end: T, duration: f32, easing: Easing) -> Self {
+        Self {
+            start,
+            end,
+            duration,
+            elapsed: 0.0,
+            easing,
+            mode: TweenMode::Once,
+            reversed: false,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: TweenMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Linear progress through the current pass, 0..1
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        }
+    }
+
+    /// The interpolated value at the current progress
+    pub fn value(&self) -> T {
+        let progress = if self.reversed {
+            1.0 - self.progress()
+        } else {
+            self.progress()
+        };
+        T::interpolate(&self.start, &self.end, self.easing.apply(progress))
+    }
+
+    /// Advances the tween by `delta_time` seconds.
+    /// Returns true once a `TweenMode::Once` tween has finished.
+    pub fn advance(&mut self, delta_time: f32) -> bool {
+        self.elapsed += delta_time;
+
+        if self.elapsed < self.duration {
+            return false;
+        }
+
+        match self.mode {
+            TweenMode::Once => {
+                self.elapsed = self.duration;
+                true
+            }
+            TweenMode::Loop => {
+                self.elapsed = Self::wrap(self.elapsed, self.duration);
+                false
+            }
+            TweenMode::PingPong => {
+                // An odd number of completed passes flips the direction
+                let passes = if self.duration > 0.0 {
+                    (self.elapsed / self.duration) as u32
+                } else {
+                    1
+                };
+                if passes % 2 == 1 {
+                    self.reversed = !self.reversed;
+                }
+                self.elapsed = Self::wrap(self.elapsed, self.duration);
+                false
+            }
+        }
+    }
+
+    fn wrap(elapsed: f32, duration: f32) -> f32 {
+        if duration > 0.0 {
+            elapsed % duration
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Marker added to an entity when one of its `TweenMode::Once` tweens completes
+#[derive(Component, Default)]
+pub struct TweenFinished;
@@ -0,0 +1,32 @@
+use bevy_ecs::component::Component;
+use serde::{Deserialize, Serialize};
+
+/// Hides an entity from every sub-renderer without despawning it while false.
+/// Entities without the component are visible.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Visible(pub bool);
+
+impl Default for Visible {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+impl Visible {
+    /// Whether an entity with this optional component should be drawn
+    pub fn is_visible(visible: Option<&Visible>) -> bool {
+        visible.is_none_or(|visible| visible.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_an_explicit_false_hides() {
+        assert!(Visible::is_visible(None));
+        assert!(Visible::is_visible(Some(&Visible::default())));
+        assert!(!Visible::is_visible(Some(&Visible(false))));
+    }
+}
@@ -0,0 +1,143 @@
+use bevy_ecs::{bundle::Bundle, entity::Entity, world::World};
+use glam::Vec3;
+
+use crate::{
+    ecs::components::{
+        gpu_bindings::model_bindings::ModelBindings,
+        materials::unlit_diffuse_material::UnlitDiffuseMaterial, mesh_filter::BasicMeshFilter,
+        transform::Transform,
+    },
+    gpu_resources::{
+        pipelines::{blend_mode::BlendMode, cull_mode::CullMode},
+        render_resources::RenderResources,
+    },
+    utils::texture::Texture,
+};
+
+/// The components drawn by the unlit diffuse sub-renderer
+#[derive(Bundle)]
+pub struct MeshBundle {
+    transform: Transform,
+    mesh_filter: BasicMeshFilter,
+    model_bindings: ModelBindings,
+    material: UnlitDiffuseMaterial,
+}
+
+impl MeshBundle {
+    pub fn new(
+        world: &mut World,
+        mesh_filter: BasicMeshFilter,
+        material: UnlitDiffuseMaterial,
+        mut transform: Transform,
+    ) -> Self {
+        let device = world.resource::<RenderResources>().device.clone();
+        let model_bindings = ModelBindings::new(world, &device, &mut transform);
+
+        Self {
+            transform,
+            mesh_filter,
+            model_bindings,
+            material,
+        }
+    }
+}
+
+/// Builds a textured mesh entity, created by `Core::spawn_mesh`
+pub struct MeshSpawner<'w> {
+    world: &'w mut World,
+    mesh_filter: BasicMeshFilter,
+    material: UnlitDiffuseMaterial,
+    transform: Transform,
+}
+
+impl<'w> MeshSpawner<'w> {
+    pub fn new(world: &'w mut World, mesh_filter: BasicMeshFilter, texture: &Texture) -> Self {
+        let material = UnlitDiffuseMaterial::new(world, texture);
+
+        Self {
+            world,
+            mesh_filter,
+            material,
+            transform: Transform::from_translation(Vec3::ZERO),
+        }
+    }
+
+    pub fn transform(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    pub fn blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.material = self.material.with_blend_mode(blend_mode);
+        self
+    }
+
+    pub fn cull_mode(mut self, cull_mode: CullMode) -> Self {
+        self.material = self.material.with_cull_mode(cull_mode);
+        self
+    }
+
+    pub fn alpha_cutout(mut self, alpha_cutout: bool) -> Self {
+        self.material = self.material.with_alpha_cutout(alpha_cutout);
+        self
+    }
+
+    /// Creates the model bindings and spawns the entity
+    pub fn spawn(self) -> Entity {
+        let bundle = MeshBundle::new(self.world, self.mesh_filter, self.material, self.transform);
+        self.world.spawn(bundle).id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        ecs::resources::model_uniform_arena::ModelUniformArena,
+        gpu_resources::layouts::{
+            model_uniform_layout::ModelUniformLayout, texture_uniform_layout::TextureUniformLayout,
+        },
+        utils::{primitives, test_device::request_device, texture::TextureBuilder},
+    };
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn spawned_cube_has_every_component() {
+        let (device, queue) = request_device();
+        let device = Arc::new(device);
+
+        let mut world = World::new();
+        let model_uniform_layout = ModelUniformLayout::new(&device);
+        world.insert_resource(ModelUniformArena::new(&device, &model_uniform_layout));
+        world.insert_resource(model_uniform_layout);
+        world.insert_resource(TextureUniformLayout::<1>::new(&device));
+        world.insert_resource(RenderResources::new(
+            device.clone(),
+            Arc::new(queue),
+            wgpu::TextureFormat::Rgba8Unorm,
+        ));
+
+        let texture = TextureBuilder::new(&device).size(1, 1).build().unwrap();
+        let cube = primitives::create_cube(&device, 1.0, 1);
+        let translation = Vec3::new(1.0, 2.0, 3.0);
+
+        let entity = MeshSpawner::new(&mut world, cube, &texture)
+            .transform(Transform::from_translation(translation))
+            .blend_mode(BlendMode::Alpha)
+            .spawn();
+
+        let entity = world.entity(entity);
+        assert_eq!(entity.get::<Transform>().unwrap().translation, translation);
+        assert!(entity.contains::<BasicMeshFilter>());
+        assert_eq!(
+            entity.get::<ModelBindings>().unwrap().position(),
+            translation
+        );
+        assert_eq!(
+            entity.get::<UnlitDiffuseMaterial>().unwrap().blend_mode,
+            BlendMode::Alpha
+        );
+    }
+}
@@ -1 +1,2 @@
 pub mod camera_bundle;
+pub mod mesh_bundle;
@@ -1,4 +1,5 @@
 pub mod components;
 pub mod entity_bundles;
 pub mod resources;
+pub mod scene;
 pub mod systems;
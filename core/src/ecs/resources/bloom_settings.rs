@@ -0,0 +1,69 @@
+use bevy_ecs::system::Resource;
+use glam::Vec3;
+
+/// Parameters of the bloom post-process pass.
+/// Bloom is skipped entirely while `intensity` is zero.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct BloomSettings {
+    /// Brightness above which pixels start to bloom. Values above 1.0 only catch HDR colors.
+    pub threshold: f32,
+    /// Scale of the blurred light added back onto the scene
+    pub intensity: f32,
+    /// Spread of the blur filter in texels of each mip, 1.0 being the default spread
+    pub radius: f32,
+}
+
+impl Default for BloomSettings {
+    fn default() -> Self {
+        Self {
+            threshold: 1.0,
+            intensity: 0.3,
+            radius: 1.0,
+        }
+    }
+}
+
+impl BloomSettings {
+    /// The part of `color` that blooms, matching `fs_prefilter` in `bloom.wgsl`.
+    /// The color is scaled by how far its brightest channel is above the threshold, which keeps its hue.
+    pub fn prefilter(&self, color: Vec3) -> Vec3 {
+        let brightness = color.max_element();
+        let contribution = (brightness - self.threshold).max(0.0) / brightness.max(0.0001);
+        color * contribution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_brightness_above_the_threshold_blooms() {
+        let settings = BloomSettings::default();
+
+        assert_eq!(settings.prefilter(Vec3::ZERO), Vec3::ZERO);
+        assert_eq!(settings.prefilter(Vec3::new(0.5, 1.0, 0.25)), Vec3::ZERO);
+        // twice the threshold blooms half the color, keeping its hue
+        let bloom = settings.prefilter(Vec3::new(2.0, 1.0, 0.0));
+        assert!(
+            bloom.abs_diff_eq(Vec3::new(1.0, 0.5, 0.0), 1e-6),
+            "{}",
+            bloom
+        );
+    }
+
+    #[test]
+    fn brighter_input_never_blooms_less() {
+        let settings = BloomSettings {
+            threshold: 0.8,
+            ..Default::default()
+        };
+
+        let mut previous = 0.0;
+        for step in 0..=64 {
+            let bloom = settings.prefilter(Vec3::splat(step as f32 * 0.25)).x;
+            assert!(bloom >= previous, "step {}: {} < {}", step, bloom, previous);
+            previous = bloom;
+        }
+    }
+}
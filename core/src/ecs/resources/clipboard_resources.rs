@@ -0,0 +1,11 @@
+use std::sync::Arc;
+
+use bevy_ecs::system::Resource;
+
+use crate::traits::clipboard_traits::ClipboardHandler;
+
+/// A resource that holds the clipboard handler, so systems can copy and paste text.
+#[derive(Resource)]
+pub struct ClipboardPlatform {
+    pub handler: Arc<dyn ClipboardHandler>,
+}
@@ -0,0 +1,7 @@
+use bevy_ecs::system::Resource;
+
+/// Whether opaque geometry is drawn into the depth buffer before the main pass.
+/// With the pre-pass on, the main pass only shades the front-most opaque fragment,
+/// which saves work when fragment shaders are expensive.
+#[derive(Resource, Default)]
+pub struct DepthPrepass(pub bool);
@@ -91,15 +91,29 @@ impl Keyboard {
     }
 }
 
+/// Longest gap in seconds between two clicks that still counts as a double click
+const DOUBLE_CLICK_TIME: f64 = 0.3;
+/// Furthest in pixels, squared, the mouse may move between the clicks of a double click
+const MAX_DISTANCE_2_FOR_DOUBLE_CLICK: f64 = 4.0 * 4.0;
+
 pub enum MouseButtonState {
     Up,
     PressedThisFrame { start_x: f64, start_y: f64 },
     Down { start_x: f64, start_y: f64 },
     Dragged { start_x: f64, start_y: f64 },
     ReleasedPressThisFrame,
+    ReleasedDoubleClickThisFrame,
     ReleasedDragThisFrame { start_x: f64, start_y: f64 },
 }
 
+/// Where and when a button was last clicked, to detect a following double click
+#[derive(Debug, Clone, Copy)]
+pub struct LastClick {
+    pub time: f64,
+    pub x: f64,
+    pub y: f64,
+}
+
 impl MouseButtonState {
     fn new() -> Self {
         Self::Up
@@ -112,7 +126,9 @@ impl MouseButtonState {
     pub fn was_released_this_frame(&self) -> bool {
         matches!(
             self,
-            Self::ReleasedPressThisFrame | Self::ReleasedDragThisFrame { .. }
+            Self::ReleasedPressThisFrame
+                | Self::ReleasedDoubleClickThisFrame
+                | Self::ReleasedDragThisFrame { .. }
         )
     }
 
@@ -155,14 +171,23 @@ impl MouseButtonState {
         }
     }
 
+    /// True for every click, including the second click of a double click
     pub fn clicked(&self) -> bool {
-        matches!(self, Self::ReleasedPressThisFrame)
+        matches!(
+            self,
+            Self::ReleasedPressThisFrame | Self::ReleasedDoubleClickThisFrame
+        )
+    }
+
+    pub fn double_clicked(&self) -> bool {
+        matches!(self, Self::ReleasedDoubleClickThisFrame)
     }
 
     fn update(&mut self, x: f64, y: f64) {
         const MIN_DISTANCE_2_FOR_DRAG: f64 = 3.0 * 3.0;
         *self = match *self {
             Self::ReleasedPressThisFrame => Self::Up,
+            Self::ReleasedDoubleClickThisFrame => Self::Up,
             Self::ReleasedDragThisFrame { .. } => Self::Up,
             Self::PressedThisFrame { start_x, start_y } | Self::Down { start_x, start_y } => {
                 let dx = x - start_x;
@@ -181,8 +206,10 @@ impl MouseButtonState {
 
 pub struct MouseButtonStateMut<'a> {
     state: &'a mut MouseButtonState,
+    last_click: &'a mut Option<LastClick>,
     x: f64,
     y: f64,
+    time: f64,
 }
 
 impl MouseButtonStateMut<'_> {
@@ -190,6 +217,33 @@ impl MouseButtonStateMut<'_> {
         self.state.press(self.x, self.y);
     }
 
+    /// Releases the button, turning a click that closely follows the previous one into a double click
+    pub fn release(&mut self) {
+        self.state.release();
+        if !self.state.clicked() {
+            return;
+        }
+
+        let is_double_click = self.last_click.is_some_and(|last_click| {
+            let dx = self.x - last_click.x;
+            let dy = self.y - last_click.y;
+            self.time - last_click.time <= DOUBLE_CLICK_TIME
+                && dx * dx + dy * dy <= MAX_DISTANCE_2_FOR_DOUBLE_CLICK
+        });
+
+        if is_double_click {
+            // a third click starts a new pair instead of double clicking again
+            *self.state = MouseButtonState::ReleasedDoubleClickThisFrame;
+            *self.last_click = None;
+        } else {
+            *self.last_click = Some(LastClick {
+                time: self.time,
+                x: self.x,
+                y: self.y,
+            });
+        }
+    }
+
     pub fn update(&mut self) {
         self.state.update(self.x, self.y);
     }
@@ -217,6 +271,9 @@ pub struct Mouse {
     pub delta_scroll_x: f64,
     pub delta_scroll_y: f64,
     pub buttons: HashMap<MouseButton, MouseButtonState>,
+    pub last_clicks: HashMap<MouseButton, Option<LastClick>>,
+    /// Seconds since startup as of the last update, used to time double clicks
+    pub time: f64,
 }
 
 impl Mouse {
@@ -229,6 +286,8 @@ impl Mouse {
             delta_scroll_x: 0.0,
             delta_scroll_y: 0.0,
             buttons: HashMap::new(),
+            last_clicks: HashMap::new(),
+            time: 0.0,
         }
     }
 
@@ -241,10 +300,13 @@ impl Mouse {
             .buttons
             .entry(button)
             .or_insert(MouseButtonState::new());
+        let last_click = self.last_clicks.entry(button).or_insert(None);
         MouseButtonStateMut {
             state,
+            last_click,
             x: self.x,
             y: self.y,
+            time: self.time,
         }
     }
 
@@ -255,12 +317,14 @@ impl Mouse {
         self.y = y;
     }
 
-    pub fn set_scroll(&mut self, scroll_x: f64, scroll_y: f64) {
-        self.delta_scroll_x = scroll_x;
-        self.delta_scroll_y = scroll_y;
+    /// Adds to this frame's scroll, so several wheel events in one frame are summed
+    pub fn add_scroll(&mut self, scroll_x: f64, scroll_y: f64) {
+        self.delta_scroll_x += scroll_x;
+        self.delta_scroll_y += scroll_y;
     }
 
-    pub fn update(&mut self) {
+    pub fn update(&mut self, time: f64) {
+        self.time = time;
         self.delta_x = 0.0;
         self.delta_y = 0.0;
         self.delta_scroll_x = 0.0;
@@ -286,8 +350,64 @@ impl Input {
         }
     }
 
-    pub fn update(&mut self) {
-        self.mouse.update();
+    /// Advances input to the next frame. `time` is the total time in seconds from `Time`.
+    pub fn update(&mut self, time: f64) {
+        self.mouse.update(time);
         self.keyboard.update();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Presses and releases the left button in one frame, then advances to `time`.
+    /// Returns whether the release was a double click.
+    fn click_then_update(mouse: &mut Mouse, time: f64) -> bool {
+        let mut button = mouse.get_or_insert_button(MouseButton::Left);
+        button.press();
+        button.release();
+        let double_clicked = button.double_clicked();
+        mouse.update(time);
+        double_clicked
+    }
+
+    #[test]
+    fn quick_second_click_is_a_double_click() {
+        let mut mouse = Mouse::new();
+
+        assert!(!click_then_update(&mut mouse, 0.1));
+        assert!(click_then_update(&mut mouse, 0.2));
+        // the third click starts a new pair
+        assert!(!click_then_update(&mut mouse, 0.3));
+    }
+
+    #[test]
+    fn slow_second_click_is_a_single_click() {
+        let mut mouse = Mouse::new();
+
+        assert!(!click_then_update(&mut mouse, 0.5));
+        assert!(!click_then_update(&mut mouse, 0.6));
+    }
+
+    #[test]
+    fn moving_between_clicks_cancels_the_double_click() {
+        let mut mouse = Mouse::new();
+
+        assert!(!click_then_update(&mut mouse, 0.1));
+        mouse.set_position(20.0, 0.0);
+        assert!(!click_then_update(&mut mouse, 0.2));
+    }
+
+    #[test]
+    fn wheel_events_in_one_frame_are_summed() {
+        let mut mouse = Mouse::new();
+
+        mouse.add_scroll(0.0, 1.0);
+        mouse.add_scroll(0.5, 2.0);
+        assert_eq!((mouse.delta_scroll_x, mouse.delta_scroll_y), (0.5, 3.0));
+
+        mouse.update(0.1);
+        assert_eq!((mouse.delta_scroll_x, mouse.delta_scroll_y), (0.0, 0.0));
+    }
+}
@@ -1,6 +1,15 @@
 pub mod apc_resources;
+pub mod bloom_settings;
+pub mod clipboard_resources;
+pub mod depth_prepass;
 pub mod http_resources;
 pub mod input;
+pub mod model_uniform_arena;
+pub mod post_process_settings;
+pub mod render_size_limit;
 pub mod screen_parameters;
+pub mod spatial_grid;
 pub mod time;
+pub mod timers;
+pub mod window_title;
 
@@ -0,0 +1,228 @@
+use std::{
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+use bevy_ecs::system::Resource;
+use encase::ShaderType;
+
+use crate::gpu_resources::{
+    layouts::model_uniform_layout::ModelUniformLayout, types::gpu_model::GpuModel,
+};
+
+const INITIAL_CAPACITY: u32 = 256;
+
+/// Holds every entity's model uniform in one buffer. Each entity owns a slot aligned to
+/// `min_uniform_buffer_offset_alignment`, and all of them share a single bind group that
+/// sub-renderers bind with the slot's dynamic offset.
+/// Writes go to a CPU copy of the buffer and `flush` uploads every changed slot at once,
+/// so moving thousands of entities costs one `write_buffer` instead of one per entity.
+#[derive(Resource)]
+pub struct ModelUniformArena {
+    pub bind_group: wgpu::BindGroup,
+    buffer: wgpu::Buffer,
+    /// CPU copy of `buffer`, the source of every upload
+    contents: Vec<u8>,
+    /// Byte range of `contents` written since the last flush
+    dirty: Option<Range<u64>>,
+    /// Slot writes since the arena was created
+    write_count: u64,
+    stride: u64,
+    capacity: u32,
+    next_slot: u32,
+    free_slots: Arc<Mutex<Vec<u32>>>,
+}
+
+/// A slot in the `ModelUniformArena`, handed back for reuse when dropped
+#[derive(Debug)]
+pub struct ModelSlot {
+    index: u32,
+    offset: u32,
+    free_slots: Arc<Mutex<Vec<u32>>>,
+}
+
+impl ModelSlot {
+    /// Dynamic offset to pass to `set_bind_group`
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+}
+
+impl Drop for ModelSlot {
+    fn drop(&mut self) {
+        if let Ok(mut free_slots) = self.free_slots.lock() {
+            free_slots.push(self.index);
+        }
+    }
+}
+
+impl ModelUniformArena {
+    pub fn new(device: &wgpu::Device, layout: &ModelUniformLayout) -> Self {
+        let stride = Self::aligned_stride(device.limits().min_uniform_buffer_offset_alignment);
+        let buffer = Self::create_buffer(device, stride, INITIAL_CAPACITY);
+        let bind_group = layout.create_bind_group(device, &buffer);
+
+        Self {
+            bind_group,
+            buffer,
+            contents: vec![0; (stride * INITIAL_CAPACITY as u64) as usize],
+            dirty: None,
+            write_count: 0,
+            stride,
+            capacity: INITIAL_CAPACITY,
+            next_slot: 0,
+            free_slots: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Size of one model uniform rounded up to the device's offset alignment
+    pub fn aligned_stride(alignment: u32) -> u64 {
+        let alignment = alignment.max(1) as u64;
+        GpuModel::min_size().get().div_ceil(alignment) * alignment
+    }
+
+    pub fn stride(&self) -> u64 {
+        self.stride
+    }
+
+    /// Number of `write` calls so far, e.g. to check that static entities upload nothing
+    pub fn write_count(&self) -> u64 {
+        self.write_count
+    }
+
+    /// Reserves a slot, reusing freed ones first and growing the buffer when full
+    pub fn allocate(&mut self, device: &wgpu::Device, layout: &ModelUniformLayout) -> ModelSlot {
+        let freed = self
+            .free_slots
+            .lock()
+            .ok()
+            .and_then(|mut slots| slots.pop());
+        let index = match freed {
+            Some(index) => index,
+            None => {
+                if self.next_slot == self.capacity {
+                    self.grow(device, layout, self.capacity * 2);
+                }
+                self.next_slot += 1;
+                self.next_slot - 1
+            }
+        };
+
+        ModelSlot {
+            index,
+            offset: (index as u64 * self.stride) as u32,
+            free_slots: self.free_slots.clone(),
+        }
+    }
+
+    /// Stores a model uniform in its slot, uploaded by the next `flush`
+    pub fn write(&mut self, slot: &ModelSlot, data: &[u8]) {
+        let start = slot.offset as u64;
+        let end = start + data.len() as u64;
+        self.contents[start as usize..end as usize].copy_from_slice(data);
+        self.mark_dirty(start..end);
+        self.write_count += 1;
+    }
+
+    /// Uploads every slot written since the last flush in a single `write_buffer`
+    pub fn flush(&mut self, queue: &wgpu::Queue) {
+        if let Some(dirty) = self.dirty.take() {
+            queue.write_buffer(
+                &self.buffer,
+                dirty.start,
+                &self.contents[dirty.start as usize..dirty.end as usize],
+            );
+        }
+    }
+
+    fn mark_dirty(&mut self, range: Range<u64>) {
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.start.min(range.start)..dirty.end.max(range.end),
+            None => range,
+        });
+    }
+
+    /// Moves to a larger buffer. Offsets stay the same, only the shared bind group is replaced.
+    /// The slots in use are uploaded again from the CPU copy by the next flush.
+    fn grow(&mut self, device: &wgpu::Device, layout: &ModelUniformLayout, capacity: u32) {
+        let buffer = Self::create_buffer(device, self.stride, capacity);
+
+        self.contents
+            .resize((self.stride * capacity as u64) as usize, 0);
+        self.mark_dirty(0..self.stride * self.next_slot as u64);
+
+        self.bind_group = layout.create_bind_group(device, &buffer);
+        self.buffer = buffer;
+        self.capacity = capacity;
+    }
+
+    fn create_buffer(device: &wgpu::Device, stride: u64, capacity: u32) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Model Arena Buffer"),
+            size: stride * capacity as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_device::request_device;
+
+    #[test]
+    fn stride_rounds_the_model_size_up_to_the_alignment() {
+        let model_size = GpuModel::min_size().get();
+        for alignment in [1, 64, 256] {
+            let stride = ModelUniformArena::aligned_stride(alignment);
+            assert_eq!(stride % alignment as u64, 0);
+            assert!(stride >= model_size && stride < model_size + alignment as u64);
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn slots_keep_their_offsets_through_growth_and_are_reused() {
+        let (device, _queue) = request_device();
+        let layout = ModelUniformLayout::new(&device);
+        let mut arena = ModelUniformArena::new(&device, &layout);
+
+        let mut slots: Vec<_> = (0..=INITIAL_CAPACITY)
+            .map(|_| arena.allocate(&device, &layout))
+            .collect();
+        for (index, slot) in slots.iter().enumerate() {
+            assert_eq!(slot.offset() as u64, index as u64 * arena.stride());
+        }
+
+        let freed_offset = slots.swap_remove(3).offset();
+        assert_eq!(arena.allocate(&device, &layout).offset(), freed_offset);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn every_write_since_the_last_flush_is_one_upload() {
+        let (device, queue) = request_device();
+        let layout = ModelUniformLayout::new(&device);
+        let mut arena = ModelUniformArena::new(&device, &layout);
+        let slots: Vec<_> = (0..1000)
+            .map(|_| arena.allocate(&device, &layout))
+            .collect();
+        let model = vec![1; GpuModel::min_size().get() as usize];
+
+        // 1000 moving entities
+        for slot in &slots {
+            arena.write(slot, &model);
+        }
+        let last_end = slots[999].offset() as u64 + model.len() as u64;
+        assert_eq!(arena.dirty, Some(0..last_end));
+        arena.flush(&queue);
+        assert_eq!(arena.dirty, None);
+
+        // only the span between the moved entities is uploaded
+        arena.write(&slots[20], &model);
+        arena.write(&slots[10], &model);
+        let end = slots[20].offset() as u64 + model.len() as u64;
+        assert_eq!(arena.dirty, Some(slots[10].offset() as u64..end));
+    }
+}
@@ -0,0 +1,22 @@
+use bevy_ecs::system::Resource;
+
+use crate::utils::tonemap::TonemapOperator;
+
+/// Settings for the post-process stack that turns the HDR scene target into the final image.
+/// With `enabled` off the HDR target is copied to the output unchanged.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct PostProcessSettings {
+    pub enabled: bool,
+    pub tonemap: TonemapOperator,
+    pub exposure: f32,
+}
+
+impl Default for PostProcessSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tonemap: TonemapOperator::default(),
+            exposure: 1.0,
+        }
+    }
+}
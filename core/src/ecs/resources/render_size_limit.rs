@@ -0,0 +1,60 @@
+use bevy_ecs::system::Resource;
+
+/// Caps the size of the render targets. The device's `max_texture_dimension_2d` is always
+/// respected, and `max_dimension` can lower it further, e.g. to save fill rate on 4K displays.
+#[derive(Resource, Default)]
+pub struct RenderSizeLimit {
+    pub max_dimension: Option<u32>,
+}
+
+impl RenderSizeLimit {
+    /// The largest width or height a render target may have on a device with the given limit
+    pub fn max_dimension(&self, device_limit: u32) -> u32 {
+        self.max_dimension
+            .map_or(device_limit, |max_dimension| {
+                max_dimension.min(device_limit)
+            })
+            .max(1)
+    }
+
+    /// Clamps a requested size into `1..=max_dimension` on both axes
+    pub fn clamp(&self, device_limit: u32, width: u32, height: u32) -> (u32, u32) {
+        let max_dimension = self.max_dimension(device_limit);
+        (
+            width.clamp(1, max_dimension),
+            height.clamp(1, max_dimension),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_limit_applies_without_a_configured_limit() {
+        let limit = RenderSizeLimit::default();
+
+        assert_eq!(limit.clamp(8192, 3840, 2160), (3840, 2160));
+        assert_eq!(limit.clamp(2048, 3840, 2160), (2048, 2048));
+    }
+
+    #[test]
+    fn configured_limit_only_lowers_the_device_limit() {
+        let limit = RenderSizeLimit {
+            max_dimension: Some(1920),
+        };
+
+        assert_eq!(limit.clamp(8192, 3840, 1080), (1920, 1080));
+        assert_eq!(limit.clamp(1024, 3840, 1080), (1024, 1024));
+    }
+
+    #[test]
+    fn zero_sizes_become_one_pixel() {
+        let limit = RenderSizeLimit {
+            max_dimension: Some(0),
+        };
+
+        assert_eq!(limit.clamp(8192, 0, 600), (1, 1));
+    }
+}
@@ -1,18 +1,74 @@
 use bevy_ecs::system::Resource;
+use glam::Vec2;
 
+/// Size of the render target in physical pixels and the display's DPI scale
 #[derive(Resource)]
 pub struct ScreenParameters {
     pub width: u32,
     pub height: u32,
+    /// Physical pixels per logical pixel, e.g. 2.0 on a high-DPI display
+    pub scale_factor: f64,
 }
 
 impl ScreenParameters {
     pub fn new(width: u32, height: u32) -> Self {
-        Self { width, height }
+        Self {
+            width,
+            height,
+            scale_factor: 1.0,
+        }
     }
 
     pub fn set_size(&mut self, width: u32, height: u32) {
         self.width = width;
         self.height = height;
     }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor;
+    }
+
+    /// The screen size in pixels
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// The screen size in pixels as a vector
+    pub fn size_vec2(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    /// The screen size in logical pixels, for laying out UI independent of DPI
+    pub fn logical_size(&self) -> Vec2 {
+        self.size_vec2() / self.scale_factor as f32
+    }
+
+    /// Width over height, 1 while the screen has no height
+    pub fn aspect_ratio(&self) -> f32 {
+        if self.height == 0 {
+            1.0
+        } else {
+            (self.width as f64 / self.height as f64) as f32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aspect_ratio_is_width_over_height() {
+        assert_eq!(ScreenParameters::new(1920, 1080).aspect_ratio(), 16.0 / 9.0);
+        assert_eq!(ScreenParameters::new(800, 0).aspect_ratio(), 1.0);
+    }
+
+    #[test]
+    fn logical_size_divides_out_the_scale_factor() {
+        let mut screen_parameters = ScreenParameters::new(2560, 1440);
+        screen_parameters.set_scale_factor(2.0);
+
+        assert_eq!(screen_parameters.size(), (2560, 1440));
+        assert_eq!(screen_parameters.logical_size(), Vec2::new(1280.0, 720.0));
+    }
 }
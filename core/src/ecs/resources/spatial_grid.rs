@@ -0,0 +1,216 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy_ecs::{entity::Entity, system::Resource};
+use glam::{IVec3, Vec3};
+
+use crate::utils::{aabb::Aabb, frustum::Frustum};
+
+/// Edge length of a grid cell in world units, used by `SpatialGrid::default`
+pub const DEFAULT_CELL_SIZE: f32 = 4.0;
+
+/// A uniform grid indexing entities by their world space bounding box, as a broad phase for
+/// frustum culling and picking. Entities are added to every cell their box overlaps.
+/// The grid is rebuilt every frame by `update_spatial_grid_system` from entities with a `MeshCollider`.
+#[derive(Resource)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec3, Vec<Entity>>,
+    entity_bounds: HashMap<Entity, Aabb>,
+    /// Box around every indexed entity, for clipping rays
+    bounds: Option<Aabb>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entity_bounds: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entity_bounds.clear();
+        self.bounds = None;
+    }
+
+    /// Indexes `entity` by its world space bounding box
+    pub fn insert(&mut self, entity: Entity, aabb: Aabb) {
+        let min_cell = self.cell_of(aabb.min);
+        let max_cell = self.cell_of(aabb.max);
+
+        for x in min_cell.x..=max_cell.x {
+            for y in min_cell.y..=max_cell.y {
+                for z in min_cell.z..=max_cell.z {
+                    self.cells
+                        .entry(IVec3::new(x, y, z))
+                        .or_default()
+                        .push(entity);
+                }
+            }
+        }
+
+        self.entity_bounds.insert(entity, aabb);
+        self.bounds = Some(match self.bounds {
+            Some(bounds) => bounds.union(&aabb),
+            None => aabb,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entity_bounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entity_bounds.is_empty()
+    }
+
+    /// Whether the entity is indexed. Entities that aren't can't be culled by the grid.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entity_bounds.contains_key(&entity)
+    }
+
+    /// The world space bounding box the entity was indexed with
+    pub fn entity_bounds(&self, entity: Entity) -> Option<Aabb> {
+        self.entity_bounds.get(&entity).copied()
+    }
+
+    /// Entities whose bounding box may be inside the frustum
+    pub fn query_frustum(&self, frustum: &Frustum) -> HashSet<Entity> {
+        let mut result = HashSet::new();
+
+        for (cell, entities) in &self.cells {
+            if !frustum.intersects_aabb(&self.cell_bounds(*cell)) {
+                continue;
+            }
+
+            for entity in entities {
+                if !result.contains(entity) && frustum.intersects_aabb(&self.entity_bounds[entity])
+                {
+                    result.insert(*entity);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Entities whose bounding box is hit by the ray, nearest box first.
+    /// The cells along the ray are walked with a 3D DDA, so only entities near the ray are tested.
+    pub fn query_ray(&self, origin: Vec3, direction: Vec3) -> Vec<Entity> {
+        let Some(bounds) = self.bounds else {
+            return Vec::new();
+        };
+        let Some((enter, exit)) = bounds.ray_intersect(origin, direction) else {
+            return Vec::new();
+        };
+
+        let start = origin + direction * enter;
+        let mut cell = self.cell_of(start);
+        let last_cell = self.cell_of(origin + direction * exit);
+
+        let step = direction.signum().as_ivec3();
+        let cell_size_in_t = self.cell_size / direction.abs();
+        // distance along the ray to the first boundary crossed on each axis
+        let next_boundary = (cell + step.max(IVec3::ZERO)).as_vec3() * self.cell_size;
+        let mut next_t = Vec3::select(
+            direction.cmpeq(Vec3::ZERO),
+            Vec3::INFINITY,
+            (next_boundary - start) / direction,
+        ) + enter;
+
+        let mut seen = HashSet::new();
+        let mut hits = Vec::new();
+        // every step moves one cell closer to the last cell on one axis
+        let cells_apart = (last_cell - cell).abs();
+        let max_steps = cells_apart.x + cells_apart.y + cells_apart.z + 1;
+
+        for _ in 0..max_steps {
+            for entity in self.cells.get(&cell).into_iter().flatten() {
+                if !seen.insert(*entity) {
+                    continue;
+                }
+                if let Some((distance, _)) =
+                    self.entity_bounds[entity].ray_intersect(origin, direction)
+                {
+                    hits.push((*entity, distance));
+                }
+            }
+
+            let axis = if next_t.x <= next_t.y && next_t.x <= next_t.z {
+                0
+            } else if next_t.y <= next_t.z {
+                1
+            } else {
+                2
+            };
+            if next_t[axis] > exit {
+                break;
+            }
+            cell[axis] += step[axis];
+            next_t[axis] += cell_size_in_t[axis];
+        }
+
+        hits.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        hits.into_iter().map(|(entity, _)| entity).collect()
+    }
+
+    fn cell_of(&self, point: Vec3) -> IVec3 {
+        (point / self.cell_size).floor().as_ivec3()
+    }
+
+    fn cell_bounds(&self, cell: IVec3) -> Aabb {
+        let min = cell.as_vec3() * self.cell_size;
+        Aabb::new(min, min + Vec3::splat(self.cell_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::world::World;
+    use glam::Mat4;
+
+    use super::*;
+
+    fn unit_box(center: Vec3) -> Aabb {
+        Aabb::new(center - Vec3::splat(0.5), center + Vec3::splat(0.5))
+    }
+
+    #[test]
+    fn frustum_query_only_returns_entities_in_view() {
+        let mut world = World::new();
+        let [in_view, behind, off_to_the_side] = [(); 3].map(|_| world.spawn_empty().id());
+
+        let mut grid = SpatialGrid::default();
+        grid.insert(in_view, unit_box(Vec3::new(0.0, 0.0, 10.0)));
+        grid.insert(behind, unit_box(Vec3::new(0.0, 0.0, -10.0)));
+        grid.insert(off_to_the_side, unit_box(Vec3::new(100.0, 0.0, 10.0)));
+
+        // a camera at the origin looking down +Z
+        let projection = Mat4::perspective_lh(60.0_f32.to_radians(), 1.0, 0.1, 50.0);
+        let visible = grid.query_frustum(&Frustum::from_view_proj(projection));
+
+        assert_eq!(visible, HashSet::from([in_view]));
+    }
+
+    #[test]
+    fn ray_query_returns_hit_entities_nearest_first() {
+        let mut world = World::new();
+        let [near, far, missed] = [(); 3].map(|_| world.spawn_empty().id());
+
+        let mut grid = SpatialGrid::default();
+        grid.insert(far, unit_box(Vec3::new(0.0, 0.0, 30.0)));
+        grid.insert(near, unit_box(Vec3::new(0.0, 0.0, 5.0)));
+        grid.insert(missed, unit_box(Vec3::new(0.0, 10.0, 5.0)));
+
+        assert_eq!(grid.query_ray(Vec3::ZERO, Vec3::Z), vec![near, far]);
+    }
+}
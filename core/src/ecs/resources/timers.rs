@@ -0,0 +1,242 @@
+use bevy_ecs::{system::Resource, world::World};
+
+/// Identifies a timer so it can be cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+enum TimerCallback {
+    Once(Box<dyn FnOnce(&mut World) + Send + Sync>),
+    Repeating(Box<dyn FnMut(&mut World) + Send + Sync>),
+}
+
+struct Timer {
+    id: TimerId,
+    /// Seconds left until the callback fires
+    remaining: f32,
+    /// Seconds between firings of a repeating timer
+    interval: f32,
+    callback: TimerCallback,
+}
+
+/// Runs callbacks after a delay or on an interval, advanced by `Time::delta_time` every update.
+/// Callbacks get the whole world, so they can spawn, despawn or schedule further timers.
+#[derive(Resource, Default)]
+pub struct Timers {
+    timers: Vec<Timer>,
+    next_id: u64,
+    /// Timers cancelled while their callbacks were running, so they aren't put back
+    cancelled: Vec<TimerId>,
+}
+
+impl Timers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `callback` once, `delay` seconds from now
+    pub fn after(
+        &mut self,
+        delay: f32,
+        callback: impl FnOnce(&mut World) + Send + Sync + 'static,
+    ) -> TimerId {
+        self.push(delay, delay, TimerCallback::Once(Box::new(callback)))
+    }
+
+    /// Calls `callback` every `interval` seconds until cancelled
+    pub fn every(
+        &mut self,
+        interval: f32,
+        callback: impl FnMut(&mut World) + Send + Sync + 'static,
+    ) -> TimerId {
+        self.push(
+            interval,
+            interval,
+            TimerCallback::Repeating(Box::new(callback)),
+        )
+    }
+
+    /// Stops a timer, which may be called from the timer's own callback.
+    /// Returns false if it wasn't pending, e.g. a one-shot timer that already fired.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let count = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        if self.timers.len() == count {
+            self.cancelled.push(id);
+            return false;
+        }
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    fn push(&mut self, remaining: f32, interval: f32, callback: TimerCallback) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        self.timers.push(Timer {
+            id,
+            remaining,
+            interval,
+            callback,
+        });
+        id
+    }
+
+    /// Advances every timer and removes the ones that are due
+    fn take_due(&mut self, delta_time: f32) -> Vec<Timer> {
+        for timer in self.timers.iter_mut() {
+            timer.remaining -= delta_time;
+        }
+
+        let (due, pending) = std::mem::take(&mut self.timers)
+            .into_iter()
+            .partition(|timer| timer.remaining <= 0.0);
+        self.timers = pending;
+        due
+    }
+}
+
+/// Fires due timers. Due timers are taken out of `Timers` before their callbacks run,
+/// so the callbacks can use the resource themselves.
+pub(crate) fn run_timers(world: &mut World, delta_time: f32) {
+    let due = world.resource_mut::<Timers>().take_due(delta_time);
+
+    for mut timer in due {
+        // an earlier callback in this batch may have cancelled the timer
+        if is_cancelled(world, timer.id) {
+            continue;
+        }
+
+        match timer.callback {
+            TimerCallback::Once(callback) => callback(world),
+            TimerCallback::Repeating(ref mut callback) => {
+                // catch up on every interval that passed, e.g. after a long frame
+                while timer.remaining <= 0.0 && !is_cancelled(world, timer.id) {
+                    callback(world);
+                    timer.remaining += timer.interval.max(f32::EPSILON);
+                }
+
+                if !is_cancelled(world, timer.id) {
+                    world.resource_mut::<Timers>().timers.push(timer);
+                }
+            }
+        }
+    }
+
+    world.resource_mut::<Timers>().cancelled.clear();
+}
+
+fn is_cancelled(world: &World, id: TimerId) -> bool {
+    world.resource::<Timers>().cancelled.contains(&id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Calls(u32);
+
+    fn world_with_timers() -> World {
+        let mut world = World::new();
+        world.insert_resource(Timers::new());
+        world.init_resource::<Calls>();
+        world
+    }
+
+    fn count_call(world: &mut World) {
+        world.resource_mut::<Calls>().0 += 1;
+    }
+
+    #[test]
+    fn after_fires_once_when_the_delay_has_passed() {
+        let mut world = world_with_timers();
+        world.resource_mut::<Timers>().after(0.5, count_call);
+
+        run_timers(&mut world, 0.3);
+        assert_eq!(world.resource::<Calls>().0, 0);
+        run_timers(&mut world, 0.3);
+        assert_eq!(world.resource::<Calls>().0, 1);
+        run_timers(&mut world, 1.0);
+        assert_eq!(world.resource::<Calls>().0, 1);
+        assert!(world.resource::<Timers>().is_empty());
+    }
+
+    #[test]
+    fn every_fires_each_interval_and_catches_up_after_long_frames() {
+        let mut world = world_with_timers();
+        world.resource_mut::<Timers>().every(0.25, count_call);
+
+        for _ in 0..4 {
+            run_timers(&mut world, 0.125);
+        }
+        assert_eq!(world.resource::<Calls>().0, 2);
+
+        run_timers(&mut world, 1.0);
+        assert_eq!(world.resource::<Calls>().0, 6);
+    }
+
+    #[derive(Resource)]
+    struct RepeatingId(TimerId);
+
+    #[test]
+    fn repeating_timer_can_cancel_itself() {
+        let mut world = world_with_timers();
+        let id = world.resource_mut::<Timers>().every(0.25, |world| {
+            count_call(world);
+            if world.resource::<Calls>().0 == 2 {
+                let id = world.resource::<RepeatingId>().0;
+                world.resource_mut::<Timers>().cancel(id);
+            }
+        });
+        world.insert_resource(RepeatingId(id));
+
+        for _ in 0..8 {
+            run_timers(&mut world, 0.25);
+        }
+        assert_eq!(world.resource::<Calls>().0, 2);
+        assert!(world.resource::<Timers>().is_empty());
+    }
+
+    #[test]
+    fn repeating_timer_cancelled_while_catching_up_stops_firing() {
+        let mut world = world_with_timers();
+        let id = world.resource_mut::<Timers>().every(0.25, |world| {
+            count_call(world);
+            if world.resource::<Calls>().0 == 2 {
+                let id = world.resource::<RepeatingId>().0;
+                world.resource_mut::<Timers>().cancel(id);
+            }
+        });
+        world.insert_resource(RepeatingId(id));
+
+        // one long frame covers four intervals
+        run_timers(&mut world, 1.0);
+        assert_eq!(world.resource::<Calls>().0, 2);
+        assert!(world.resource::<Timers>().is_empty());
+    }
+
+    #[derive(Resource)]
+    struct OneShotId(TimerId);
+
+    #[test]
+    fn one_shot_cancelled_earlier_in_the_same_batch_does_not_fire() {
+        let mut world = world_with_timers();
+        world.resource_mut::<Timers>().after(0.1, |world| {
+            let id = world.resource::<OneShotId>().0;
+            world.resource_mut::<Timers>().cancel(id);
+        });
+        let id = world.resource_mut::<Timers>().after(0.2, count_call);
+        world.insert_resource(OneShotId(id));
+
+        // both are due in the same frame, and the first cancels the second
+        run_timers(&mut world, 0.5);
+        assert_eq!(world.resource::<Calls>().0, 0);
+        assert!(world.resource::<Timers>().is_empty());
+    }
+}
@@ -0,0 +1,8 @@
+use bevy_ecs::system::Resource;
+
+/// A window title change requested by the core or its systems, e.g. to show the frame rate.
+/// The windowing layer takes the pending title after each update and applies it.
+#[derive(Resource, Default)]
+pub struct WindowTitle {
+    pub pending: Option<String>,
+}
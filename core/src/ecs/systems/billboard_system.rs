@@ -0,0 +1,89 @@
+use bevy_ecs::{
+    query::{With, Without},
+    system::Query,
+};
+use glam::{Quat, Vec3};
+
+use crate::ecs::components::{
+    billboard::{Billboard, BillboardMode},
+    camera::Camera,
+    transform::Transform,
+};
+
+/// Turns every billboard's `Transform` to face the camera.
+/// Runs in pre_render after the camera has moved and before model bindings are uploaded.
+pub fn billboard_system(
+    camera_query: Query<&Transform, With<Camera>>,
+    mut billboard_query: Query<(&mut Transform, &Billboard), Without<Camera>>,
+) {
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+    let camera_position = camera_transform.translation;
+    let camera_up = camera_transform.up();
+
+    for (mut transform, billboard) in billboard_query.iter_mut() {
+        let to_camera = camera_position - transform.translation;
+
+        match billboard.mode {
+            BillboardMode::Spherical => {
+                if to_camera.length_squared() > f32::EPSILON {
+                    // the camera's up avoids a degenerate basis when looking straight down
+                    transform.look_at(camera_position, camera_up);
+                }
+            }
+            BillboardMode::Cylindrical => {
+                let flat = Vec3::new(to_camera.x, 0.0, to_camera.z);
+                if flat.length_squared() > f32::EPSILON {
+                    let yaw = flat.x.atan2(flat.z);
+                    transform.set_rotation(Quat::from_rotation_y(yaw));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{schedule::Schedule, world::World};
+
+    fn face_camera(mode: BillboardMode, camera_position: Vec3) -> Transform {
+        let mut world = World::new();
+        let mut camera_transform = Transform::from_translation(camera_position);
+        camera_transform.look_at(Vec3::ZERO, Vec3::Y);
+        world.spawn((camera_transform, Camera::default()));
+        let billboard = world
+            .spawn((
+                Transform::from_translation(Vec3::new(1.0, 0.0, 2.0)),
+                Billboard { mode },
+            ))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(billboard_system);
+        schedule.run(&mut world);
+
+        *world.get::<Transform>(billboard).unwrap()
+    }
+
+    #[test]
+    fn spherical_billboard_points_at_the_camera() {
+        let camera_position = Vec3::new(4.0, 6.0, -3.0);
+        let transform = face_camera(BillboardMode::Spherical, camera_position);
+
+        let to_camera = (camera_position - transform.translation).normalize();
+        assert!(transform.forward().abs_diff_eq(to_camera, 1e-5));
+    }
+
+    #[test]
+    fn cylindrical_billboard_stays_upright() {
+        let camera_position = Vec3::new(4.0, 6.0, -3.0);
+        let transform = face_camera(BillboardMode::Cylindrical, camera_position);
+
+        let to_camera = camera_position - transform.translation;
+        let flat_to_camera = Vec3::new(to_camera.x, 0.0, to_camera.z).normalize();
+        assert!(transform.forward().abs_diff_eq(flat_to_camera, 1e-5));
+        assert!(transform.up().abs_diff_eq(Vec3::Y, 1e-5));
+    }
+}
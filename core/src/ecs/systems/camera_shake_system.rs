@@ -0,0 +1,108 @@
+use bevy_ecs::system::{Query, Res};
+use glam::{EulerRot, Quat, Vec3};
+
+use crate::{
+    ecs::{
+        components::{camera_shake::CameraShake, transform::Transform},
+        resources::time::Time,
+    },
+    utils::noise::perlin_2d,
+};
+
+/// Undoes last frame's shake so controllers see the unshaken transform. Runs in early update.
+pub fn remove_camera_shake_system(mut query: Query<(&mut Transform, &mut CameraShake)>) {
+    for (mut transform, mut shake) in query.iter_mut() {
+        if shake.applied_translation == Vec3::ZERO && shake.applied_rotation == Quat::IDENTITY {
+            continue;
+        }
+
+        transform.translate(-shake.applied_translation);
+        transform.rotate(shake.applied_rotation.inverse());
+        shake.applied_translation = Vec3::ZERO;
+        shake.applied_rotation = Quat::IDENTITY;
+    }
+}
+
+/// Decays trauma and applies a fresh noise driven offset. Runs in late update, after controllers.
+pub fn camera_shake_system(time: Res<Time>, mut query: Query<(&mut Transform, &mut CameraShake)>) {
+    for (mut transform, mut shake) in query.iter_mut() {
+        shake.trauma = (shake.trauma - shake.decay * time.delta_time).max(0.0);
+        if shake.trauma <= 0.0 {
+            continue;
+        }
+
+        // each axis reads its own row of the noise field so they move independently
+        let t = time.total_time * shake.frequency;
+        let sample = |row: u32| perlin_2d(t, row as f32 * 17.0, row);
+        let strength = shake.shake();
+
+        let translation = shake.max_offset * Vec3::new(sample(0), sample(1), sample(2)) * strength;
+        let angles = shake.max_rotation * Vec3::new(sample(3), sample(4), sample(5)) * strength;
+        let rotation = Quat::from_euler(EulerRot::YXZ, angles.x, angles.y, angles.z);
+
+        transform.translate(translation);
+        transform.rotate(rotation);
+        shake.applied_translation = translation;
+        shake.applied_rotation = rotation;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{
+        entity::Entity,
+        schedule::{IntoSystemConfigs, Schedule},
+        world::World,
+    };
+
+    fn world_with_shaking_camera(base: Transform) -> (World, Schedule, Entity) {
+        let mut world = World::new();
+        world.insert_resource(Time::new());
+        let camera = world.spawn((base, CameraShake::new(1.0, 2.0))).id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems((remove_camera_shake_system, camera_shake_system).chain());
+        (world, schedule, camera)
+    }
+
+    fn step(world: &mut World, schedule: &mut Schedule, delta_time: f32) {
+        world.resource_mut::<Time>().new_frame(delta_time);
+        schedule.run(world);
+    }
+
+    #[test]
+    fn trauma_decays_and_the_base_transform_is_restored() {
+        let base = Transform::from_translation(Vec3::new(1.0, 2.0, 3.0));
+        let (mut world, mut schedule, camera) = world_with_shaking_camera(base);
+
+        step(&mut world, &mut schedule, 0.1);
+        let shake = world.get::<CameraShake>(camera).unwrap();
+        assert!((shake.trauma - 0.8).abs() < 1e-5);
+        let shaken = world.get::<Transform>(camera).unwrap().translation;
+        assert_ne!(shaken, base.translation);
+
+        // 2 trauma per second is gone well within a second
+        for _ in 0..10 {
+            step(&mut world, &mut schedule, 0.1);
+        }
+        assert_eq!(world.get::<CameraShake>(camera).unwrap().trauma, 0.0);
+        let transform = world.get::<Transform>(camera).unwrap();
+        assert!(transform.translation.abs_diff_eq(base.translation, 1e-5));
+        assert!(transform.rotation.abs_diff_eq(base.rotation, 1e-5));
+    }
+
+    #[test]
+    fn early_update_sees_the_unshaken_transform() {
+        let base = Transform::from_translation(Vec3::ZERO);
+        let (mut world, mut schedule, camera) = world_with_shaking_camera(base);
+        step(&mut world, &mut schedule, 0.1);
+
+        let mut early_update = Schedule::default();
+        early_update.add_systems(remove_camera_shake_system);
+        early_update.run(&mut world);
+
+        let transform = world.get::<Transform>(camera).unwrap();
+        assert!(transform.translation.abs_diff_eq(Vec3::ZERO, 1e-6));
+    }
+}
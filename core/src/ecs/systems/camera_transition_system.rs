@@ -0,0 +1,129 @@
+use bevy_ecs::{
+    entity::Entity,
+    system::{Commands, Query},
+};
+
+use crate::ecs::components::{
+    camera::Camera, dolly_zoom::DollyZoom, projection_blend::ProjectionBlend, transform::Transform,
+};
+
+/// Moves each dolly zooming camera to its distance and matches the field of view to it
+pub fn dolly_zoom_system(mut query: Query<(&mut Camera, &mut Transform, &DollyZoom)>) {
+    for (mut camera, mut transform, dolly_zoom) in query.iter_mut() {
+        let position = dolly_zoom.subject - transform.forward() * dolly_zoom.distance;
+        if transform.translation != position {
+            transform.set_position(position);
+        }
+
+        let fov = dolly_zoom.fov();
+        if camera.fov != fov {
+            camera.set_fov(fov);
+        }
+    }
+}
+
+/// Applies projection blends every frame, since any camera parameter change resets the
+/// projection, and finishes the switch once a blend is complete
+pub fn projection_blend_system(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Camera, &ProjectionBlend)>,
+) {
+    for (entity, mut camera, blend) in query.iter_mut() {
+        if blend.t >= 1.0 {
+            camera.set_projection_type(blend.target);
+            commands.entity(entity).remove::<ProjectionBlend>();
+        } else {
+            camera.set_blended_projection(blend.target, blend.t);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{schedule::Schedule, world::World};
+    use glam::Vec3;
+
+    use super::*;
+    use crate::ecs::components::camera::ProjectionType;
+
+    /// Height of a vertical subject centered on `subject` on screen, in normalized device
+    /// coordinates, so 2 spans the whole view
+    fn projected_height(
+        world: &mut World,
+        camera: Entity,
+        subject: Vec3,
+        subject_size: f32,
+    ) -> f32 {
+        let mut entity = world.entity_mut(camera);
+        let view = entity
+            .get_mut::<Transform>()
+            .unwrap()
+            .get_trs_matrix()
+            .inverse();
+        let view_proj = entity.get_mut::<Camera>().unwrap().get_projection_matrix() * view;
+
+        let half_size = Vec3::Y * subject_size / 2.0;
+        let top = view_proj.project_point3(subject + half_size);
+        let bottom = view_proj.project_point3(subject - half_size);
+        top.y - bottom.y
+    }
+
+    #[test]
+    fn dolly_zoom_keeps_the_subject_size() {
+        let subject = Vec3::new(1.0, 2.0, 10.0);
+        let subject_size = 3.0;
+
+        let mut world = World::new();
+        let camera = world
+            .spawn((
+                Camera::default(),
+                Transform::from_translation(Vec3::ZERO),
+                DollyZoom::new(subject, subject_size, 5.0),
+            ))
+            .id();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(dolly_zoom_system);
+
+        for distance in [5.0, 2.0, 40.0] {
+            world.get_mut::<DollyZoom>(camera).unwrap().distance = distance;
+            schedule.run(&mut world);
+
+            let position = world.get::<Transform>(camera).unwrap().translation;
+            assert!((position.distance(subject) - distance).abs() < 1e-4);
+            let height = projected_height(&mut world, camera, subject, subject_size);
+            assert!((height - 2.0).abs() < 1e-4, "{} at {}", height, distance);
+        }
+    }
+
+    #[test]
+    fn finished_projection_blend_switches_the_projection() {
+        let mut world = World::new();
+        let mut camera = Camera::default();
+        let perspective = camera.projection_matrix_for(ProjectionType::Perspective);
+        let orthographic = camera.projection_matrix_for(ProjectionType::Orthographic);
+        camera.get_projection_matrix();
+        let entity = world
+            .spawn((
+                camera,
+                ProjectionBlend {
+                    target: ProjectionType::Orthographic,
+                    t: 0.5,
+                },
+            ))
+            .id();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(projection_blend_system);
+
+        schedule.run(&mut world);
+        let halfway = (perspective + orthographic) * 0.5;
+        let mut camera = world.get_mut::<Camera>(entity).unwrap();
+        assert!(camera.get_projection_matrix().abs_diff_eq(halfway, 1e-6));
+
+        world.get_mut::<ProjectionBlend>(entity).unwrap().t = 1.0;
+        schedule.run(&mut world);
+        assert!(world.get::<ProjectionBlend>(entity).is_none());
+        let mut camera = world.get_mut::<Camera>(entity).unwrap();
+        assert_eq!(camera.projection_type, ProjectionType::Orthographic);
+        assert_eq!(camera.get_projection_matrix(), orthographic);
+    }
+}
@@ -0,0 +1,117 @@
+use bevy_ecs::system::{Query, Res};
+use glam::Vec3;
+use winit::keyboard::KeyCode;
+
+use crate::ecs::{
+    components::{fly_camera_controller::FlyCameraController, transform::Transform},
+    resources::{
+        input::{Input, Keyboard},
+        time::Time,
+    },
+};
+
+/// Turns every enabled fly controller with the mouse, then moves it along its new view for
+/// the keys held this frame. Disabled controllers and entities without one are left alone.
+pub fn update_fly_camera_system(
+    input: Res<Input>,
+    time: Res<Time>,
+    mut query: Query<(&mut Transform, &mut FlyCameraController)>,
+) {
+    let mouse = &input.mouse;
+    let keyboard = &input.keyboard;
+    for (mut transform, mut controller) in query.iter_mut() {
+        if !controller.enabled {
+            continue;
+        }
+
+        let looking = controller
+            .look_button
+            .is_none_or(|button| mouse.get_button(button).is_some_and(|state| state.down()));
+        if looking {
+            let look_speed = controller.look_speed;
+            controller.look(
+                mouse.delta_x as f32 * look_speed,
+                mouse.delta_y as f32 * look_speed,
+            );
+        }
+        transform.set_rotation(controller.rotation());
+
+        let direction = transform.forward() * axis(keyboard, KeyCode::KeyW, KeyCode::KeyS)
+            + transform.right() * axis(keyboard, KeyCode::KeyD, KeyCode::KeyA)
+            + transform.up() * axis(keyboard, KeyCode::KeyE, KeyCode::KeyQ);
+        if direction != Vec3::ZERO {
+            // diagonals move no faster than straight lines
+            transform.translate(direction.normalize() * controller.move_speed * time.delta_time);
+        }
+    }
+}
+
+/// 1 while only `positive` is held, -1 while only `negative` is and 0 otherwise
+fn axis(keyboard: &Keyboard, positive: KeyCode, negative: KeyCode) -> f32 {
+    let held = |key| keyboard.get_key(key).is_some_and(|state| state.is_held()) as i32;
+    (held(positive) - held(negative)) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{schedule::Schedule, world::World};
+
+    /// Holds W for one 0.5 second frame and returns where the controlled entity ends up
+    fn fly_forward_for_a_frame(controller: FlyCameraController) -> Transform {
+        let mut world = World::new();
+        world.insert_resource(Input::new());
+        world.insert_resource(Time::new());
+        let camera = world
+            .spawn((Transform::from_translation(Vec3::ZERO), controller))
+            .id();
+
+        world.resource_mut::<Time>().new_frame(0.5);
+        world
+            .resource_mut::<Input>()
+            .keyboard
+            .get_or_insert_key(KeyCode::KeyW)
+            .press();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_fly_camera_system);
+        schedule.run(&mut world);
+
+        *world.get::<Transform>(camera).unwrap()
+    }
+
+    #[test]
+    fn forward_key_moves_along_the_view() {
+        let controller = FlyCameraController {
+            yaw: 90.0_f32.to_radians(),
+            ..FlyCameraController::new(4.0)
+        };
+
+        let transform = fly_forward_for_a_frame(controller);
+
+        // turned to face +X, moving 4 units a second for half a second
+        assert!(transform.forward().abs_diff_eq(Vec3::X, 1e-5));
+        assert!(
+            transform
+                .translation
+                .abs_diff_eq(Vec3::new(2.0, 0.0, 0.0), 1e-5)
+        );
+    }
+
+    #[test]
+    fn disabled_controller_ignores_input() {
+        let controller = FlyCameraController {
+            enabled: false,
+            yaw: 90.0_f32.to_radians(),
+            ..Default::default()
+        };
+
+        let transform = fly_forward_for_a_frame(controller);
+
+        assert_eq!(transform.translation, Vec3::ZERO);
+        assert_eq!(
+            transform.rotation,
+            Transform::from_translation(Vec3::ZERO).rotation
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use bevy_ecs::{
+    query::{With, Without},
+    system::Query,
+};
+
+use crate::ecs::components::{
+    camera::Camera, lod::Lod, mesh_filter::BasicMeshFilter, transform::Transform,
+};
+
+/// Picks each `Lod` entity's level from its distance to the camera and swaps in that mesh
+pub fn lod_system(
+    camera_query: Query<&Transform, With<Camera>>,
+    mut lod_query: Query<(&Transform, &mut Lod, &mut BasicMeshFilter), Without<Camera>>,
+) {
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+
+    for (transform, mut lod, mut mesh_filter) in lod_query.iter_mut() {
+        let distance = transform.translation.distance(camera_transform.translation);
+        let level = lod.select_level(distance);
+        if level != lod.active_level() {
+            lod.switch_to(level, &mut mesh_filter);
+        }
+    }
+}
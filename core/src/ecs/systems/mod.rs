@@ -1,4 +1,16 @@
+pub mod billboard_system;
+pub mod camera_shake_system;
+pub mod camera_transition_system;
+pub mod fly_camera_system;
+pub mod lod_system;
+pub mod orbit_camera_system;
 pub mod rotate_transform_system;
+pub mod smooth_follow_system;
+pub mod timers_system;
+pub mod tween_system;
 pub mod update_camera_system;
 pub mod update_input_system;
+pub mod update_line_bindings_system;
 pub mod update_model_bindings_system;
+pub mod update_particles_system;
+pub mod update_spatial_grid_system;
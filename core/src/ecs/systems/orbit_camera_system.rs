@@ -0,0 +1,110 @@
+use bevy_ecs::system::{Query, Res};
+use glam::Vec3;
+
+use crate::ecs::{
+    components::{orbit_camera_controller::OrbitCameraController, transform::Transform},
+    resources::input::Input,
+};
+
+/// Turns, pans and zooms every orbit controller from this frame's mouse input and places its
+/// transform accordingly. Entities without an `OrbitCameraController` are left alone.
+pub fn update_orbit_camera_system(
+    input: Res<Input>,
+    mut query: Query<(&mut Transform, &mut OrbitCameraController)>,
+) {
+    let mouse = &input.mouse;
+    for (mut transform, mut controller) in query.iter_mut() {
+        let rotate_drag = mouse
+            .get_button(controller.rotate_button)
+            .and_then(|state| state.dragging());
+        let pan_drag = mouse
+            .get_button(controller.pan_button)
+            .and_then(|state| state.dragging());
+
+        if let Some(drag_start) = rotate_drag.or(pan_drag) {
+            // the first dragged frame measures from where the press started,
+            // so the movement before the drag threshold was crossed isn't lost
+            let (anchor_x, anchor_y) = controller.drag_anchor.unwrap_or(drag_start);
+            let delta_x = (mouse.x - anchor_x) as f32;
+            let delta_y = (mouse.y - anchor_y) as f32;
+            controller.drag_anchor = Some((mouse.x, mouse.y));
+
+            if rotate_drag.is_some() {
+                let rotate_speed = controller.rotate_speed;
+                controller.rotate(-delta_x * rotate_speed, delta_y * rotate_speed);
+            } else {
+                let pan_scale = controller.pan_speed * controller.distance;
+                controller.target +=
+                    (transform.up() * delta_y - transform.right() * delta_x) * pan_scale;
+            }
+        } else {
+            controller.drag_anchor = None;
+        }
+
+        if mouse.delta_scroll_y != 0.0 {
+            let zoom_speed = controller.zoom_speed;
+            controller.zoom((-zoom_speed * mouse.delta_scroll_y as f32).exp());
+        }
+
+        transform.set_position(controller.position());
+        transform.look_at(controller.target, Vec3::Y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{schedule::Schedule, world::World};
+    use winit::event::MouseButton;
+
+    fn run_frame(world: &mut World) {
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_orbit_camera_system);
+        schedule.run(world);
+    }
+
+    #[test]
+    fn dragging_rotates_around_the_target() {
+        let mut world = World::new();
+        world.insert_resource(Input::new());
+        let target = Vec3::new(1.0, 2.0, 3.0);
+        let camera = world
+            .spawn((
+                Transform::from_translation(Vec3::ZERO),
+                OrbitCameraController::new(target, 5.0),
+            ))
+            .id();
+        run_frame(&mut world);
+        let start = world.get::<Transform>(camera).unwrap().translation;
+
+        {
+            let mut input = world.resource_mut::<Input>();
+            input.mouse.set_position(100.0, 100.0);
+            input.mouse.get_or_insert_button(MouseButton::Left).press();
+            input.update(0.0);
+            // far enough to count as a drag
+            input.mouse.set_position(150.0, 100.0);
+            input.update(0.1);
+        }
+        run_frame(&mut world);
+
+        let transform = *world.get::<Transform>(camera).unwrap();
+        let controller = *world.get::<OrbitCameraController>(camera).unwrap();
+        assert_eq!(controller.yaw, -50.0 * controller.rotate_speed);
+        assert_ne!(transform.translation, start);
+        // a sideways drag keeps the distance and height
+        assert!((transform.translation.distance(target) - 5.0).abs() < 1e-5);
+        assert!((transform.translation.y - target.y).abs() < 1e-5);
+        assert!(
+            transform
+                .forward()
+                .abs_diff_eq((target - transform.translation).normalize(), 1e-5)
+        );
+
+        // holding still mid drag doesn't turn any further
+        world.resource_mut::<Input>().update(0.2);
+        run_frame(&mut world);
+        let held = world.get::<Transform>(camera).unwrap().translation;
+        assert!(held.abs_diff_eq(transform.translation, 1e-6));
+    }
+}
@@ -0,0 +1,82 @@
+use bevy_ecs::{
+    query::Without,
+    system::{Query, Res},
+};
+use glam::Vec3;
+
+use crate::ecs::{
+    components::{smooth_follow::SmoothFollow, transform::Transform},
+    resources::time::Time,
+};
+
+/// Exponentially damps every follower towards its target. Followers can't follow other followers,
+/// which keeps the target query disjoint from the one being moved.
+pub fn smooth_follow_system(
+    time: Res<Time>,
+    target_query: Query<&Transform, Without<SmoothFollow>>,
+    mut follower_query: Query<(&mut Transform, &SmoothFollow)>,
+) {
+    for (mut transform, follow) in follower_query.iter_mut() {
+        let Ok(target) = target_query.get(follow.target) else {
+            continue;
+        };
+
+        let goal = target.translation + follow.offset;
+        let position = transform
+            .translation
+            .lerp(goal, follow.blend_factor(time.delta_time));
+        transform.set_position(position);
+
+        if follow.look_at && (target.translation - position).length_squared() > f32::EPSILON {
+            transform.look_at(target.translation, Vec3::Y);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{schedule::Schedule, world::World};
+
+    /// Follows a still target for `seconds` at `fps` and returns where the follower ends up
+    fn follow_for(seconds: f32, fps: u32) -> (Vec3, Vec3) {
+        let mut world = World::new();
+        world.insert_resource(Time::new());
+        let target = world
+            .spawn(Transform::from_translation(Vec3::new(10.0, 0.0, 0.0)))
+            .id();
+        let follow = SmoothFollow::new(target, Vec3::new(0.0, 2.0, 0.0), 5.0);
+        let follower = world
+            .spawn((Transform::from_translation(Vec3::ZERO), follow))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(smooth_follow_system);
+        for _ in 0..(seconds * fps as f32).round() as u32 {
+            world.resource_mut::<Time>().new_frame(1.0 / fps as f32);
+            schedule.run(&mut world);
+        }
+
+        let goal = Vec3::new(10.0, 2.0, 0.0);
+        (world.get::<Transform>(follower).unwrap().translation, goal)
+    }
+
+    #[test]
+    fn follower_approaches_the_same_way_at_any_frame_rate() {
+        let (at_30_fps, _) = follow_for(0.5, 30);
+        let (at_144_fps, _) = follow_for(0.5, 144);
+
+        assert!(at_30_fps.abs_diff_eq(at_144_fps, 1e-3));
+    }
+
+    #[test]
+    fn follower_converges_on_the_offset_target() {
+        let (early, goal) = follow_for(0.2, 60);
+        let (settled, _) = follow_for(3.0, 60);
+
+        // 1 - e^(-5 * 0.2) of the way there
+        let expected = goal * (1.0 - (-1.0_f32).exp());
+        assert!(early.abs_diff_eq(expected, 1e-3));
+        assert!(settled.abs_diff_eq(goal, 1e-3));
+    }
+}
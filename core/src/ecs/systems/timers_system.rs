@@ -0,0 +1,16 @@
+use bevy_ecs::world::World;
+
+use crate::ecs::resources::{
+    time::Time,
+    timers::{Timers, run_timers},
+};
+
+/// Advances `Timers` by the frame's delta time and runs every due callback
+pub fn timers_system(world: &mut World) {
+    if !world.contains_resource::<Timers>() {
+        return;
+    }
+
+    let delta_time = world.resource::<Time>().delta_time;
+    run_timers(world, delta_time);
+}
@@ -0,0 +1,79 @@
+use bevy_ecs::{
+    entity::Entity,
+    system::{Commands, Query, Res},
+};
+
+use crate::ecs::{
+    components::tween::{Tween, TweenFinished, Tweenable},
+    resources::time::Time,
+};
+
+/// Advances every `Tween<T>` and writes the eased value into the entity's `T`.
+/// Finished one-shot tweens are removed and replaced with a `TweenFinished` marker.
+pub fn tween_system<T: Tweenable>(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut T, &mut Tween<T>)>,
+) {
+    for (entity, mut target, mut tween) in query.iter_mut() {
+        let finished = tween.advance(time.delta_time);
+        *target = tween.value();
+
+        if finished {
+            commands
+                .entity(entity)
+                .remove::<Tween<T>>()
+                .insert(TweenFinished);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{schedule::Schedule, world::World};
+    use glam::Vec3;
+
+    use crate::{ecs::components::transform::Transform, utils::easing::Easing};
+
+    fn world_with_position_tween() -> (World, Schedule, Entity) {
+        let mut world = World::new();
+        world.insert_resource(Time::new());
+        let start = Transform::from_translation(Vec3::ZERO);
+        let end = Transform::from_translation(Vec3::new(10.0, 0.0, -4.0));
+        let entity = world
+            .spawn((start, Tween::new(start, end, 1.0, Easing::Linear)))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(tween_system::<Transform>);
+        (world, schedule, entity)
+    }
+
+    #[test]
+    fn position_tween_reaches_the_midpoint_halfway() {
+        let (mut world, mut schedule, entity) = world_with_position_tween();
+        for _ in 0..2 {
+            world.resource_mut::<Time>().new_frame(0.25);
+            schedule.run(&mut world);
+        }
+
+        let translation = world.get::<Transform>(entity).unwrap().translation;
+        assert!(translation.abs_diff_eq(Vec3::new(5.0, 0.0, -2.0), 1e-5));
+    }
+
+    #[test]
+    fn finished_tween_is_replaced_by_a_marker() {
+        let (mut world, mut schedule, entity) = world_with_position_tween();
+        world.resource_mut::<Time>().new_frame(1.5);
+        schedule.run(&mut world);
+
+        let entity = world.entity(entity);
+        assert!(entity.get::<Tween<Transform>>().is_none());
+        assert!(entity.contains::<TweenFinished>());
+        assert_eq!(
+            entity.get::<Transform>().unwrap().translation,
+            Vec3::new(10.0, 0.0, -4.0)
+        );
+    }
+}
@@ -10,17 +10,30 @@ use crate::{
     gpu_resources::render_resources::RenderResources,
 };
 
+/// Aspect ratio changes smaller than this don't rebuild the projection
+const ASPECT_RATIO_EPSILON: f32 = 1e-6;
+
 pub fn update_camera_system(
     screen_parameters: Res<ScreenParameters>,
     mut camera_query: Query<(&mut Camera,)>,
 ) {
     let (mut camera,) = camera_query.single_mut();
-    let aspect_ratio = (screen_parameters.width as f64 / screen_parameters.height as f64) as f32;
-    camera.set_aspect_ratio(aspect_ratio);
+    if camera.pixel_origin.is_some() {
+        camera.fit_to_screen(screen_parameters.width, screen_parameters.height);
+        return;
+    }
+
+    // only invalidate the cached projection when the screen shape actually changed,
+    // otherwise a still camera would rebuild and upload its matrices every frame
+    let aspect_ratio = screen_parameters.aspect_ratio();
+    if (camera.aspect_ratio - aspect_ratio).abs() > ASPECT_RATIO_EPSILON {
+        camera.set_aspect_ratio(aspect_ratio);
+    }
 }
 
 pub fn update_camera_bindings(
     render_resources: Res<RenderResources>,
+    screen_parameters: Res<ScreenParameters>,
     mut camera_query: Query<(&mut Camera, &mut Transform, &mut CameraBindings)>,
 ) {
     let (camera, transform, mut bindings) = camera_query.single_mut();
@@ -28,5 +41,58 @@ pub fn update_camera_bindings(
         &render_resources.queue,
         camera.into_inner(),
         transform.into_inner(),
+        screen_parameters.size_vec2(),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{schedule::Schedule, world::World};
+
+    use super::*;
+
+    fn world_with_camera(width: u32, height: u32) -> (World, Schedule) {
+        let mut world = World::new();
+        world.insert_resource(ScreenParameters::new(width, height));
+        world.spawn((Camera::default(),));
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_camera_system);
+        (world, schedule)
+    }
+
+    /// Runs a frame, builds the projection like the renderer does and returns whether
+    /// the frame had to rebuild it
+    fn frame_rebuilds_projection(world: &mut World, schedule: &mut Schedule) -> bool {
+        schedule.run(world);
+        let mut camera = world.query::<&mut Camera>().single_mut(world);
+        let needed_update = camera.needs_update();
+        camera.get_projection_matrix();
+        needed_update
+    }
+
+    #[test]
+    fn unchanged_screen_keeps_the_cached_projection() {
+        let (mut world, mut schedule) = world_with_camera(1920, 1080);
+
+        assert!(frame_rebuilds_projection(&mut world, &mut schedule));
+        assert!(!frame_rebuilds_projection(&mut world, &mut schedule));
+
+        world
+            .resource_mut::<ScreenParameters>()
+            .set_size(1080, 1080);
+        assert!(frame_rebuilds_projection(&mut world, &mut schedule));
+        assert_eq!(world.query::<&Camera>().single(&world).aspect_ratio, 1.0);
+    }
+
+    #[test]
+    fn rounding_differences_in_the_aspect_ratio_are_ignored() {
+        let (mut world, mut schedule) = world_with_camera(1920, 1080);
+        assert!(frame_rebuilds_projection(&mut world, &mut schedule));
+
+        // the same shape computed another way
+        let mut camera = world.query::<&mut Camera>().single_mut(&mut world);
+        camera.aspect_ratio = (1920.0_f64 / 1080.0_f64) as f32 + f32::EPSILON;
+        assert!(!frame_rebuilds_projection(&mut world, &mut schedule));
+    }
+}
@@ -1,7 +1,7 @@
-use bevy_ecs::system::ResMut;
+use bevy_ecs::system::{Res, ResMut};
 
-use crate::ecs::resources::input::Input;
+use crate::ecs::resources::{input::Input, time::Time};
 
-pub fn update_input_system(mut input: ResMut<Input>) {
-    input.as_mut().update();
+pub fn update_input_system(mut input: ResMut<Input>, time: Res<Time>) {
+    input.as_mut().update(time.total_time as f64);
 }
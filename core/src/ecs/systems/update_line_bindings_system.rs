@@ -0,0 +1,21 @@
+use bevy_ecs::{
+    query::Changed,
+    system::{Query, Res},
+};
+
+use crate::{
+    ecs::components::{gpu_bindings::line_bindings::LineBindings, lines::Lines},
+    gpu_resources::render_resources::RenderResources,
+};
+
+pub fn update_line_bindings_system(
+    render_resources: Res<RenderResources>,
+    mut line_query: Query<(&Lines, &mut LineBindings), Changed<Lines>>,
+) {
+    let device = &render_resources.device;
+    let queue = &render_resources.queue;
+
+    for (lines, mut bindings) in line_query.iter_mut() {
+        bindings.update(device, queue, lines);
+    }
+}
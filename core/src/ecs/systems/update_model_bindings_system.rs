@@ -1,17 +1,89 @@
-use bevy_ecs::system::{Query, Res};
+use bevy_ecs::{
+    query::Changed,
+    system::{Query, Res, ResMut},
+};
 
 use crate::{
-    ecs::components::{gpu_bindings::model_bindings::ModelBindings, transform::Transform},
+    ecs::{
+        components::{gpu_bindings::model_bindings::ModelBindings, transform::Transform},
+        resources::model_uniform_arena::ModelUniformArena,
+    },
     gpu_resources::render_resources::RenderResources,
 };
 
+/// Uploads the model matrix of every entity whose `Transform` changed since the last run.
+/// Static entities aren't visited at all. All matrices, including those of entities spawned
+/// since the last run, go to the GPU together in one write.
 pub fn update_model_bindings_system(
     render_resources: Res<RenderResources>,
-    mut model_query: Query<(&mut Transform, &mut ModelBindings)>,
+    mut arena: ResMut<ModelUniformArena>,
+    mut model_query: Query<(&mut Transform, &mut ModelBindings), Changed<Transform>>,
 ) {
-    let queue = &render_resources.queue;
+    for (mut transform, mut bindings) in model_query.iter_mut() {
+        // caching the matrix isn't a change, so don't flag the transform again for the next frame
+        bindings.update(&mut arena, transform.bypass_change_detection());
+    }
+
+    arena.flush(&render_resources.queue);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        gpu_resources::layouts::model_uniform_layout::ModelUniformLayout,
+        utils::test_device::request_device,
+    };
+    use bevy_ecs::{schedule::Schedule, world::World};
+    use glam::Vec3;
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn static_entities_write_nothing_after_the_first_frame() {
+        let (device, queue) = request_device();
+        let mut world = World::new();
+        let layout = ModelUniformLayout::new(&device);
+        world.insert_resource(ModelUniformArena::new(&device, &layout));
+        world.insert_resource(layout);
+        let device = Arc::new(device);
+        world.insert_resource(RenderResources::new(
+            device.clone(),
+            Arc::new(queue),
+            wgpu::TextureFormat::Rgba8Unorm,
+        ));
+
+        let mut entities = Vec::new();
+        for x in 0..10 {
+            let mut transform = Transform::from_translation(Vec3::new(x as f32, 0.0, 0.0));
+            let bindings = ModelBindings::new(&mut world, &device, &mut transform);
+            entities.push(world.spawn((transform, bindings)).id());
+        }
+
+        // one schedule throughout, so change detection remembers what it has seen
+        let mut schedule = Schedule::default();
+        schedule.add_systems(update_model_bindings_system);
+        schedule.run(&mut world);
+        let after_first_frame = world.resource::<ModelUniformArena>().write_count();
+
+        for _ in 0..3 {
+            schedule.run(&mut world);
+        }
+        assert_eq!(
+            world.resource::<ModelUniformArena>().write_count(),
+            after_first_frame
+        );
 
-    for (transform, mut bindings) in model_query.iter_mut() {
-        bindings.update(queue, transform.into_inner());
+        // moving one entity writes only its slot
+        world
+            .get_mut::<Transform>(entities[4])
+            .unwrap()
+            .translate(Vec3::Y);
+        schedule.run(&mut world);
+        assert_eq!(
+            world.resource::<ModelUniformArena>().write_count(),
+            after_first_frame + 1
+        );
     }
 }
@@ -0,0 +1,33 @@
+use bevy_ecs::system::{Query, Res};
+
+use crate::{
+    ecs::{
+        components::{
+            gpu_bindings::particle_bindings::ParticleBindings, particle_emitter::ParticleEmitter,
+            transform::Transform,
+        },
+        resources::time::Time,
+    },
+    gpu_resources::render_resources::RenderResources,
+};
+
+pub fn update_particle_emitter_system(
+    time: Res<Time>,
+    mut emitter_query: Query<(&Transform, &mut ParticleEmitter)>,
+) {
+    for (transform, mut emitter) in emitter_query.iter_mut() {
+        emitter.update(time.delta_time, transform.translation);
+    }
+}
+
+pub fn update_particle_bindings_system(
+    render_resources: Res<RenderResources>,
+    mut particle_query: Query<(&ParticleEmitter, &mut ParticleBindings)>,
+) {
+    let device = &render_resources.device;
+    let queue = &render_resources.queue;
+
+    for (emitter, mut bindings) in particle_query.iter_mut() {
+        bindings.update(device, queue, emitter);
+    }
+}
@@ -0,0 +1,27 @@
+use bevy_ecs::{
+    entity::Entity,
+    system::{Query, ResMut},
+};
+use glam::Mat4;
+
+use crate::ecs::{
+    components::{mesh_collider::MeshCollider, transform::Transform},
+    resources::spatial_grid::SpatialGrid,
+};
+
+/// Rebuilds the spatial grid from the world bounds of every entity with a `MeshCollider`
+pub fn update_spatial_grid_system(
+    mut spatial_grid: ResMut<SpatialGrid>,
+    collider_query: Query<(Entity, &Transform, &MeshCollider)>,
+) {
+    spatial_grid.clear();
+
+    for (entity, transform, collider) in collider_query.iter() {
+        let model_matrix = Mat4::from_scale_rotation_translation(
+            transform.scale,
+            transform.rotation,
+            transform.translation,
+        );
+        spatial_grid.insert(entity, collider.local_bounds().transformed(model_matrix));
+    }
+}
@@ -2,6 +2,8 @@ use bevy_ecs::world::World;
 
 pub mod camera_uniform_layout;
 pub mod model_uniform_layout;
+pub mod shadow_map_uniform_layout;
+pub mod storage_buffer_layout;
 pub mod texture_uniform_layout;
 
 pub fn initialize_bind_group_layouts(world: &mut World, device: &wgpu::Device) {
@@ -10,6 +12,11 @@ pub fn initialize_bind_group_layouts(world: &mut World, device: &wgpu::Device) {
 
     world.insert_resource(model_uniform_layout::ModelUniformLayout::new(device));
 
+    // Depth texture with a comparison sampler, for shadow maps
+    world.insert_resource(shadow_map_uniform_layout::ShadowMapUniformLayout::new(
+        device,
+    ));
+
     // Initialize texture uniform bind group layout and insert it into the world
     world.insert_resource(texture_uniform_layout::TextureUniformLayout::<1>::new(
         device,
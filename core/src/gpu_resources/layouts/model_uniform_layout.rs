@@ -1,4 +1,7 @@
 use bevy_ecs::system::Resource;
+use encase::ShaderType;
+
+use crate::gpu_resources::types::gpu_model::GpuModel;
 
 const MODEL_LAYOUT_DESCRIPTOR: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroupLayoutDescriptor {
     label: Some("model_bind_group_layout"),
@@ -7,7 +10,8 @@ const MODEL_LAYOUT_DESCRIPTOR: wgpu::BindGroupLayoutDescriptor = wgpu::BindGroup
         visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
         ty: wgpu::BindingType::Buffer {
             ty: wgpu::BufferBindingType::Uniform,
-            has_dynamic_offset: false,
+            // every model lives in one buffer, see `ModelUniformArena`
+            has_dynamic_offset: true,
             min_binding_size: None,
         },
         count: None,
@@ -25,6 +29,7 @@ impl ModelUniformLayout {
         Self { layout }
     }
 
+    /// Binds one model sized window of `buffer`, positioned by the dynamic offset at draw time
     pub fn create_bind_group(
         &self,
         device: &wgpu::Device,
@@ -35,7 +40,11 @@ impl ModelUniformLayout {
             layout: &self.layout,
             entries: &[wgpu::BindGroupEntry {
                 binding: 0,
-                resource: buffer.as_entire_binding(),
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer,
+                    offset: 0,
+                    size: Some(GpuModel::min_size()),
+                }),
             }],
         })
     }
@@ -0,0 +1,105 @@
+use bevy_ecs::system::Resource;
+
+use crate::utils::texture::Texture;
+
+/// Bind group layout for a depth texture and a comparison sampler, e.g. a shadow map.
+/// The texture is at binding 0 and the sampler at binding 1, like slot 0 of `TextureUniformLayout`.
+#[derive(Resource)]
+pub struct ShadowMapUniformLayout {
+    pub layout: wgpu::BindGroupLayout,
+}
+
+impl ShadowMapUniformLayout {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("shadow_map_bind_group_layout"),
+            entries: &Self::entries(),
+        });
+
+        Self { layout }
+    }
+
+    /// The depth texture and comparison sampler entries of the layout
+    fn entries() -> [wgpu::BindGroupLayoutEntry; 2] {
+        [
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Depth,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                count: None,
+            },
+        ]
+    }
+
+    /// Creates a bind group for a texture made with `Texture::new_shadow_map`
+    pub fn create_bind_group(
+        &self,
+        device: &wgpu::Device,
+        shadow_map: &Texture,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("shadow_map_bind_group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&shadow_map.view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&shadow_map.sampler),
+                },
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        gpu_resources::pipelines::fallback::catch_validation_error,
+        utils::test_device::request_device,
+    };
+
+    #[test]
+    fn binds_a_depth_texture_with_a_comparison_sampler() {
+        let [texture, sampler] = ShadowMapUniformLayout::entries();
+
+        assert_eq!(texture.binding, 0);
+        assert!(matches!(
+            texture.ty,
+            wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Depth,
+                ..
+            }
+        ));
+        assert_eq!(sampler.binding, 1);
+        assert_eq!(
+            sampler.ty,
+            wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison)
+        );
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn accepts_a_shadow_map_texture() {
+        let (device, _queue) = request_device();
+
+        let layout = ShadowMapUniformLayout::new(&device);
+        let shadow_map = Texture::new_shadow_map(&device, 64, None);
+        let (_, error) =
+            catch_validation_error(&device, || layout.create_bind_group(&device, &shadow_map));
+        assert!(error.is_none(), "{:?}", error);
+    }
+}
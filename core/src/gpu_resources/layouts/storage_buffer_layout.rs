@@ -0,0 +1,124 @@
+/// Whether shaders may write to a storage buffer bound through the layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageAccess {
+    ReadOnly,
+    ReadWrite,
+}
+
+impl StorageAccess {
+    fn read_only(self) -> bool {
+        self == StorageAccess::ReadOnly
+    }
+}
+
+/// Layout with a single storage buffer at binding 0. Unlike the uniform layouts this
+/// isn't a shared resource, since each compute or GPU-driven feature picks its own
+/// access and visibility. Read-write storage buffers aren't allowed in vertex shaders.
+///
+/// The device must allow storage buffers, which WebGL2 and `Limits::downlevel_webgl2_defaults`
+/// don't. `DemoWinitHandler::required_limits` asks for the adapter's storage buffer limits.
+pub struct StorageBufferLayout {
+    pub layout: wgpu::BindGroupLayout,
+    pub access: StorageAccess,
+    pub visibility: wgpu::ShaderStages,
+}
+
+impl StorageBufferLayout {
+    pub fn new(
+        device: &wgpu::Device,
+        access: StorageAccess,
+        visibility: wgpu::ShaderStages,
+    ) -> Result<Self, String> {
+        if device.limits().max_storage_buffers_per_shader_stage == 0 {
+            return Err(
+                "Storage buffers aren't available, the device allows 0 per shader stage"
+                    .to_string(),
+            );
+        }
+        if access == StorageAccess::ReadWrite && visibility.contains(wgpu::ShaderStages::VERTEX) {
+            return Err(
+                "Read-write storage buffers can't be visible to vertex shaders".to_string(),
+            );
+        }
+
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("storage_buffer_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage {
+                        read_only: access.read_only(),
+                    },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        Ok(Self {
+            layout,
+            access,
+            visibility,
+        })
+    }
+
+    pub fn create_bind_group(
+        &self,
+        device: &wgpu::Device,
+        buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("storage_buffer_bind_group"),
+            layout: &self.layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wgpu::util::DeviceExt;
+
+    use super::*;
+    use crate::utils::test_device::request_device_with_limits;
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn layout_binds_a_storage_buffer() {
+        // the downlevel defaults guarantee compute storage buffers
+        let (device, _queue) = request_device_with_limits(|_| wgpu::Limits::downlevel_defaults());
+
+        let layout = StorageBufferLayout::new(
+            &device,
+            StorageAccess::ReadWrite,
+            wgpu::ShaderStages::COMPUTE,
+        )
+        .unwrap();
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&[0u32; 16]),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        layout.create_bind_group(&device, &buffer);
+        let error = futures::executor::block_on(device.pop_error_scope());
+        assert!(error.is_none(), "{:?}", error);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn webgl2_limits_are_reported_instead_of_panicking() {
+        let (device, _queue) =
+            request_device_with_limits(|_| wgpu::Limits::downlevel_webgl2_defaults());
+
+        let layout =
+            StorageBufferLayout::new(&device, StorageAccess::ReadOnly, wgpu::ShaderStages::VERTEX);
+        assert!(layout.is_err());
+    }
+}
@@ -2,6 +2,8 @@ use bevy_ecs::system::Resource;
 
 use crate::utils::texture::Texture;
 
+/// Bind group layout for `N` texture-sampler pairs.
+/// Slot i binds its texture at binding 2i and its sampler at binding 2i + 1.
 #[derive(Resource)]
 pub struct TextureUniformLayout<const N: usize> {
     pub layout: wgpu::BindGroupLayout,
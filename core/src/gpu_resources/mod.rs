@@ -5,6 +5,7 @@ use bevy_ecs::world::World;
 pub mod layouts;
 pub mod pipelines;
 pub mod render_resources;
+pub mod shader_preprocessor;
 mod shaders;
 pub mod types;
 
@@ -0,0 +1,114 @@
+/// How a material's color is combined with what is already in the render target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlendMode {
+    /// No blending, writes depth
+    #[default]
+    Opaque,
+    /// Standard alpha blending: `src * srcA + dst * (1 - srcA)`
+    Alpha,
+    /// Alpha blending for premultiplied colors: `src + dst * (1 - srcA)`.
+    /// The material's texture must already have its color multiplied by its alpha,
+    /// as most UI and font atlases exported for compositing do. Straight alpha textures,
+    /// like the JPEG and PNG images loaded by `include_texture!`, belong in `Alpha`.
+    PremultipliedAlpha,
+    /// Adds the alpha-weighted source to the destination, for fire and glow
+    Additive,
+    /// Multiplies the destination by the source color
+    Multiply,
+}
+
+impl BlendMode {
+    /// Every blend mode, in the order they should be drawn
+    pub const ALL: [BlendMode; 5] = [
+        BlendMode::Opaque,
+        BlendMode::Alpha,
+        BlendMode::PremultipliedAlpha,
+        BlendMode::Additive,
+        BlendMode::Multiply,
+    ];
+
+    /// The blend state used by the color target for this mode
+    pub fn blend_state(&self) -> Option<wgpu::BlendState> {
+        match self {
+            BlendMode::Opaque => None,
+            BlendMode::Alpha => Some(wgpu::BlendState::ALPHA_BLENDING),
+            BlendMode::PremultipliedAlpha => Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            BlendMode::Additive => Some(wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: KEEP_DESTINATION_ALPHA,
+            }),
+            BlendMode::Multiply => Some(wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Dst,
+                    dst_factor: wgpu::BlendFactor::Zero,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: KEEP_DESTINATION_ALPHA,
+            }),
+        }
+    }
+
+    /// Only opaque geometry writes depth, blended geometry is tested but doesn't occlude
+    pub fn depth_write_enabled(&self) -> bool {
+        matches!(self, BlendMode::Opaque)
+    }
+
+    /// Whether this mode needs to be drawn after the opaque geometry
+    pub fn is_transparent(&self) -> bool {
+        !matches!(self, BlendMode::Opaque)
+    }
+}
+
+const KEEP_DESTINATION_ALPHA: wgpu::BlendComponent = wgpu::BlendComponent {
+    src_factor: wgpu::BlendFactor::Zero,
+    dst_factor: wgpu::BlendFactor::One,
+    operation: wgpu::BlendOperation::Add,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_mode_selects_its_blend_state() {
+        assert_eq!(BlendMode::Opaque.blend_state(), None);
+        assert_eq!(
+            BlendMode::Alpha.blend_state(),
+            Some(wgpu::BlendState::ALPHA_BLENDING)
+        );
+
+        let additive = BlendMode::Additive.blend_state().unwrap();
+        assert_eq!(additive.color.src_factor, wgpu::BlendFactor::SrcAlpha);
+        assert_eq!(additive.color.dst_factor, wgpu::BlendFactor::One);
+
+        let multiply = BlendMode::Multiply.blend_state().unwrap();
+        assert_eq!(multiply.color.src_factor, wgpu::BlendFactor::Dst);
+        assert_eq!(multiply.color.dst_factor, wgpu::BlendFactor::Zero);
+    }
+
+    #[test]
+    fn only_opaque_writes_depth() {
+        for blend_mode in BlendMode::ALL {
+            assert_eq!(
+                blend_mode.depth_write_enabled(),
+                !blend_mode.is_transparent()
+            );
+        }
+        assert!(BlendMode::Opaque.depth_write_enabled());
+    }
+
+    #[test]
+    fn premultiplied_alpha_adds_the_source_unscaled() {
+        let premultiplied = BlendMode::PremultipliedAlpha.blend_state().unwrap();
+        assert_eq!(premultiplied.color.src_factor, wgpu::BlendFactor::One);
+        assert_eq!(
+            premultiplied.color.dst_factor,
+            wgpu::BlendFactor::OneMinusSrcAlpha
+        );
+        assert!(BlendMode::PremultipliedAlpha.is_transparent());
+    }
+}
@@ -0,0 +1,156 @@
+use bevy_ecs::{system::Resource, world::World};
+
+use crate::gpu_resources::{
+    layouts::texture_uniform_layout::TextureUniformLayout,
+    render_resources::{HDR_FORMAT, RenderResources},
+};
+
+use super::super::shaders::bloom::{
+    SHADER_DESCRIPTOR_COMPOSITE, SHADER_DESCRIPTOR_DOWNSAMPLE, SHADER_DESCRIPTOR_PREFILTER,
+    SHADER_DESCRIPTOR_UPSAMPLE, SHADER_DESCRIPTOR_VERTEX,
+};
+use super::fallback;
+
+const BLOOM_SETTINGS_LAYOUT_DESCRIPTOR: wgpu::BindGroupLayoutDescriptor =
+    wgpu::BindGroupLayoutDescriptor {
+        label: Some("bloom_settings_bind_group_layout"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+    };
+
+/// Adds the source onto what is already in the target
+const ADDITIVE_BLEND: wgpu::BlendState = wgpu::BlendState {
+    color: wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::One,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    },
+    alpha: wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::Zero,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    },
+};
+
+/// Fullscreen pipelines for each stage of the bloom pass.
+/// Group 0 is the texture being read, group 1 the `GpuBloomSettings` uniform.
+#[derive(Resource)]
+pub struct BloomPipeline {
+    /// Thresholds the HDR target into the first mip of the bloom chain
+    pub prefilter_pipeline: wgpu::RenderPipeline,
+    /// Reads one mip and writes the next smaller one
+    pub downsample_pipeline: wgpu::RenderPipeline,
+    /// Reads one mip and adds it onto the next larger one
+    pub upsample_pipeline: wgpu::RenderPipeline,
+    /// Reads the first mip and adds it onto the HDR target
+    pub composite_pipeline: wgpu::RenderPipeline,
+    pub settings_layout: wgpu::BindGroupLayout,
+}
+
+impl BloomPipeline {
+    pub fn new(world: &World) -> Self {
+        let render_resources = world.get_resource::<RenderResources>().unwrap();
+        let device = &render_resources.device;
+
+        let texture_uniform_layout = &world
+            .get_resource::<TextureUniformLayout<1>>()
+            .unwrap()
+            .layout;
+        let settings_layout = device.create_bind_group_layout(&BLOOM_SETTINGS_LAYOUT_DESCRIPTOR);
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("bloom_pipeline_layout"),
+            bind_group_layouts: &[texture_uniform_layout, &settings_layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader_module = fallback::create_shader_module(device, SHADER_DESCRIPTOR_VERTEX);
+
+        let create_pipeline = |label: &str,
+                               fragment_descriptor: wgpu::ShaderModuleDescriptor,
+                               entry_point: &str,
+                               blend: Option<wgpu::BlendState>| {
+            let fragment_shader_module =
+                fallback::create_shader_module(device, fragment_descriptor);
+
+            fallback::create_render_pipeline(
+                device,
+                &wgpu::RenderPipelineDescriptor {
+                    label: Some(label),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &vertex_shader_module,
+                        entry_point: "vs_main",
+                        buffers: &[],
+                        compilation_options: Default::default(),
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &fragment_shader_module,
+                        entry_point,
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format: HDR_FORMAT,
+                            blend,
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                        compilation_options: Default::default(),
+                    }),
+                    primitive: wgpu::PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                },
+            )
+        };
+
+        Self {
+            prefilter_pipeline: create_pipeline(
+                "bloom_prefilter_pipeline",
+                SHADER_DESCRIPTOR_PREFILTER,
+                "fs_prefilter",
+                None,
+            ),
+            downsample_pipeline: create_pipeline(
+                "bloom_downsample_pipeline",
+                SHADER_DESCRIPTOR_DOWNSAMPLE,
+                "fs_downsample",
+                None,
+            ),
+            upsample_pipeline: create_pipeline(
+                "bloom_upsample_pipeline",
+                SHADER_DESCRIPTOR_UPSAMPLE,
+                "fs_upsample",
+                Some(ADDITIVE_BLEND),
+            ),
+            composite_pipeline: create_pipeline(
+                "bloom_composite_pipeline",
+                SHADER_DESCRIPTOR_COMPOSITE,
+                "fs_composite",
+                Some(ADDITIVE_BLEND),
+            ),
+            settings_layout,
+        }
+    }
+
+    pub fn create_settings_bind_group(
+        &self,
+        device: &wgpu::Device,
+        settings_buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("bloom_settings_bind_group"),
+            layout: &self.settings_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: settings_buffer.as_entire_binding(),
+            }],
+        })
+    }
+}
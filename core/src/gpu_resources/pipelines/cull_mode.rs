@@ -0,0 +1,25 @@
+/// Which faces of a triangle are discarded before rasterization.
+/// Front faces wind counter-clockwise, as the meshes in `utils::primitives` do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CullMode {
+    /// Draws both sides, for double-sided materials like foliage or open meshes
+    None,
+    /// Skips faces pointing away from the camera, for closed solid meshes
+    #[default]
+    Back,
+    /// Skips faces pointing toward the camera, e.g. to draw the inside of a skybox
+    Front,
+}
+
+impl CullMode {
+    pub const ALL: [CullMode; 3] = [CullMode::None, CullMode::Back, CullMode::Front];
+
+    /// The face the pipeline's primitive state culls for this mode
+    pub fn face(&self) -> Option<wgpu::Face> {
+        match self {
+            CullMode::None => None,
+            CullMode::Back => Some(wgpu::Face::Back),
+            CullMode::Front => Some(wgpu::Face::Front),
+        }
+    }
+}
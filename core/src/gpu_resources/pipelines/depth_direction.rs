@@ -0,0 +1,56 @@
+/// Which end of the depth range is closest to the camera.
+/// Reversed depth (near = 1, far = 0) follows `Camera::reversed_depth` and needs the
+/// depth buffer cleared to 0 and tested with `Greater` instead of 1 and `Less`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DepthDirection {
+    /// Near plane at 0, far plane at 1
+    #[default]
+    Standard,
+    /// Near plane at 1, far plane at 0
+    Reversed,
+}
+
+impl DepthDirection {
+    pub const ALL: [DepthDirection; 2] = [DepthDirection::Standard, DepthDirection::Reversed];
+
+    pub fn from_reversed(reversed_depth: bool) -> Self {
+        if reversed_depth {
+            DepthDirection::Reversed
+        } else {
+            DepthDirection::Standard
+        }
+    }
+
+    /// The depth of the far plane, which the depth buffer is cleared to
+    pub fn clear_value(&self) -> f32 {
+        match self {
+            DepthDirection::Standard => 1.0,
+            DepthDirection::Reversed => 0.0,
+        }
+    }
+
+    /// The compare function that passes fragments closer to the camera
+    pub fn compare(&self) -> wgpu::CompareFunction {
+        match self {
+            DepthDirection::Standard => wgpu::CompareFunction::Less,
+            DepthDirection::Reversed => wgpu::CompareFunction::Greater,
+        }
+    }
+
+    /// Like `compare`, but also passes fragments at exactly the stored depth,
+    /// so geometry already written by a pre-pass still counts as visible
+    pub fn compare_or_equal(&self) -> wgpu::CompareFunction {
+        match self {
+            DepthDirection::Standard => wgpu::CompareFunction::LessEqual,
+            DepthDirection::Reversed => wgpu::CompareFunction::GreaterEqual,
+        }
+    }
+
+    /// Whether a depth buffer value is still at the clear value, i.e. nothing was drawn there
+    pub fn is_background(&self, depth: f32) -> bool {
+        match self {
+            DepthDirection::Standard => depth >= 1.0,
+            DepthDirection::Reversed => depth <= 0.0,
+        }
+    }
+}
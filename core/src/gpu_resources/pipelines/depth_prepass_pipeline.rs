@@ -0,0 +1,111 @@
+use bevy_ecs::{system::Resource, world::World};
+
+use crate::gpu_resources::layouts::camera_uniform_layout::CameraUniformLayout;
+use crate::gpu_resources::layouts::model_uniform_layout::ModelUniformLayout;
+use crate::gpu_resources::render_resources::RenderResources;
+use crate::gpu_resources::types::basic_vertex::BasicVertex;
+
+use super::cull_mode::CullMode;
+use super::depth_direction::DepthDirection;
+use super::fallback;
+
+use super::super::shaders::unlit_diffuse::SHADER_DESCRIPTOR_VERTEX;
+
+/// Writes opaque geometry into the depth buffer only, with no color output.
+/// It reuses the unlit vertex shader so the depths match the main pass exactly,
+/// which the main pass relies on when it tests with `CompareFunction::Equal`.
+#[derive(Resource)]
+pub struct DepthPrepassPipeline {
+    /// Indexed by depth direction, then cull mode
+    render_pipelines: [[wgpu::RenderPipeline; 3]; 2],
+}
+
+impl DepthPrepassPipeline {
+    pub fn new(world: &World) -> Self {
+        let render_resources = world.get_resource::<RenderResources>().unwrap();
+        let device = &render_resources.device;
+
+        let model_uniform_layout = &world.get_resource::<ModelUniformLayout>().unwrap().layout;
+        let camera_uniform_layout = &world.get_resource::<CameraUniformLayout>().unwrap().layout;
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("depth_prepass_pipeline_layout"),
+            bind_group_layouts: &[camera_uniform_layout, model_uniform_layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader_module = fallback::create_shader_module(device, SHADER_DESCRIPTOR_VERTEX);
+
+        let render_pipelines = DepthDirection::ALL.map(|depth_direction| {
+            CullMode::ALL.map(|cull_mode| {
+                Self::create_render_pipeline(
+                    device,
+                    &pipeline_layout,
+                    &vertex_shader_module,
+                    depth_direction,
+                    cull_mode,
+                )
+            })
+        });
+
+        Self { render_pipelines }
+    }
+
+    /// The pipeline variant for the given depth direction and cull mode.
+    /// The cull mode must match the main pass, or culled faces would still occlude.
+    pub fn render_pipeline(
+        &self,
+        depth_direction: DepthDirection,
+        cull_mode: CullMode,
+    ) -> &wgpu::RenderPipeline {
+        &self.render_pipelines[depth_direction as usize][cull_mode as usize]
+    }
+
+    fn create_render_pipeline(
+        device: &wgpu::Device,
+        pipeline_layout: &wgpu::PipelineLayout,
+        vertex_shader_module: &wgpu::ShaderModule,
+        depth_direction: DepthDirection,
+        cull_mode: CullMode,
+    ) -> wgpu::RenderPipeline {
+        fallback::create_render_pipeline(
+            device,
+            &wgpu::RenderPipelineDescriptor {
+                label: Some(&format!(
+                    "depth_prepass_pipeline_{:?}_cull{:?}",
+                    depth_direction, cull_mode
+                )),
+                layout: Some(pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: vertex_shader_module,
+                    entry_point: "vs_main",
+                    buffers: &[BasicVertex::vertex_layout()],
+                    compilation_options: Default::default(),
+                },
+                fragment: None,
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: cull_mode.face(),
+                    unclipped_depth: false,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth32Float,
+                    depth_write_enabled: true,
+                    depth_compare: depth_direction.compare(),
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            },
+        )
+    }
+}
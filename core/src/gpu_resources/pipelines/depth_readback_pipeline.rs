@@ -0,0 +1,119 @@
+use bevy_ecs::{system::Resource, world::World};
+
+use crate::gpu_resources::render_resources::RenderResources;
+
+use super::super::shaders::depth_readback::SHADER_DESCRIPTOR_FRAGMENT;
+use super::super::shaders::depth_readback::SHADER_DESCRIPTOR_VERTEX;
+use super::fallback;
+
+/// Format of the intermediate target the depth value is copied into
+pub const DEPTH_READBACK_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Float;
+
+const DEPTH_READBACK_LAYOUT_DESCRIPTOR: wgpu::BindGroupLayoutDescriptor =
+    wgpu::BindGroupLayoutDescriptor {
+        label: Some("depth_readback_bind_group_layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                // bound as a plain float texture, since GL can't `textureLoad` a depth texture
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ],
+    };
+
+/// Pipeline copying one texel of the depth buffer into a 1x1 `R32Float` target
+#[derive(Resource)]
+pub struct DepthReadbackPipeline {
+    pub render_pipeline: wgpu::RenderPipeline,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+}
+
+impl DepthReadbackPipeline {
+    pub fn new(world: &World) -> Self {
+        let render_resources = world.get_resource::<RenderResources>().unwrap();
+        let device = &render_resources.device;
+
+        let bind_group_layout = device.create_bind_group_layout(&DEPTH_READBACK_LAYOUT_DESCRIPTOR);
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("depth_readback_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader_module = fallback::create_shader_module(device, SHADER_DESCRIPTOR_VERTEX);
+        let fragment_shader_module =
+            fallback::create_shader_module(device, SHADER_DESCRIPTOR_FRAGMENT);
+
+        let render_pipeline = fallback::create_render_pipeline(
+            device,
+            &wgpu::RenderPipelineDescriptor {
+                label: Some("depth_readback_pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &vertex_shader_module,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &fragment_shader_module,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: DEPTH_READBACK_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            },
+        );
+
+        Self {
+            render_pipeline,
+            bind_group_layout,
+        }
+    }
+
+    pub fn create_bind_group(
+        &self,
+        device: &wgpu::Device,
+        depth_view: &wgpu::TextureView,
+        pixel_buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("depth_readback_bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(depth_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: pixel_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+}
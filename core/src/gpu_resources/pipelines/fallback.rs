@@ -0,0 +1,321 @@
+/// Runs `create` inside a validation error scope, returning what it made and the first
+/// validation error raised, instead of letting wgpu's uncaptured error handler panic.
+pub fn catch_validation_error<T>(
+    device: &wgpu::Device,
+    create: impl FnOnce() -> T,
+) -> (T, Option<wgpu::Error>) {
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let created = create();
+    let error = futures::executor::block_on(device.pop_error_scope());
+    (created, error)
+}
+
+/// Creates a shader module, logging a validation failure with the shader's label.
+/// The returned module is invalid in that case, so `create_render_pipeline` falls back.
+pub fn create_shader_module(
+    device: &wgpu::Device,
+    descriptor: wgpu::ShaderModuleDescriptor,
+) -> wgpu::ShaderModule {
+    let label = descriptor.label.unwrap_or("unlabeled shader").to_string();
+    let (module, error) =
+        catch_validation_error(device, || device.create_shader_module(descriptor));
+    if let Some(error) = error {
+        log::error!("Shader {} failed to compile: {}", label, error);
+    }
+    module
+}
+
+/// Creates a render pipeline, or if it fails validation, logs the error and creates it again
+/// with a flat magenta fragment stage, so a broken shader shows up on screen instead of
+/// crashing. A depth-only pipeline gets a vertex stage that draws nothing instead.
+/// Only the failing stage's shader is replaced, so a broken fragment pipeline's vertex stage
+/// and any other invalid state still panic.
+pub fn create_render_pipeline(
+    device: &wgpu::Device,
+    descriptor: &wgpu::RenderPipelineDescriptor,
+) -> wgpu::RenderPipeline {
+    let (render_pipeline, error) =
+        catch_validation_error(device, || device.create_render_pipeline(descriptor));
+    let Some(error) = error else {
+        return render_pipeline;
+    };
+
+    let label = descriptor.label.unwrap_or("unlabeled pipeline");
+    log::error!(
+        "Pipeline {} failed to build, drawing it magenta: {}",
+        label,
+        error
+    );
+
+    let Some(fragment) = &descriptor.fragment else {
+        // a depth-only pipeline has no fragment stage to swap out, so skip its geometry
+        let empty_module = device.create_shader_module(EMPTY_VERTEX_SHADER_DESCRIPTOR);
+        return device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            vertex: wgpu::VertexState {
+                module: &empty_module,
+                entry_point: "vs_main",
+                compilation_options: Default::default(),
+                buffers: descriptor.vertex.buffers,
+            },
+            ..descriptor.clone()
+        });
+    };
+
+    let error_module = device.create_shader_module(error_shader_descriptor(fragment.entry_point));
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        fragment: Some(wgpu::FragmentState {
+            module: &error_module,
+            entry_point: fragment.entry_point,
+            targets: fragment.targets,
+            compilation_options: Default::default(),
+        }),
+        ..descriptor.clone()
+    })
+}
+
+/// A vertex shader placing every vertex outside the clip volume, so nothing is rasterized.
+/// It reads no bindings or vertex attributes, so it fits any layout and vertex buffers.
+const EMPTY_VERTEX_SHADER_DESCRIPTOR: wgpu::ShaderModuleDescriptor = wgpu::ShaderModuleDescriptor {
+    label: Some("empty_vertex_shader"),
+    source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(
+        "@vertex fn vs_main() -> @builtin(position) vec4<f32> { return vec4<f32>(0.0, 0.0, 2.0, 1.0); }",
+    )),
+};
+
+/// A fragment shader writing magenta, with the entry point the failed pipeline expects.
+/// It reads no bindings or vertex outputs, so it fits any layout and vertex stage.
+fn error_shader_descriptor(entry_point: &str) -> wgpu::ShaderModuleDescriptor<'static> {
+    wgpu::ShaderModuleDescriptor {
+        label: Some("error_shader"),
+        source: wgpu::ShaderSource::Wgsl(
+            format!(
+                "@fragment fn {}() -> @location(0) vec4<f32> {{ return vec4<f32>(1.0, 0.0, 1.0, 1.0); }}",
+                entry_point
+            )
+            .into(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_device::request_device;
+
+    const SIZE: u32 = 4;
+    /// Rows copied out of a texture must be padded to this many bytes
+    const PADDED_ROW_BYTES: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+    const VERTEX_SHADER: &str = r#"
+@vertex
+fn vs_main(@builtin(vertex_index) vertex: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((vertex << 1u) & 2u), f32(vertex & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+"#;
+
+    /// Returns a vec3 where a vec4 is expected
+    const BROKEN_FRAGMENT_SHADER: &str = r#"
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec3<f32>(0.0, 1.0, 0.0);
+}
+"#;
+
+    /// Returns a vec3 where a vec4 is expected
+    const BROKEN_VERTEX_SHADER: &str = r#"
+@vertex
+fn vs_main() -> @builtin(position) vec4<f32> {
+    return vec3<f32>(0.0, 0.0, 0.0);
+}
+"#;
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn broken_depth_only_pipeline_draws_nothing() {
+        let (device, queue) = request_device();
+        let format = wgpu::TextureFormat::Depth32Float;
+
+        let vertex_module = create_shader_module(
+            &device,
+            wgpu::ShaderModuleDescriptor {
+                label: Some("broken"),
+                source: wgpu::ShaderSource::Wgsl(BROKEN_VERTEX_SHADER.into()),
+            },
+        );
+        let pipeline = create_render_pipeline(
+            &device,
+            &wgpu::RenderPipelineDescriptor {
+                label: Some("broken_depth_only"),
+                layout: None,
+                vertex: wgpu::VertexState {
+                    module: &vertex_module,
+                    entry_point: "vs_main",
+                    compilation_options: Default::default(),
+                    buffers: &[],
+                },
+                fragment: None,
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil: Default::default(),
+                    bias: Default::default(),
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            },
+        );
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&Default::default());
+
+        let (_, error) = catch_validation_error(&device, || {
+            let mut encoder = device.create_command_encoder(&Default::default());
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[],
+                    depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                        view: &view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(1.0),
+                            store: wgpu::StoreOp::Store,
+                        }),
+                        stencil_ops: None,
+                    }),
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                render_pass.set_pipeline(&pipeline);
+                render_pass.draw(0..3, 0..1);
+            }
+            queue.submit([encoder.finish()]);
+        });
+        assert!(error.is_none(), "fallback pipeline is invalid: {:?}", error);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn broken_fragment_shader_draws_magenta() {
+        let (device, queue) = request_device();
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+
+        let vertex_module = create_shader_module(
+            &device,
+            wgpu::ShaderModuleDescriptor {
+                label: Some("vertex"),
+                source: wgpu::ShaderSource::Wgsl(VERTEX_SHADER.into()),
+            },
+        );
+        let fragment_module = create_shader_module(
+            &device,
+            wgpu::ShaderModuleDescriptor {
+                label: Some("broken"),
+                source: wgpu::ShaderSource::Wgsl(BROKEN_FRAGMENT_SHADER.into()),
+            },
+        );
+        let pipeline = create_render_pipeline(
+            &device,
+            &wgpu::RenderPipelineDescriptor {
+                label: Some("broken"),
+                layout: None,
+                vertex: wgpu::VertexState {
+                    module: &vertex_module,
+                    entry_point: "vs_main",
+                    compilation_options: Default::default(),
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &fragment_module,
+                    entry_point: "fs_main",
+                    compilation_options: Default::default(),
+                    targets: &[Some(format.into())],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            },
+        );
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&Default::default());
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (PADDED_ROW_BYTES * SIZE) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        // anything still invalid would fail the submit instead of drawing
+        let (_, error) = catch_validation_error(&device, || {
+            let mut encoder = device.create_command_encoder(&Default::default());
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                render_pass.set_pipeline(&pipeline);
+                render_pass.draw(0..3, 0..1);
+            }
+            encoder.copy_texture_to_buffer(
+                texture.as_image_copy(),
+                wgpu::ImageCopyBuffer {
+                    buffer: &readback,
+                    layout: wgpu::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(PADDED_ROW_BYTES),
+                        rows_per_image: None,
+                    },
+                },
+                texture.size(),
+            );
+            queue.submit([encoder.finish()]);
+        });
+        assert!(error.is_none(), "fallback pipeline is invalid: {:?}", error);
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        device.poll(wgpu::Maintain::Wait);
+        let texels = slice.get_mapped_range();
+        assert_eq!(&texels[..4], &[255, 0, 255, 255]);
+    }
+}
@@ -0,0 +1,263 @@
+use bevy_ecs::{system::Resource, world::World};
+
+use crate::gpu_resources::layouts::camera_uniform_layout::CameraUniformLayout;
+use crate::gpu_resources::render_resources::{HDR_FORMAT, RenderResources};
+use crate::gpu_resources::types::line_instance::LineInstance;
+
+use super::depth_direction::DepthDirection;
+use super::fallback;
+
+use super::super::shaders::line::SHADER_DESCRIPTOR_FRAGMENT;
+use super::super::shaders::line::SHADER_DESCRIPTOR_VERTEX;
+
+/// The screen space line pipeline, with one variant per `DepthDirection`
+#[derive(Resource)]
+pub struct LinePipeline {
+    render_pipelines: [wgpu::RenderPipeline; 2],
+}
+
+impl LinePipeline {
+    pub fn new(world: &World) -> Self {
+        let render_resources = world.get_resource::<RenderResources>().unwrap();
+        let device = &render_resources.device;
+
+        let camera_uniform_layout = &world.get_resource::<CameraUniformLayout>().unwrap().layout;
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("line_pipeline_layout"),
+            bind_group_layouts: &[camera_uniform_layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader_module = fallback::create_shader_module(device, SHADER_DESCRIPTOR_VERTEX);
+        let fragment_shader_module =
+            fallback::create_shader_module(device, SHADER_DESCRIPTOR_FRAGMENT);
+
+        let render_pipelines = DepthDirection::ALL.map(|depth_direction| {
+            Self::create_render_pipeline(
+                device,
+                &pipeline_layout,
+                &vertex_shader_module,
+                &fragment_shader_module,
+                HDR_FORMAT,
+                depth_direction,
+            )
+        });
+
+        Self { render_pipelines }
+    }
+
+    /// The pipeline variant for the given depth direction
+    pub fn render_pipeline(&self, depth_direction: DepthDirection) -> &wgpu::RenderPipeline {
+        &self.render_pipelines[depth_direction as usize]
+    }
+
+    fn create_render_pipeline(
+        device: &wgpu::Device,
+        pipeline_layout: &wgpu::PipelineLayout,
+        vertex_shader_module: &wgpu::ShaderModule,
+        fragment_shader_module: &wgpu::ShaderModule,
+        format: wgpu::TextureFormat,
+        depth_direction: DepthDirection,
+    ) -> wgpu::RenderPipeline {
+        fallback::create_render_pipeline(
+            device,
+            &wgpu::RenderPipelineDescriptor {
+                label: Some(&format!("line_pipeline_{:?}", depth_direction)),
+                layout: Some(pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: vertex_shader_module,
+                    entry_point: "vs_main",
+                    buffers: &[LineInstance::instance_layout()],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: fragment_shader_module,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    unclipped_depth: false,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                },
+                // Lines are depth tested against the scene but don't occlude each other
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth32Float,
+                    depth_write_enabled: false,
+                    depth_compare: depth_direction.compare(),
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use glam::{Mat4, Vec3, Vec4};
+    use wgpu::util::DeviceExt;
+
+    use super::*;
+    use crate::{
+        ecs::components::{gpu_bindings::line_bindings::LineBindings, lines::Lines},
+        gpu_resources::types::{gpu_camera::GpuCamera, gpu_type_macros::GpuUniformType},
+        utils::test_device::request_device,
+    };
+
+    const SIZE: u32 = 32;
+    /// `HDR_FORMAT` texels are 8 bytes, so a row of the target is already aligned for copying
+    const ROW_BYTES: u32 = SIZE * 8;
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn four_pixel_line_covers_four_rows() {
+        let (device, queue) = request_device();
+        let device = Arc::new(device);
+        let queue = Arc::new(queue);
+
+        let mut world = World::new();
+        world.insert_resource(CameraUniformLayout::new(&device));
+        world.insert_resource(RenderResources::new(
+            device.clone(),
+            queue.clone(),
+            HDR_FORMAT,
+        ));
+        let pipeline = LinePipeline::new(&world);
+
+        // with identity matrices world space is clip space
+        let size = SIZE as f32;
+        let gpu_camera = GpuCamera {
+            view: Mat4::IDENTITY,
+            proj: Mat4::IDENTITY,
+            view_proj: Mat4::IDENTITY,
+            viewport: Vec4::new(size, size, 1.0 / size, 1.0 / size),
+        };
+        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: &gpu_camera.as_buffer(),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let camera_bind_group = world
+            .resource::<CameraUniformLayout>()
+            .create_bind_group(&device, &camera_buffer);
+
+        // a hard edged line straight across the middle of the target
+        let mut lines = Lines::new().with_antialiasing(false);
+        lines.line(
+            Vec3::new(-1.0, 0.0, 0.5),
+            Vec3::new(1.0, 0.0, 0.5),
+            Vec4::ONE,
+            4.0,
+        );
+        let line_bindings = LineBindings::new(&device, &queue, &lines);
+
+        let size_3d = wgpu::Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        };
+        let color_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: size_3d,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: HDR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: size_3d,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let color_view = color_texture.create_view(&Default::default());
+        let depth_view = depth_texture.create_view(&Default::default());
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (ROW_BYTES * SIZE) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &color_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(DepthDirection::Standard.clear_value()),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(pipeline.render_pipeline(DepthDirection::Standard));
+            render_pass.set_bind_group(0, &camera_bind_group, &[]);
+            line_bindings.draw(&mut render_pass);
+        }
+        encoder.copy_texture_to_buffer(
+            color_texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(ROW_BYTES),
+                    rows_per_image: None,
+                },
+            },
+            size_3d,
+        );
+        queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        device.poll(wgpu::Maintain::Wait);
+        let texels = slice.get_mapped_range();
+
+        // the alpha half float of each texel in the middle column
+        let x = SIZE / 2;
+        let covered_rows: Vec<u32> = (0..SIZE)
+            .filter(|y| {
+                let alpha = (y * ROW_BYTES + x * 8 + 6) as usize;
+                texels[alpha..alpha + 2] != [0, 0]
+            })
+            .collect();
+        assert_eq!(covered_rows, vec![14, 15, 16, 17]);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::gpu_resources::layouts::texture_uniform_layout::TextureUniformLayout;
+
+use super::super::shaders::mipmap::SHADER_DESCRIPTOR_FRAGMENT;
+use super::super::shaders::mipmap::SHADER_DESCRIPTOR_VERTEX;
+use super::fallback;
+
+/// Fullscreen pipeline rendering a mip level from a linear sample of the level above it.
+/// Unlike the other pipelines it isn't a resource, textures build one for their own format
+/// while generating their mips and drop it afterwards.
+pub struct MipmapPipeline {
+    render_pipeline: wgpu::RenderPipeline,
+    texture_layout: TextureUniformLayout<1>,
+    sampler: wgpu::Sampler,
+}
+
+impl MipmapPipeline {
+    /// Whether mips of `format` can be generated, it must be both filterable and renderable
+    pub fn supports_format(device: &wgpu::Device, format: wgpu::TextureFormat) -> bool {
+        let features = format.guaranteed_format_features(device.features());
+        features
+            .allowed_usages
+            .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+            && features
+                .flags
+                .contains(wgpu::TextureFormatFeatureFlags::FILTERABLE)
+    }
+
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let texture_layout = TextureUniformLayout::<1>::new(device);
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("mipmap_pipeline_layout"),
+            bind_group_layouts: &[&texture_layout.layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader_module = fallback::create_shader_module(device, SHADER_DESCRIPTOR_VERTEX);
+        let fragment_shader_module =
+            fallback::create_shader_module(device, SHADER_DESCRIPTOR_FRAGMENT);
+
+        let render_pipeline = fallback::create_render_pipeline(
+            device,
+            &wgpu::RenderPipelineDescriptor {
+                label: Some(&format!("mipmap_pipeline_{:?}", format)),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &vertex_shader_module,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &fragment_shader_module,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            },
+        );
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("mipmap_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            render_pipeline,
+            texture_layout,
+            sampler,
+        }
+    }
+
+    /// Renders mip levels 1 to `mip_level_count - 1` of `texture`, each from the one before it.
+    /// Level 0 must already hold the image.
+    pub fn generate(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+        mip_level_count: u32,
+    ) {
+        let views: Vec<wgpu::TextureView> = (0..mip_level_count)
+            .map(|mip_level| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some(&format!("mipmap_view_{}", mip_level)),
+                    base_mip_level: mip_level,
+                    mip_level_count: Some(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Mipmap Encoder"),
+        });
+
+        for target_level in 1..mip_level_count as usize {
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("mipmap_bind_group"),
+                layout: &self.texture_layout.layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&views[target_level - 1]),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                ],
+            });
+
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Mipmap Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &views[target_level],
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+}
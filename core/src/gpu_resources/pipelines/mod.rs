@@ -1,9 +1,41 @@
 use bevy_ecs::world::World;
 
+pub mod blend_mode;
+pub mod bloom_pipeline;
+pub mod cull_mode;
+pub mod depth_direction;
+pub mod depth_prepass_pipeline;
+pub mod depth_readback_pipeline;
+pub mod fallback;
+pub mod line_pipeline;
+pub mod mipmap_pipeline;
+pub mod multi_texture_pipeline;
+pub mod occlusion_test_pipeline;
+pub mod particle_pipeline;
+pub mod tonemap_pipeline;
 pub mod unlit_diffuse_pipeline;
+pub mod vertex_color_pipeline;
 
 pub fn initialize_pipelines(world: &mut World) {
     let unlit_diffuse_pipeline = unlit_diffuse_pipeline::UnlitDiffusePipeline::new(world);
+    let particle_pipeline = particle_pipeline::ParticlePipeline::new(world);
+    let depth_readback_pipeline = depth_readback_pipeline::DepthReadbackPipeline::new(world);
+    let depth_prepass_pipeline = depth_prepass_pipeline::DepthPrepassPipeline::new(world);
+    let multi_texture_pipeline = multi_texture_pipeline::MultiTexturePipeline::new(world);
+    let tonemap_pipeline = tonemap_pipeline::TonemapPipeline::new(world);
+    let bloom_pipeline = bloom_pipeline::BloomPipeline::new(world);
+    let occlusion_test_pipeline = occlusion_test_pipeline::OcclusionTestPipeline::new(world);
+    let vertex_color_pipeline = vertex_color_pipeline::VertexColorPipeline::new(world);
+    let line_pipeline = line_pipeline::LinePipeline::new(world);
 
     world.insert_resource(unlit_diffuse_pipeline);
+    world.insert_resource(particle_pipeline);
+    world.insert_resource(depth_readback_pipeline);
+    world.insert_resource(depth_prepass_pipeline);
+    world.insert_resource(multi_texture_pipeline);
+    world.insert_resource(tonemap_pipeline);
+    world.insert_resource(bloom_pipeline);
+    world.insert_resource(occlusion_test_pipeline);
+    world.insert_resource(vertex_color_pipeline);
+    world.insert_resource(line_pipeline);
 }
@@ -0,0 +1,123 @@
+use bevy_ecs::{system::Resource, world::World};
+
+use crate::gpu_resources::layouts::camera_uniform_layout::CameraUniformLayout;
+use crate::gpu_resources::layouts::model_uniform_layout::ModelUniformLayout;
+use crate::gpu_resources::layouts::texture_uniform_layout::TextureUniformLayout;
+use crate::gpu_resources::render_resources::{HDR_FORMAT, RenderResources};
+use crate::gpu_resources::types::basic_vertex::BasicVertex;
+
+use super::super::shaders::multi_texture::SHADER_DESCRIPTOR_FRAGMENT;
+use super::super::shaders::multi_texture::SHADER_DESCRIPTOR_VERTEX;
+use super::depth_direction::DepthDirection;
+use super::fallback;
+
+/// Number of texture slots the multi texture shader samples: diffuse, detail and mask
+pub const MULTI_TEXTURE_SLOTS: usize = 3;
+
+/// Opaque pipeline that combines a diffuse, a tiled detail and a mask texture.
+/// One variant per `DepthDirection`.
+#[derive(Resource)]
+pub struct MultiTexturePipeline {
+    render_pipelines: [wgpu::RenderPipeline; 2],
+}
+
+impl MultiTexturePipeline {
+    pub fn new(world: &World) -> Self {
+        let render_resources = world.get_resource::<RenderResources>().unwrap();
+        let device = &render_resources.device;
+
+        let texture_uniform_layout = &world
+            .get_resource::<TextureUniformLayout<MULTI_TEXTURE_SLOTS>>()
+            .unwrap()
+            .layout;
+        let model_uniform_layout = &world.get_resource::<ModelUniformLayout>().unwrap().layout;
+        let camera_uniform_layout = &world.get_resource::<CameraUniformLayout>().unwrap().layout;
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("multi_texture_pipeline_layout"),
+            bind_group_layouts: &[
+                camera_uniform_layout,
+                model_uniform_layout,
+                texture_uniform_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader_module = fallback::create_shader_module(device, SHADER_DESCRIPTOR_VERTEX);
+        let fragment_shader_module =
+            fallback::create_shader_module(device, SHADER_DESCRIPTOR_FRAGMENT);
+
+        let render_pipelines = DepthDirection::ALL.map(|depth_direction| {
+            Self::create_render_pipeline(
+                device,
+                &pipeline_layout,
+                &vertex_shader_module,
+                &fragment_shader_module,
+                HDR_FORMAT,
+                depth_direction,
+            )
+        });
+
+        Self { render_pipelines }
+    }
+
+    /// The pipeline variant for the given depth direction
+    pub fn render_pipeline(&self, depth_direction: DepthDirection) -> &wgpu::RenderPipeline {
+        &self.render_pipelines[depth_direction as usize]
+    }
+
+    fn create_render_pipeline(
+        device: &wgpu::Device,
+        pipeline_layout: &wgpu::PipelineLayout,
+        vertex_shader_module: &wgpu::ShaderModule,
+        fragment_shader_module: &wgpu::ShaderModule,
+        format: wgpu::TextureFormat,
+        depth_direction: DepthDirection,
+    ) -> wgpu::RenderPipeline {
+        fallback::create_render_pipeline(
+            device,
+            &wgpu::RenderPipelineDescriptor {
+                label: Some(&format!("multi_texture_pipeline_{:?}", depth_direction)),
+                layout: Some(pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: vertex_shader_module,
+                    entry_point: "vs_main",
+                    buffers: &[BasicVertex::vertex_layout()],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: fragment_shader_module,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    unclipped_depth: false,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth32Float,
+                    depth_write_enabled: true,
+                    depth_compare: depth_direction.compare(),
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            },
+        )
+    }
+}
@@ -0,0 +1,98 @@
+use bevy_ecs::{system::Resource, world::World};
+
+use crate::gpu_resources::layouts::camera_uniform_layout::CameraUniformLayout;
+use crate::gpu_resources::layouts::model_uniform_layout::ModelUniformLayout;
+use crate::gpu_resources::render_resources::RenderResources;
+use crate::gpu_resources::types::basic_vertex::BasicVertex;
+
+use super::depth_direction::DepthDirection;
+use super::fallback;
+
+use super::super::shaders::unlit_diffuse::SHADER_DESCRIPTOR_VERTEX;
+
+/// Depth tests geometry without writing depth or color.
+/// Entities hidden by their last occlusion query are drawn with this inside a new query,
+/// so they are noticed once they come back into view without showing up in the frame.
+#[derive(Resource)]
+pub struct OcclusionTestPipeline {
+    render_pipelines: [wgpu::RenderPipeline; 2],
+}
+
+impl OcclusionTestPipeline {
+    pub fn new(world: &World) -> Self {
+        let render_resources = world.get_resource::<RenderResources>().unwrap();
+        let device = &render_resources.device;
+
+        let model_uniform_layout = &world.get_resource::<ModelUniformLayout>().unwrap().layout;
+        let camera_uniform_layout = &world.get_resource::<CameraUniformLayout>().unwrap().layout;
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("occlusion_test_pipeline_layout"),
+            bind_group_layouts: &[camera_uniform_layout, model_uniform_layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader_module = fallback::create_shader_module(device, SHADER_DESCRIPTOR_VERTEX);
+
+        let render_pipelines = DepthDirection::ALL.map(|depth_direction| {
+            Self::create_render_pipeline(
+                device,
+                &pipeline_layout,
+                &vertex_shader_module,
+                depth_direction,
+            )
+        });
+
+        Self { render_pipelines }
+    }
+
+    /// The pipeline variant for the given depth direction
+    pub fn render_pipeline(&self, depth_direction: DepthDirection) -> &wgpu::RenderPipeline {
+        &self.render_pipelines[depth_direction as usize]
+    }
+
+    fn create_render_pipeline(
+        device: &wgpu::Device,
+        pipeline_layout: &wgpu::PipelineLayout,
+        vertex_shader_module: &wgpu::ShaderModule,
+        depth_direction: DepthDirection,
+    ) -> wgpu::RenderPipeline {
+        fallback::create_render_pipeline(
+            device,
+            &wgpu::RenderPipelineDescriptor {
+                label: Some(&format!("occlusion_test_pipeline_{:?}", depth_direction)),
+                layout: Some(pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: vertex_shader_module,
+                    entry_point: "vs_main",
+                    buffers: &[BasicVertex::vertex_layout()],
+                    compilation_options: Default::default(),
+                },
+                fragment: None,
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    unclipped_depth: false,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth32Float,
+                    depth_write_enabled: false,
+                    // the pre-pass may already hold this geometry's own depth
+                    depth_compare: depth_direction.compare_or_equal(),
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            },
+        )
+    }
+}
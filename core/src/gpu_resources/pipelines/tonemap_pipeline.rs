@@ -0,0 +1,103 @@
+use bevy_ecs::{system::Resource, world::World};
+
+use crate::gpu_resources::{
+    layouts::texture_uniform_layout::TextureUniformLayout, render_resources::RenderResources,
+};
+
+use super::super::shaders::tonemap::SHADER_DESCRIPTOR_FRAGMENT;
+use super::super::shaders::tonemap::SHADER_DESCRIPTOR_VERTEX;
+use super::fallback;
+
+const TONEMAP_SETTINGS_LAYOUT_DESCRIPTOR: wgpu::BindGroupLayoutDescriptor =
+    wgpu::BindGroupLayoutDescriptor {
+        label: Some("tonemap_settings_bind_group_layout"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+    };
+
+/// Fullscreen pipeline tonemapping the HDR scene target into the surface format.
+/// Group 0 is the HDR texture, group 1 the `GpuTonemapSettings` uniform.
+#[derive(Resource)]
+pub struct TonemapPipeline {
+    pub render_pipeline: wgpu::RenderPipeline,
+    pub settings_layout: wgpu::BindGroupLayout,
+}
+
+impl TonemapPipeline {
+    pub fn new(world: &World) -> Self {
+        let render_resources = world.get_resource::<RenderResources>().unwrap();
+        let device = &render_resources.device;
+
+        let texture_uniform_layout = &world
+            .get_resource::<TextureUniformLayout<1>>()
+            .unwrap()
+            .layout;
+        let settings_layout = device.create_bind_group_layout(&TONEMAP_SETTINGS_LAYOUT_DESCRIPTOR);
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("tonemap_pipeline_layout"),
+            bind_group_layouts: &[texture_uniform_layout, &settings_layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader_module = fallback::create_shader_module(device, SHADER_DESCRIPTOR_VERTEX);
+        let fragment_shader_module =
+            fallback::create_shader_module(device, SHADER_DESCRIPTOR_FRAGMENT);
+
+        let render_pipeline = fallback::create_render_pipeline(
+            device,
+            &wgpu::RenderPipelineDescriptor {
+                label: Some("tonemap_pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &vertex_shader_module,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &fragment_shader_module,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: render_resources.surface_format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            },
+        );
+
+        Self {
+            render_pipeline,
+            settings_layout,
+        }
+    }
+
+    pub fn create_settings_bind_group(
+        &self,
+        device: &wgpu::Device,
+        settings_buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("tonemap_settings_bind_group"),
+            layout: &self.settings_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: settings_buffer.as_entire_binding(),
+            }],
+        })
+    }
+}
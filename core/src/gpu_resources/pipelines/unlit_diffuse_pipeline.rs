@@ -1,17 +1,81 @@
+use std::collections::HashMap;
+
 use bevy_ecs::{system::Resource, world::World};
 
 use crate::gpu_resources::layouts::camera_uniform_layout::CameraUniformLayout;
 use crate::gpu_resources::layouts::model_uniform_layout::ModelUniformLayout;
 use crate::gpu_resources::layouts::texture_uniform_layout::TextureUniformLayout;
-use crate::gpu_resources::render_resources::RenderResources;
+use crate::gpu_resources::render_resources::{HDR_FORMAT, RenderResources};
+use crate::gpu_resources::shader_preprocessor::{self, ShaderDefines};
 use crate::gpu_resources::types::basic_vertex::BasicVertex;
 
-use super::super::shaders::unlit_diffuse::SHADER_DESCRIPTOR_FRAGMENT;
 use super::super::shaders::unlit_diffuse::SHADER_DESCRIPTOR_VERTEX;
+use super::blend_mode::BlendMode;
+use super::cull_mode::CullMode;
+use super::depth_direction::DepthDirection;
+use super::fallback;
+
+/// Source of the fragment stage, preprocessed per variant
+const FRAGMENT_SOURCE: &str = include_str!("../shaders/unlit_diffuse_fragment.wgsl");
+
+/// Alpha below which an alpha cutout material discards the texel
+pub const ALPHA_CUTOFF: f32 = 0.5;
+
+/// Selects one of the cached `UnlitDiffusePipeline` variants
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct UnlitPipelineKey {
+    pub blend_mode: BlendMode,
+    /// Whether opaque depth was already written by a depth pre-pass
+    pub depth_prepass: bool,
+    pub depth_direction: DepthDirection,
+    pub cull_mode: CullMode,
+    /// Whether texels with alpha below `ALPHA_CUTOFF` are discarded
+    pub alpha_cutout: bool,
+}
 
+impl UnlitPipelineKey {
+    /// Transparent geometry is never pre-passed, so those keys share the regular variant.
+    /// Blending already handles alpha, so cutout only applies to opaque geometry,
+    /// which is then left out of the pre-pass since it writes depth for the discarded texels.
+    fn normalized(mut self) -> Self {
+        if self.blend_mode.is_transparent() {
+            self.alpha_cutout = false;
+        }
+        if self.blend_mode.is_transparent() || self.alpha_cutout {
+            self.depth_prepass = false;
+        }
+        self
+    }
+
+    /// Every distinct variant
+    fn all() -> impl Iterator<Item = UnlitPipelineKey> {
+        BlendMode::ALL.into_iter().flat_map(|blend_mode| {
+            DepthDirection::ALL
+                .into_iter()
+                .flat_map(move |depth_direction| {
+                    CullMode::ALL.into_iter().flat_map(move |cull_mode| {
+                        [false, true].into_iter().flat_map(move |alpha_cutout| {
+                            [false, true]
+                                .into_iter()
+                                .map(move |depth_prepass| UnlitPipelineKey {
+                                    blend_mode,
+                                    depth_prepass,
+                                    depth_direction,
+                                    cull_mode,
+                                    alpha_cutout,
+                                })
+                                .filter(|key| *key == key.normalized())
+                        })
+                    })
+                })
+        })
+    }
+}
+
+/// The unlit diffuse pipeline, with one cached variant per `UnlitPipelineKey`
 #[derive(Resource)]
 pub struct UnlitDiffusePipeline {
-    pub render_pipeline: wgpu::RenderPipeline,
+    render_pipelines: HashMap<UnlitPipelineKey, wgpu::RenderPipeline>,
 }
 
 impl UnlitDiffusePipeline {
@@ -36,52 +100,195 @@ impl UnlitDiffusePipeline {
             push_constant_ranges: &[],
         });
 
-        let vertex_shader_module = device.create_shader_module(SHADER_DESCRIPTOR_VERTEX);
-        let fragment_shader_module = device.create_shader_module(SHADER_DESCRIPTOR_FRAGMENT);
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("unlit_diffuse_pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &vertex_shader_module,
-                entry_point: "vs_main",
-                buffers: &[BasicVertex::vertex_layout()],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &fragment_shader_module,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: render_resources.surface_format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                unclipped_depth: false,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                conservative: false,
-            },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
+        let vertex_shader_module = fallback::create_shader_module(device, SHADER_DESCRIPTOR_VERTEX);
+        let fragment_shader_modules = [false, true].map(|alpha_cutout| {
+            Self::create_fragment_shader_module(device, alpha_cutout).unwrap_or_else(|error| {
+                log::error!("Shader failed to compile: {}", error);
+                // without an entry point the pipelines fall back to magenta
+                device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("unlit_diffuse::fs_main"),
+                    source: wgpu::ShaderSource::Wgsl("".into()),
+                })
+            })
         });
 
-        Self { render_pipeline }
+        let render_pipelines = UnlitPipelineKey::all()
+            .map(|key| {
+                let render_pipeline = Self::create_render_pipeline(
+                    device,
+                    &pipeline_layout,
+                    &vertex_shader_module,
+                    &fragment_shader_modules[key.alpha_cutout as usize],
+                    HDR_FORMAT,
+                    key,
+                );
+                (key, render_pipeline)
+            })
+            .collect();
+
+        Self { render_pipelines }
+    }
+
+    /// The pipeline variant for the given key
+    pub fn render_pipeline(&self, key: UnlitPipelineKey) -> &wgpu::RenderPipeline {
+        &self.render_pipelines[&key.normalized()]
+    }
+
+    /// Depth write and compare function used by a pipeline variant.
+    /// After a pre-pass, opaque geometry only passes where it matches the stored depth,
+    /// so each pixel is shaded once. Transparent geometry is never part of the pre-pass.
+    pub fn depth_state(key: UnlitPipelineKey) -> (bool, wgpu::CompareFunction) {
+        let key = key.normalized();
+        if key.depth_prepass {
+            (false, wgpu::CompareFunction::Equal)
+        } else {
+            (
+                key.blend_mode.depth_write_enabled(),
+                key.depth_direction.compare(),
+            )
+        }
+    }
+
+    /// Preprocesses and compiles the fragment stage, with or without alpha cutout
+    fn create_fragment_shader_module(
+        device: &wgpu::Device,
+        alpha_cutout: bool,
+    ) -> Result<wgpu::ShaderModule, String> {
+        let defines = if alpha_cutout {
+            ShaderDefines::new()
+                .flag("ALPHA_CUTOUT")
+                .value("ALPHA_CUTOFF", format!("{:?}", ALPHA_CUTOFF))
+        } else {
+            ShaderDefines::new()
+        };
+        shader_preprocessor::create_shader_module(
+            device,
+            "unlit_diffuse::fs_main",
+            FRAGMENT_SOURCE,
+            &defines,
+        )
+    }
+
+    fn create_render_pipeline(
+        device: &wgpu::Device,
+        pipeline_layout: &wgpu::PipelineLayout,
+        vertex_shader_module: &wgpu::ShaderModule,
+        fragment_shader_module: &wgpu::ShaderModule,
+        format: wgpu::TextureFormat,
+        key: UnlitPipelineKey,
+    ) -> wgpu::RenderPipeline {
+        let (depth_write_enabled, depth_compare) = Self::depth_state(key);
+        let label = format!(
+            "unlit_diffuse_pipeline_{:?}_{:?}_cull{:?}{}{}",
+            key.blend_mode,
+            key.depth_direction,
+            key.cull_mode,
+            if key.alpha_cutout { "_cutout" } else { "" },
+            if key.depth_prepass { "_prepassed" } else { "" }
+        );
+
+        fallback::create_render_pipeline(
+            device,
+            &wgpu::RenderPipelineDescriptor {
+                label: Some(&label),
+                layout: Some(pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: vertex_shader_module,
+                    entry_point: "vs_main",
+                    buffers: &[BasicVertex::vertex_layout()],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: fragment_shader_module,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: key.blend_mode.blend_state(),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: key.cull_mode.face(),
+                    unclipped_depth: false,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth32Float,
+                    depth_write_enabled,
+                    depth_compare,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{gpu_resources::initialize_gpu_resources, utils::test_device::request_device};
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn plain_and_cutout_fragment_stages_compile() {
+        let (device, _queue) = request_device();
+
+        for alpha_cutout in [false, true] {
+            let module = UnlitDiffusePipeline::create_fragment_shader_module(&device, alpha_cutout);
+            assert!(
+                module.is_ok(),
+                "cutout {}: {:?}",
+                alpha_cutout,
+                module.err()
+            );
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn cutout_keys_have_their_own_pipelines() {
+        let (device, queue) = request_device();
+
+        let mut world = World::new();
+        initialize_gpu_resources(
+            &mut world,
+            Arc::new(device),
+            Arc::new(queue),
+            wgpu::TextureFormat::Rgba8Unorm,
+        );
+
+        let pipeline = world.resource::<UnlitDiffusePipeline>();
+        let plain = UnlitPipelineKey::default();
+        let cutout = UnlitPipelineKey {
+            alpha_cutout: true,
+            ..plain
+        };
+        assert_ne!(
+            pipeline.render_pipeline(plain).global_id(),
+            pipeline.render_pipeline(cutout).global_id()
+        );
+        // cutout geometry is drawn with the regular depth test even after a pre-pass
+        let prepassed_cutout = UnlitPipelineKey {
+            depth_prepass: true,
+            ..cutout
+        };
+        assert_eq!(
+            UnlitDiffusePipeline::depth_state(prepassed_cutout),
+            UnlitDiffusePipeline::depth_state(cutout)
+        );
     }
 }
@@ -0,0 +1,111 @@
+use bevy_ecs::{system::Resource, world::World};
+
+use crate::gpu_resources::layouts::camera_uniform_layout::CameraUniformLayout;
+use crate::gpu_resources::layouts::model_uniform_layout::ModelUniformLayout;
+use crate::gpu_resources::render_resources::{HDR_FORMAT, RenderResources};
+use crate::gpu_resources::types::color_vertex::ColorVertex;
+
+use super::super::shaders::vertex_color::SHADER_DESCRIPTOR_FRAGMENT;
+use super::super::shaders::vertex_color::SHADER_DESCRIPTOR_VERTEX;
+use super::depth_direction::DepthDirection;
+use super::fallback;
+
+/// Opaque pipeline that outputs the interpolated color of `ColorVertex` meshes.
+/// One variant per `DepthDirection`.
+#[derive(Resource)]
+pub struct VertexColorPipeline {
+    render_pipelines: [wgpu::RenderPipeline; 2],
+}
+
+impl VertexColorPipeline {
+    pub fn new(world: &World) -> Self {
+        let render_resources = world.get_resource::<RenderResources>().unwrap();
+        let device = &render_resources.device;
+
+        let model_uniform_layout = &world.get_resource::<ModelUniformLayout>().unwrap().layout;
+        let camera_uniform_layout = &world.get_resource::<CameraUniformLayout>().unwrap().layout;
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("vertex_color_pipeline_layout"),
+            bind_group_layouts: &[camera_uniform_layout, model_uniform_layout],
+            push_constant_ranges: &[],
+        });
+
+        let vertex_shader_module = fallback::create_shader_module(device, SHADER_DESCRIPTOR_VERTEX);
+        let fragment_shader_module =
+            fallback::create_shader_module(device, SHADER_DESCRIPTOR_FRAGMENT);
+
+        let render_pipelines = DepthDirection::ALL.map(|depth_direction| {
+            Self::create_render_pipeline(
+                device,
+                &pipeline_layout,
+                &vertex_shader_module,
+                &fragment_shader_module,
+                HDR_FORMAT,
+                depth_direction,
+            )
+        });
+
+        Self { render_pipelines }
+    }
+
+    /// The pipeline variant for the given depth direction
+    pub fn render_pipeline(&self, depth_direction: DepthDirection) -> &wgpu::RenderPipeline {
+        &self.render_pipelines[depth_direction as usize]
+    }
+
+    fn create_render_pipeline(
+        device: &wgpu::Device,
+        pipeline_layout: &wgpu::PipelineLayout,
+        vertex_shader_module: &wgpu::ShaderModule,
+        fragment_shader_module: &wgpu::ShaderModule,
+        format: wgpu::TextureFormat,
+        depth_direction: DepthDirection,
+    ) -> wgpu::RenderPipeline {
+        fallback::create_render_pipeline(
+            device,
+            &wgpu::RenderPipelineDescriptor {
+                label: Some(&format!("vertex_color_pipeline_{:?}", depth_direction)),
+                layout: Some(pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: vertex_shader_module,
+                    entry_point: "vs_main",
+                    buffers: &[ColorVertex::vertex_layout()],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: fragment_shader_module,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    unclipped_depth: false,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: wgpu::TextureFormat::Depth32Float,
+                    depth_write_enabled: true,
+                    depth_compare: depth_direction.compare(),
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            },
+        )
+    }
+}
@@ -1,11 +1,17 @@
 use bevy_ecs::system::Resource;
 use std::sync::Arc;
 
+/// Format of the offscreen target the scene is rendered into before post-processing.
+/// Scene pipelines target this; only the final post-process pass writes `surface_format`.
+pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
 #[derive(Resource)]
 pub struct RenderResources {
     pub device: Arc<wgpu::Device>,
     pub queue: Arc<wgpu::Queue>,
     pub surface_format: wgpu::TextureFormat,
+    /// Largest width or height of a 2D texture, and so of any render target
+    pub max_texture_dimension_2d: u32,
 }
 
 impl RenderResources {
@@ -14,10 +20,13 @@ impl RenderResources {
         queue: Arc<wgpu::Queue>,
         surface_format: wgpu::TextureFormat,
     ) -> Self {
+        let max_texture_dimension_2d = device.limits().max_texture_dimension_2d;
+
         Self {
             device,
             queue,
             surface_format,
+            max_texture_dimension_2d,
         }
     }
 }
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use super::pipelines::fallback::catch_validation_error;
+
+/// Defines passed to `preprocess`, e.g. to toggle vertex colors or alpha cutout per pipeline.
+/// A define can be a plain flag for `#ifdef`, or carry a value that replaces the name in the source.
+#[derive(Debug, Clone, Default)]
+pub struct ShaderDefines {
+    defines: HashMap<String, Option<String>>,
+}
+
+impl ShaderDefines {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a flag that only affects `#ifdef`/`#ifndef`
+    pub fn flag(mut self, name: &str) -> Self {
+        self.defines.insert(name.to_string(), None);
+        self
+    }
+
+    /// Adds a define whose name is replaced by `value` wherever it appears as a whole word
+    pub fn value(mut self, name: &str, value: impl ToString) -> Self {
+        self.defines
+            .insert(name.to_string(), Some(value.to_string()));
+        self
+    }
+
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.defines.contains_key(name)
+    }
+}
+
+/// One open `#ifdef`/`#ifndef` block
+struct Conditional {
+    /// Line the block was opened on, for unterminated block errors
+    line: usize,
+    /// Whether the enclosing blocks are all active
+    parent_active: bool,
+    /// Whether the current branch of this block is active
+    active: bool,
+    /// Whether `#else` has already been seen
+    in_else: bool,
+}
+
+/// Resolves `#define`, `#ifdef`, `#ifndef`, `#else` and `#endif` directives in WGSL source.
+/// Directives must be on their own line. `#import` lines are passed through untouched.
+/// Unknown directives and unbalanced blocks are reported with their 1-based line number.
+pub fn preprocess(source: &str, defines: &ShaderDefines) -> Result<String, String> {
+    let mut defines = defines.clone();
+    let mut stack: Vec<Conditional> = Vec::new();
+    let mut output = String::with_capacity(source.len());
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let active = stack.last().is_none_or(|c| c.parent_active && c.active);
+        let trimmed = line.trim_start();
+
+        let Some(directive) = trimmed.strip_prefix('#') else {
+            if active {
+                output.push_str(&substitute(line, &defines));
+            }
+            output.push('\n');
+            continue;
+        };
+
+        let mut parts = directive.split_whitespace();
+        let keyword = parts.next().unwrap_or("");
+        let name = parts.next();
+
+        match keyword {
+            "ifdef" | "ifndef" => {
+                let name = name.ok_or_else(|| {
+                    format!("Line {}: #{} is missing a name", line_number, keyword)
+                })?;
+                let defined = defines.is_defined(name);
+                stack.push(Conditional {
+                    line: line_number,
+                    parent_active: active,
+                    active: if keyword == "ifdef" {
+                        defined
+                    } else {
+                        !defined
+                    },
+                    in_else: false,
+                });
+            }
+            "else" => {
+                let conditional = stack
+                    .last_mut()
+                    .ok_or_else(|| format!("Line {}: #else without #ifdef", line_number))?;
+                if conditional.in_else {
+                    return Err(format!("Line {}: duplicate #else", line_number));
+                }
+                conditional.in_else = true;
+                conditional.active = !conditional.active;
+            }
+            "endif" => {
+                stack
+                    .pop()
+                    .ok_or_else(|| format!("Line {}: #endif without #ifdef", line_number))?;
+            }
+            "define" => {
+                let name =
+                    name.ok_or_else(|| format!("Line {}: #define is missing a name", line_number))?;
+                if active {
+                    let value: Vec<&str> = parts.collect();
+                    let value = (!value.is_empty()).then(|| value.join(" "));
+                    defines.defines.insert(name.to_string(), value);
+                }
+            }
+            "import" => {
+                if active {
+                    output.push_str(line);
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Line {}: unknown directive #{}",
+                    line_number, keyword
+                ));
+            }
+        }
+
+        // keep line numbers in the output aligned with the source
+        output.push('\n');
+    }
+
+    if let Some(conditional) = stack.last() {
+        return Err(format!(
+            "Line {}: #ifdef is never closed with #endif",
+            conditional.line
+        ));
+    }
+
+    Ok(output)
+}
+
+/// Preprocesses `source` and creates a shader module from the result.
+/// Invalid WGSL is returned as an error rather than panicking, so a shader edited at runtime
+/// can be fixed and reloaded.
+pub fn create_shader_module(
+    device: &wgpu::Device,
+    label: &str,
+    source: &str,
+    defines: &ShaderDefines,
+) -> Result<wgpu::ShaderModule, String> {
+    let source = preprocess(source, defines).map_err(|e| format!("{}: {}", label, e))?;
+
+    let (module, error) = catch_validation_error(device, || {
+        device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        })
+    });
+    match error {
+        Some(error) => Err(format!("{}: {}", label, error)),
+        None => Ok(module),
+    }
+}
+
+/// Replaces whole word occurrences of valued defines in a line
+fn substitute(line: &str, defines: &ShaderDefines) -> String {
+    if defines.defines.values().all(Option::is_none) {
+        return line.to_string();
+    }
+
+    let mut result = String::with_capacity(line.len());
+    let mut word = String::new();
+
+    let flush = |word: &mut String, result: &mut String| {
+        match defines.defines.get(word.as_str()) {
+            Some(Some(value)) => result.push_str(value),
+            _ => result.push_str(word),
+        }
+        word.clear();
+    };
+
+    for c in line.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut result);
+            result.push(c);
+        }
+    }
+    flush(&mut word, &mut result);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "\
+#ifdef VERTEX_COLOR
+let color = in.color;
+#else
+let color = vec4<f32>(1.0);
+#endif
+let cutoff = ALPHA_CUTOFF;";
+
+    #[test]
+    fn different_defines_emit_different_source() {
+        let plain = preprocess(SOURCE, &ShaderDefines::new().value("ALPHA_CUTOFF", 0.5)).unwrap();
+        let colored = preprocess(
+            SOURCE,
+            &ShaderDefines::new()
+                .flag("VERTEX_COLOR")
+                .value("ALPHA_CUTOFF", 0.5),
+        )
+        .unwrap();
+
+        assert_ne!(plain, colored);
+        assert!(plain.contains("vec4<f32>(1.0)") && !plain.contains("in.color"));
+        assert!(colored.contains("in.color") && !colored.contains("vec4<f32>(1.0)"));
+        assert!(plain.contains("let cutoff = 0.5;"));
+    }
+
+    #[test]
+    fn output_keeps_source_line_numbers() {
+        let output = preprocess(SOURCE, &ShaderDefines::new()).unwrap();
+        assert_eq!(output.lines().count(), SOURCE.lines().count());
+    }
+
+    #[test]
+    fn define_in_source_applies_to_later_lines() {
+        let output = preprocess(
+            "#define SIZE 4\nvar<private> a: array<f32, SIZE>;",
+            &ShaderDefines::new(),
+        )
+        .unwrap();
+        assert!(output.contains("array<f32, 4>"));
+    }
+
+    #[test]
+    fn unknown_directive_reports_its_line() {
+        let error = preprocess("let a = 1;\n#pragma once", &ShaderDefines::new()).unwrap_err();
+        assert_eq!(error, "Line 2: unknown directive #pragma");
+    }
+
+    #[test]
+    fn unbalanced_blocks_are_errors() {
+        assert!(preprocess("#ifdef A\nlet a = 1;", &ShaderDefines::new()).is_err());
+        assert!(preprocess("#endif", &ShaderDefines::new()).is_err());
+        assert!(preprocess("#ifdef A\n#else\n#else\n#endif", &ShaderDefines::new()).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+// Bloom: bright pixels are thresholded into the first mip of a chain, blurred by
+// downsampling through the chain and upsampling back up, then added onto the HDR target.
+
+#define TEXTURE_GROUP 0
+#define TEXTURE_BINDING 0
+#import include/texture_sampler.wgsl as source
+
+#import include/bloom_settings.wgsl
+
+@group(1) @binding(0)
+var<uniform> settings: bloom_settings::BloomSettings;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Oversized triangle covering the whole target
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * 2.0 - vec2<f32>(1.0, 1.0), 0.0, 1.0);
+    // texture space has y pointing down
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+fn source_texel_size() -> vec2<f32> {
+    return 1.0 / vec2<f32>(textureDimensions(source::texture));
+}
+
+// Four bilinear taps around the pixel, averaging a 4x4 block of the source
+fn box_downsample(uv: vec2<f32>) -> vec3<f32> {
+    let offset = source_texel_size();
+    let a = source::sample_2D(uv + vec2<f32>(-offset.x, -offset.y)).rgb;
+    let b = source::sample_2D(uv + vec2<f32>(offset.x, -offset.y)).rgb;
+    let c = source::sample_2D(uv + vec2<f32>(-offset.x, offset.y)).rgb;
+    let d = source::sample_2D(uv + vec2<f32>(offset.x, offset.y)).rgb;
+    return (a + b + c + d) * 0.25;
+}
+
+// 3x3 tent filter scaled by the bloom radius
+fn tent_upsample(uv: vec2<f32>) -> vec3<f32> {
+    let offset = source_texel_size() * settings.radius;
+
+    var color = source::sample_2D(uv).rgb * 4.0;
+    color += source::sample_2D(uv + vec2<f32>(-offset.x, 0.0)).rgb * 2.0;
+    color += source::sample_2D(uv + vec2<f32>(offset.x, 0.0)).rgb * 2.0;
+    color += source::sample_2D(uv + vec2<f32>(0.0, -offset.y)).rgb * 2.0;
+    color += source::sample_2D(uv + vec2<f32>(0.0, offset.y)).rgb * 2.0;
+    color += source::sample_2D(uv + vec2<f32>(-offset.x, -offset.y)).rgb;
+    color += source::sample_2D(uv + vec2<f32>(offset.x, -offset.y)).rgb;
+    color += source::sample_2D(uv + vec2<f32>(-offset.x, offset.y)).rgb;
+    color += source::sample_2D(uv + vec2<f32>(offset.x, offset.y)).rgb;
+    return color / 16.0;
+}
+
+// `BloomSettings::prefilter` mirrors the threshold on the CPU
+@fragment
+fn fs_prefilter(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = box_downsample(in.uv);
+    let brightness = max(color.r, max(color.g, color.b));
+    let contribution = max(brightness - settings.threshold, 0.0) / max(brightness, 0.0001);
+    return vec4<f32>(color * contribution, 1.0);
+}
+
+@fragment
+fn fs_downsample(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(box_downsample(in.uv), 1.0);
+}
+
+// Blended additively onto the next larger mip
+@fragment
+fn fs_upsample(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(tent_upsample(in.uv), 1.0);
+}
+
+// Blended additively onto the HDR target
+@fragment
+fn fs_composite(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(tent_upsample(in.uv) * settings.intensity, 0.0);
+}
@@ -0,0 +1,23 @@
+// Copies a single texel of the depth buffer into a 1x1 R32Float target.
+// Depth formats can't be copied into a mappable buffer on every backend,
+// so the value is routed through a color target first.
+
+@group(0) @binding(0)
+var depth_texture: texture_2d<f32>;
+
+// xy: pixel to read, zw: unused padding
+@group(0) @binding(1)
+var<uniform> pixel: vec4<u32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    // Oversized triangle covering the whole 1x1 target
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return vec4<f32>(uv * 2.0 - vec2<f32>(1.0, 1.0), 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    let depth = textureLoad(depth_texture, vec2<i32>(pixel.xy), 0).r;
+    return vec4<f32>(depth, 0.0, 0.0, 1.0);
+}
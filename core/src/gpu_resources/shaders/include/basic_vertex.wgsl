@@ -2,5 +2,8 @@
 struct BasicVertex {
     @location(0) position: vec3<f32>,
     @location(1) tex_coords: vec2<f32>,
+    @location(2) normal: vec3<f32>,
+    // xyz along increasing u, w is the bitangent's handedness: cross(normal, tangent) * w
+    @location(3) tangent: vec4<f32>,
 }
 
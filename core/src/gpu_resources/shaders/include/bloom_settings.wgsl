@@ -0,0 +1,10 @@
+// Settings shared by every stage of the bloom pass
+
+@export struct BloomSettings {
+    // Brightness above which pixels start to bloom
+    threshold: f32,
+    // Scale of the blurred result when it is added back onto the scene
+    intensity: f32,
+    // Spread of the upsampling filter, in texels of the mip being read
+    radius: f32,
+}
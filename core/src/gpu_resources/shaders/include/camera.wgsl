@@ -7,3 +7,18 @@ var<uniform> camera: camera_h::CameraUniform;
 fn to_clip(pos: vec3<f32>) -> vec4<f32> {
     return camera.view_proj * vec4<f32>(pos, 1.0);
 }
+
+// World space right axis of the camera (first row of the view matrix)
+fn view_right() -> vec3<f32> {
+    return vec3<f32>(camera.view[0][0], camera.view[1][0], camera.view[2][0]);
+}
+
+// World space up axis of the camera (second row of the view matrix)
+fn view_up() -> vec3<f32> {
+    return vec3<f32>(camera.view[0][1], camera.view[1][1], camera.view[2][1]);
+}
+
+// Render target size in pixels
+fn viewport_size() -> vec2<f32> {
+    return camera.viewport.xy;
+}
@@ -2,4 +2,6 @@
     view: mat4x4<f32>,
     proj: mat4x4<f32>,
     view_proj: mat4x4<f32>,
+    // xy: render target size in pixels, zw: reciprocal of the size
+    viewport: vec4<f32>,
 }
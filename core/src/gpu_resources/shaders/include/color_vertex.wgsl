@@ -0,0 +1,5 @@
+@export
+struct ColorVertex {
+    @location(0) position: vec3<f32>,
+    @location(1) color: vec4<f32>,
+}
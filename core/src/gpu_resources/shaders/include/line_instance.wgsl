@@ -0,0 +1,11 @@
+// Per-instance data for a single line segment, expanded to a screen space quad.
+// Kept as three vec4s so the CPU-side layout matches the GPU without padding.
+
+@export
+struct LineInstance {
+    // xyz: world space start, w: line width in pixels
+    @location(0) start_width: vec4<f32>,
+    // xyz: world space end, w: antialiasing falloff in pixels, 0 for hard edges
+    @location(1) end_feather: vec4<f32>,
+    @location(2) color: vec4<f32>,
+}
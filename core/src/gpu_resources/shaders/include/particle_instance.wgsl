@@ -0,0 +1,9 @@
+// Per-instance data for a single billboarded particle.
+// Kept as two vec4s so the CPU-side layout matches the GPU without padding.
+
+@export
+struct ParticleInstance {
+    // xyz: world space position, w: quad size in world units
+    @location(0) position_size: vec4<f32>,
+    @location(1) color: vec4<f32>,
+}
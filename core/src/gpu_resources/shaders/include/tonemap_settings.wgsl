@@ -0,0 +1,12 @@
+// Settings for the final tonemapping pass of the post-process stack
+
+@export struct TonemapSettings {
+    // Scene colors are multiplied by this before tonemapping
+    exposure: f32,
+    // 0: ACES, 1: Reinhard
+    operator: u32,
+    // 1 when the output target isn't sRGB, so gamma is applied in the shader
+    apply_gamma: u32,
+    // 0 copies the HDR target straight to the output
+    enabled: u32,
+}
@@ -0,0 +1,91 @@
+#define CAMERA_GROUP 0
+#import include/camera.wgsl
+
+#import include/line_instance.wgsl
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // signed distance from the line center in pixels
+    @location(0) edge_distance: f32,
+    @location(1) color: vec4<f32>,
+    @location(2) half_width: f32,
+    @location(3) feather: f32,
+};
+
+// Keeps endpoints in front of the camera so the perspective divide stays valid
+const MIN_CLIP_W: f32 = 0.0001;
+
+fn clip_to_near(point: vec4<f32>, other: vec4<f32>) -> vec4<f32> {
+    if point.w >= MIN_CLIP_W || other.w < MIN_CLIP_W {
+        return point;
+    }
+    let t = (MIN_CLIP_W - point.w) / (other.w - point.w);
+    return mix(point, other, t);
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    instance: line_instance::LineInstance,
+) -> VertexOutput {
+    // x: 0 at the start and 1 at the end, y: side of the line
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+
+    let unclipped_start = camera::to_clip(instance.start_width.xyz);
+    let unclipped_end = camera::to_clip(instance.end_feather.xyz);
+    let clip_start = clip_to_near(unclipped_start, unclipped_end);
+    let clip_end = clip_to_near(unclipped_end, unclipped_start);
+
+    // Work out the segment direction in pixels so the width is independent of depth
+    let half_viewport = camera::viewport_size() * 0.5;
+    let screen_start = clip_start.xy / clip_start.w * half_viewport;
+    let screen_end = clip_end.xy / clip_end.w * half_viewport;
+    var direction = vec2<f32>(1.0, 0.0);
+    if distance(screen_start, screen_end) > 0.0001 {
+        direction = normalize(screen_end - screen_start);
+    }
+    let normal = vec2<f32>(-direction.y, direction.x);
+
+    // The quad grows by the falloff so the soft edge lies outside the requested width
+    let half_width = instance.start_width.w * 0.5;
+    let feather = max(instance.end_feather.w, 0.0);
+    let extent = half_width + feather;
+
+    // Extend past the endpoints as well so joined segments don't leave gaps
+    let offset = normal * corner.y * extent + direction * (corner.x * 2.0 - 1.0) * extent;
+
+    var clip_position = mix(clip_start, clip_end, corner.x);
+    clip_position = vec4<f32>(
+        clip_position.xy + offset / half_viewport * clip_position.w,
+        clip_position.zw,
+    );
+
+    var out: VertexOutput;
+    out.clip_position = clip_position;
+    out.edge_distance = corner.y * extent;
+    out.color = instance.color;
+    out.half_width = half_width;
+    out.feather = feather;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    var alpha = in.color.a;
+    if in.feather > 0.0 {
+        let distance = abs(in.edge_distance);
+        alpha *= 1.0 - smoothstep(in.half_width, in.half_width + in.feather, distance);
+    }
+    if alpha <= 0.0 {
+        discard;
+    }
+    return vec4<f32>(in.color.rgb, alpha);
+}
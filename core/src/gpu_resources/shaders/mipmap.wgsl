@@ -0,0 +1,28 @@
+// Downsamples one mip level into the next. Rendering at half size with a linear
+// sampler averages each 2x2 block of source texels into one destination texel.
+
+#define TEXTURE_GROUP 0
+#define TEXTURE_BINDING 0
+#import include/texture_sampler.wgsl as source
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Oversized triangle covering the whole target
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * 2.0 - vec2<f32>(1.0, 1.0), 0.0, 1.0);
+    // texture space has y pointing down
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return source::sample_2D(in.uv);
+}
@@ -2,7 +2,33 @@ use crate::{include_wgsl_shader, include_wgsl_shader_vertex_fragment};
 mod shader_macros;
 
 include_wgsl_shader!(r#"include/basic_vertex.wgsl"#, basic_vertex);
+include_wgsl_shader!(r#"include/bloom_settings.wgsl"#, bloom_settings);
 include_wgsl_shader!(r#"include/camera_h.wgsl"#, gpu_camera);
+include_wgsl_shader!(r#"include/color_vertex.wgsl"#, color_vertex);
+include_wgsl_shader!(r#"include/line_instance.wgsl"#, line_instance);
 include_wgsl_shader!(r#"include/model_h.wgsl"#, gpu_model);
+include_wgsl_shader!(r#"include/particle_instance.wgsl"#, particle_instance);
+include_wgsl_shader!(r#"include/tonemap_settings.wgsl"#, tonemap_settings);
 
-include_wgsl_shader_vertex_fragment!(r#"unlit_diffuse.wgsl"#, unlit_diffuse);
+include_wgsl_shader_vertex_fragment!(r#"particle.wgsl"#, particle);
+include_wgsl_shader_vertex_fragment!(r#"line.wgsl"#, line);
+include_wgsl_shader_vertex_fragment!(r#"depth_readback.wgsl"#, depth_readback);
+include_wgsl_shader_vertex_fragment!(r#"multi_texture.wgsl"#, multi_texture);
+include_wgsl_shader_vertex_fragment!(r#"tonemap.wgsl"#, tonemap);
+include_wgsl_shader_vertex_fragment!(r#"vertex_color.wgsl"#, vertex_color);
+include_wgsl_shader_vertex_fragment!(r#"mipmap.wgsl"#, mipmap);
+// the fragment stage is preprocessed at runtime, see `UnlitDiffusePipeline`
+include_wgsl_shader!(
+    r#"unlit_diffuse.wgsl"#,
+    unlit_diffuse,
+    vs_main as SHADER_DESCRIPTOR_VERTEX
+);
+include_wgsl_shader!(
+    r#"bloom.wgsl"#,
+    bloom,
+    vs_main as SHADER_DESCRIPTOR_VERTEX,
+    fs_prefilter as SHADER_DESCRIPTOR_PREFILTER,
+    fs_downsample as SHADER_DESCRIPTOR_DOWNSAMPLE,
+    fs_upsample as SHADER_DESCRIPTOR_UPSAMPLE,
+    fs_composite as SHADER_DESCRIPTOR_COMPOSITE
+);
@@ -0,0 +1,49 @@
+#define CAMERA_GROUP 0
+#import include/camera.wgsl
+
+#define MODEL_GROUP 1
+#import include/model.wgsl
+
+#import include/basic_vertex.wgsl
+
+// Texture slot i of TextureUniformLayout<3> is bound at binding 2i, its sampler at 2i + 1
+@group(2) @binding(0)
+var diffuse_texture: texture_2d<f32>;
+@group(2) @binding(1)
+var diffuse_sampler: sampler;
+@group(2) @binding(2)
+var detail_texture: texture_2d<f32>;
+@group(2) @binding(3)
+var detail_sampler: sampler;
+@group(2) @binding(4)
+var mask_texture: texture_2d<f32>;
+@group(2) @binding(5)
+var mask_sampler: sampler;
+
+// How many times the detail texture repeats across the diffuse texture
+const DETAIL_TILING: f32 = 8.0;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+@vertex
+fn vs_main(in: basic_vertex::BasicVertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.tex_coords = in.tex_coords;
+    out.clip_position = camera::to_clip(model::to_world(in.position));
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let diffuse = textureSample(diffuse_texture, diffuse_sampler, in.tex_coords);
+    let detail = textureSample(detail_texture, detail_sampler, in.tex_coords * DETAIL_TILING);
+    let mask = textureSample(mask_texture, mask_sampler, in.tex_coords).r;
+
+    // detail is centered on mid grey so 0.5 leaves the diffuse unchanged,
+    // and the mask's red channel controls where it is applied
+    let detailed = diffuse.rgb * detail.rgb * 2.0;
+    return vec4<f32>(mix(diffuse.rgb, detailed, mask), diffuse.a);
+}
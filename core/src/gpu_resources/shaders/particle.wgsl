@@ -0,0 +1,50 @@
+#define CAMERA_GROUP 0
+#import include/camera.wgsl
+
+#import include/particle_instance.wgsl
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    instance: particle_instance::ParticleInstance,
+) -> VertexOutput {
+    // Two triangles forming a unit quad centered on the particle
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-0.5, -0.5),
+        vec2<f32>(0.5, -0.5),
+        vec2<f32>(0.5, 0.5),
+        vec2<f32>(-0.5, -0.5),
+        vec2<f32>(0.5, 0.5),
+        vec2<f32>(-0.5, 0.5),
+    );
+    let corner = corners[vertex_index];
+    let size = instance.position_size.w;
+
+    // Expand the quad along the camera axes so it always faces the viewer
+    let world_position = instance.position_size.xyz
+        + camera::view_right() * corner.x * size
+        + camera::view_up() * corner.y * size;
+
+    var out: VertexOutput;
+    out.clip_position = camera::to_clip(world_position);
+    out.uv = corner + vec2<f32>(0.5, 0.5);
+    out.color = instance.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Soft round sprite
+    let distance = length(in.uv * 2.0 - vec2<f32>(1.0, 1.0));
+    let alpha = in.color.a * (1.0 - smoothstep(0.8, 1.0, distance));
+    if alpha <= 0.0 {
+        discard;
+    }
+    return vec4<f32>(in.color.rgb, alpha);
+}
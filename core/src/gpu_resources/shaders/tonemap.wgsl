@@ -0,0 +1,63 @@
+// Final post-process pass: maps the HDR scene target into the output's displayable range.
+
+#define TEXTURE_GROUP 0
+#define TEXTURE_BINDING 0
+#import include/texture_sampler.wgsl as hdr
+
+#import include/tonemap_settings.wgsl
+
+@group(1) @binding(0)
+var<uniform> settings: tonemap_settings::TonemapSettings;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    // Oversized triangle covering the whole target
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * 2.0 - vec2<f32>(1.0, 1.0), 0.0, 1.0);
+    // texture space has y pointing down
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+fn reinhard(color: vec3<f32>) -> vec3<f32> {
+    return color / (color + vec3<f32>(1.0));
+}
+
+// Narkowicz's fit of the ACES filmic curve
+fn aces(color: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return clamp((color * (a * color + b)) / (color * (c * color + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let hdr_color = hdr::sample_2D(in.uv);
+    if settings.enabled == 0u {
+        return hdr_color;
+    }
+
+    let exposed = hdr_color.rgb * settings.exposure;
+    var color: vec3<f32>;
+    if settings.operator == 1u {
+        color = reinhard(exposed);
+    } else {
+        color = aces(exposed);
+    }
+
+    if settings.apply_gamma == 1u {
+        color = pow(color, vec3<f32>(1.0 / 2.2));
+    }
+
+    return vec4<f32>(color, hdr_color.a);
+}
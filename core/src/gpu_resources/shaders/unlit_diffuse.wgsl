@@ -4,10 +4,6 @@
 #define MODEL_GROUP 1
 #import include/model.wgsl
 
-#define TEXTURE_GROUP 2
-#define TEXTURE_BINDING 0
-#import include/texture_sampler.wgsl as diffuse
-
 #import include/basic_vertex.wgsl
 
 struct VertexOutput {
@@ -22,9 +18,3 @@ fn vs_main(in: basic_vertex::BasicVertex) -> VertexOutput {
     out.clip_position = camera::to_clip(model::to_world(in.position));
     return out;
 }
-
-
-@fragment
-fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return diffuse::sample_2D(in.tex_coords.xy);
-}
@@ -0,0 +1,19 @@
+// Fragment stage of the unlit diffuse pipeline. It is built at runtime through
+// `shader_preprocessor`, so one source covers every variant:
+// ALPHA_CUTOUT discards texels whose alpha is below ALPHA_CUTOFF.
+
+@group(2) @binding(0)
+var diffuse_texture: texture_2d<f32>;
+@group(2) @binding(1)
+var diffuse_sampler: sampler;
+
+@fragment
+fn fs_main(@location(0) tex_coords: vec2<f32>) -> @location(0) vec4<f32> {
+    let color = textureSample(diffuse_texture, diffuse_sampler, tex_coords);
+#ifdef ALPHA_CUTOUT
+    if color.a < ALPHA_CUTOFF {
+        discard;
+    }
+#endif
+    return color;
+}
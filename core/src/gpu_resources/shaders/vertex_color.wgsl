@@ -0,0 +1,25 @@
+#define CAMERA_GROUP 0
+#import include/camera.wgsl
+
+#define MODEL_GROUP 1
+#import include/model.wgsl
+
+#import include/color_vertex.wgsl
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(in: color_vertex::ColorVertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.color = in.color;
+    out.clip_position = camera::to_clip(model::to_world(in.position));
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
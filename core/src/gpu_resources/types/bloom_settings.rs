@@ -0,0 +1,17 @@
+use crate::{
+    define_gpu_data_type, ecs::resources::bloom_settings::BloomSettings as BloomSettingsResource,
+};
+
+define_gpu_data_type!(
+    super::super::shaders::bloom_settings::naga::types::BloomSettings as GpuBloomSettings
+);
+
+impl GpuBloomSettings {
+    pub fn from_settings(settings: &BloomSettingsResource) -> Self {
+        Self {
+            threshold: settings.threshold,
+            intensity: settings.intensity,
+            radius: settings.radius,
+        }
+    }
+}
@@ -0,0 +1,3 @@
+use crate::define_gpu_data_type;
+
+define_gpu_data_type!(super::super::shaders::color_vertex::naga::types::ColorVertex as ColorVertex);
@@ -1,19 +1,45 @@
+use glam::{Vec2, Vec4};
+
 use crate::{
     define_gpu_data_type,
     ecs::components::{camera::Camera, transform::Transform},
 };
 
-define_gpu_data_type!(super::super::shaders::gpu_camera::naga::types::CameraUniform as GpuCamera);
+// three mat4x4s and the viewport vec4
+define_gpu_data_type!(
+    super::super::shaders::gpu_camera::naga::types::CameraUniform as GpuCamera,
+    size = 3 * 64 + 16
+);
 
 impl GpuCamera {
-    pub fn from_camera_and_transform(camera: &mut Camera, transform: &mut Transform) -> Self {
+    pub fn from_camera_and_transform(
+        camera: &mut Camera,
+        transform: &mut Transform,
+        viewport_size: Vec2,
+    ) -> Self {
         let view = transform.get_trs_matrix().inverse();
         let proj = camera.get_projection_matrix();
         Self {
             view,
             proj,
             view_proj: proj * view,
+            viewport: Self::viewport_from_size(viewport_size),
+        }
+    }
+
+    fn viewport_from_size(size: Vec2) -> Vec4 {
+        let size = size.max(Vec2::ONE);
+        Vec4::new(size.x, size.y, 1.0 / size.x, 1.0 / size.y)
+    }
+
+    /// Updates the render target size used by screen space shaders, returns true if it changed
+    pub fn update_viewport(&mut self, viewport_size: Vec2) -> bool {
+        let viewport = Self::viewport_from_size(viewport_size);
+        if viewport == self.viewport {
+            return false;
         }
+        self.viewport = viewport;
+        true
     }
 
     pub fn update_view_proj(&mut self, camera: &mut Camera, transform: &mut Transform) -> bool {
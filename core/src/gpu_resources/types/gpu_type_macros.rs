@@ -20,6 +20,29 @@ where
     }
 }
 
+/// # Safety
+/// This trait should only be implemented by the below macros
+pub unsafe trait GpuStorageType: GpuType {
+    /// Packs the value for a storage buffer, which unlike uniforms allows arrays strided below 16 bytes
+    fn as_storage_buffer(&self) -> Vec<u8>;
+}
+
+unsafe impl<T> GpuStorageType for T
+where
+    T: encase::ShaderType + GpuType + encase::internal::WriteInto,
+{
+    fn as_storage_buffer(&self) -> Vec<u8> {
+        let mut buffer = encase::StorageBuffer::new(Vec::new());
+        buffer.write(self).unwrap();
+
+        buffer.into_inner()
+    }
+}
+
+/// Aliases a struct generated from an `@export` WGSL struct.
+/// Fixed-size arrays and nested structs are laid out by `encase`, so they need no extra handling.
+/// Passing `size = N` checks at compile time that the packed size matches the WGSL struct,
+/// so a layout drift between the shader and the Rust side fails the build.
 #[macro_export]
 macro_rules! define_gpu_data_type {
     ($original:path as $alias:ident) => {
@@ -27,4 +50,67 @@ macro_rules! define_gpu_data_type {
 
         unsafe impl $crate::gpu_resources::types::gpu_type_macros::GpuType for $alias {}
     };
+    ($original:path as $alias:ident, size = $size:expr) => {
+        $crate::define_gpu_data_type!($original as $alias);
+
+        const _: () = assert!(
+            <$alias as encase::ShaderSize>::SHADER_SIZE.get() == $size,
+            concat!(
+                stringify!($alias),
+                " does not match the size of its WGSL struct"
+            )
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec4;
+
+    use super::*;
+
+    /// Stands in for a struct include-wgsl-oil generates from
+    /// `struct Lights { count: u32, colors: array<vec4<f32>, 4> }`
+    #[derive(encase::ShaderType, Debug, PartialEq)]
+    pub struct LightsUniform {
+        count: u32,
+        colors: [Vec4; 4],
+    }
+
+    // the array is 16 byte aligned, so `count` is padded to a full vec4
+    crate::define_gpu_data_type!(self::LightsUniform as Lights, size = 16 + 4 * 16);
+
+    /// A storage-only struct, uniforms would need each `f32` padded to 16 bytes
+    #[derive(encase::ShaderType)]
+    pub struct WeightsStorage {
+        weights: [f32; 4],
+    }
+
+    crate::define_gpu_data_type!(self::WeightsStorage as Weights, size = 4 * 4);
+
+    #[test]
+    fn array_uniform_round_trips_through_as_buffer() {
+        let lights = Lights {
+            count: 2,
+            colors: [Vec4::X, Vec4::Y, Vec4::Z, Vec4::W],
+        };
+
+        let bytes = lights.as_buffer();
+        assert_eq!(bytes.len(), 80);
+        assert_eq!(bytes[0..4], 2u32.to_ne_bytes());
+        // the second color starts at the second element of the array
+        assert_eq!(bytes[36..40], 1.0f32.to_ne_bytes());
+
+        let read: Lights = encase::UniformBuffer::new(bytes).create().unwrap();
+        assert_eq!(read, lights);
+    }
+
+    #[test]
+    fn storage_arrays_are_tightly_packed() {
+        let weights = Weights {
+            weights: [0.1, 0.2, 0.3, 0.4],
+        };
+
+        assert_eq!(weights.as_storage_buffer().len(), 16);
+    }
 }
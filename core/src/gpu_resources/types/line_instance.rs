@@ -0,0 +1,19 @@
+use crate::define_gpu_data_type;
+
+define_gpu_data_type!(
+    super::super::shaders::line_instance::naga::types::LineInstance as LineInstance
+);
+
+const LINE_INSTANCE_ATTRIBUTES: [wgpu::VertexAttribute; 3] =
+    wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x4, 2 => Float32x4];
+
+impl LineInstance {
+    /// Vertex buffer layout stepping once per line segment
+    pub fn instance_layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<LineInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &LINE_INSTANCE_ATTRIBUTES,
+        }
+    }
+}
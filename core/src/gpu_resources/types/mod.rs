@@ -1,4 +1,9 @@
 pub mod basic_vertex;
+pub mod bloom_settings;
+pub mod color_vertex;
 pub mod gpu_camera;
 pub mod gpu_model;
 pub mod gpu_type_macros;
+pub mod line_instance;
+pub mod particle_instance;
+pub mod tonemap_settings;
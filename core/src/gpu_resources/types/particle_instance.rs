@@ -0,0 +1,19 @@
+use crate::define_gpu_data_type;
+
+define_gpu_data_type!(
+    super::super::shaders::particle_instance::naga::types::ParticleInstance as ParticleInstance
+);
+
+const PARTICLE_INSTANCE_ATTRIBUTES: [wgpu::VertexAttribute; 2] =
+    wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x4];
+
+impl ParticleInstance {
+    /// Vertex buffer layout stepping once per particle instance
+    pub fn instance_layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ParticleInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &PARTICLE_INSTANCE_ATTRIBUTES,
+        }
+    }
+}
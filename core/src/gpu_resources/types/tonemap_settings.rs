@@ -0,0 +1,27 @@
+use crate::{
+    define_gpu_data_type, ecs::resources::post_process_settings::PostProcessSettings,
+    utils::tonemap::TonemapOperator,
+};
+
+define_gpu_data_type!(
+    super::super::shaders::tonemap_settings::naga::types::TonemapSettings as GpuTonemapSettings
+);
+
+impl GpuTonemapSettings {
+    /// `surface_format` decides whether gamma has to be applied in the shader,
+    /// since sRGB targets already encode on write
+    pub fn from_settings(
+        settings: &PostProcessSettings,
+        surface_format: wgpu::TextureFormat,
+    ) -> Self {
+        Self {
+            exposure: settings.exposure,
+            operator: match settings.tonemap {
+                TonemapOperator::Aces => 0,
+                TonemapOperator::Reinhard => 1,
+            },
+            apply_gamma: (!surface_format.is_srgb()) as u32,
+            enabled: settings.enabled as u32,
+        }
+    }
+}
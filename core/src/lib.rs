@@ -6,3 +6,6 @@ mod gpu_resources;
 mod render;
 pub mod traits;
 mod utils;
+
+#[cfg(feature = "test-utils")]
+pub use utils::test_device;
@@ -0,0 +1,226 @@
+use bevy_ecs::world::World;
+use wgpu::util::DeviceExt;
+
+use crate::{
+    ecs::resources::bloom_settings::BloomSettings,
+    gpu_resources::{
+        layouts::texture_uniform_layout::TextureUniformLayout,
+        pipelines::bloom_pipeline::BloomPipeline,
+        render_resources::{HDR_FORMAT, RenderResources},
+        types::{bloom_settings::GpuBloomSettings, gpu_type_macros::GpuUniformType},
+    },
+    utils::texture::{SamplerConfig, Texture, TextureBuilder},
+};
+
+use super::post_process_stack::PostProcessPass;
+
+/// Upper limit for the number of mips in the bloom chain. Each extra mip spreads the glow twice as far.
+const MAX_BLOOM_MIPS: u32 = 6;
+
+/// The mip chain bright pixels are blurred through, starting at half the size of the HDR target
+struct BloomChain {
+    /// Reads the HDR target for the prefilter
+    hdr_bind_group: wgpu::BindGroup,
+    /// One view per mip, for rendering into it
+    mip_views: Vec<wgpu::TextureView>,
+    /// One bind group per mip, for reading from it
+    mip_bind_groups: Vec<wgpu::BindGroup>,
+}
+
+/// Post-process pass adding a blurred copy of the scene's brightest pixels back onto the HDR target
+pub struct BloomPass {
+    settings: Option<BloomSettings>,
+    settings_buffer: wgpu::Buffer,
+    settings_bind_group: wgpu::BindGroup,
+    chain: Option<BloomChain>,
+}
+
+impl BloomPass {
+    pub fn new(world: &World) -> Self {
+        let device = &world.get_resource::<RenderResources>().unwrap().device;
+        let bloom_pipeline = world.get_resource::<BloomPipeline>().unwrap();
+
+        let settings_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Bloom Settings Buffer"),
+            contents: &GpuBloomSettings::from_settings(&BloomSettings::default()).as_buffer(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let settings_bind_group =
+            bloom_pipeline.create_settings_bind_group(device, &settings_buffer);
+
+        Self {
+            settings: None,
+            settings_buffer,
+            settings_bind_group,
+            chain: None,
+        }
+    }
+
+    /// Number of mips for a chain whose first mip is `width` x `height`
+    fn mip_count(width: u32, height: u32) -> u32 {
+        let smallest_side = width.min(height).max(1);
+        (smallest_side.ilog2() + 1).min(MAX_BLOOM_MIPS)
+    }
+
+    fn draw_fullscreen(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        label: &str,
+        pipeline: &wgpu::RenderPipeline,
+        source: &wgpu::BindGroup,
+        target: &wgpu::TextureView,
+        load: wgpu::LoadOp<wgpu::Color>,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some(label),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, source, &[]);
+        render_pass.set_bind_group(1, &self.settings_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+impl PostProcessPass for BloomPass {
+    fn set_target(&mut self, world: &World, hdr_target: &Texture) {
+        let device = &world.get_resource::<RenderResources>().unwrap().device;
+        let texture_uniform_layout = world.get_resource::<TextureUniformLayout<1>>().unwrap();
+
+        let (hdr_width, hdr_height) = hdr_target.dimensions;
+        let width = (hdr_width / 2).max(1);
+        let height = (hdr_height / 2).max(1);
+        let mip_count = Self::mip_count(width, height);
+
+        let texture = TextureBuilder::new(device)
+            .size(width, height)
+            .render_target(1)
+            .format(HDR_FORMAT)
+            .mip_level_count(mip_count)
+            .label("Bloom Chain")
+            .sampler_config(SamplerConfig {
+                mag_filter: Some("Linear".to_string()),
+                min_filter: Some("Linear".to_string()),
+                ..Default::default()
+            })
+            .build()
+            .expect("Failed to create bloom chain");
+
+        let mip_views: Vec<wgpu::TextureView> = (0..mip_count)
+            .map(|mip| {
+                texture.texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some(&format!("Bloom Chain Mip {}", mip)),
+                    base_mip_level: mip,
+                    mip_level_count: Some(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        let mip_bind_groups = mip_views
+            .iter()
+            .enumerate()
+            .map(|(mip, view)| {
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some(&format!("bloom_chain_mip_{}_bind_group", mip)),
+                    layout: &texture_uniform_layout.layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(&texture.sampler),
+                        },
+                    ],
+                })
+            })
+            .collect();
+
+        self.chain = Some(BloomChain {
+            hdr_bind_group: texture_uniform_layout
+                .create_bind_group_for_slot(device, hdr_target, 0),
+            mip_views,
+            mip_bind_groups,
+        });
+    }
+
+    fn render(&mut self, world: &World, encoder: &mut wgpu::CommandEncoder, hdr_target: &Texture) {
+        let render_resources = world.get_resource::<RenderResources>().unwrap();
+        let bloom_pipeline = world.get_resource::<BloomPipeline>().unwrap();
+        let settings = *world.get_resource::<BloomSettings>().unwrap();
+
+        if settings.intensity <= 0.0 {
+            return;
+        }
+
+        if self.settings != Some(settings) {
+            render_resources.queue.write_buffer(
+                &self.settings_buffer,
+                0,
+                &GpuBloomSettings::from_settings(&settings).as_buffer(),
+            );
+            self.settings = Some(settings);
+        }
+
+        let Some(chain) = &self.chain else {
+            return;
+        };
+        let mip_count = chain.mip_views.len();
+
+        // keep only the bright pixels, at half resolution
+        self.draw_fullscreen(
+            encoder,
+            "Bloom Prefilter",
+            &bloom_pipeline.prefilter_pipeline,
+            &chain.hdr_bind_group,
+            &chain.mip_views[0],
+            wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+        );
+
+        // blur by halving the resolution down the chain
+        for mip in 1..mip_count {
+            self.draw_fullscreen(
+                encoder,
+                "Bloom Downsample",
+                &bloom_pipeline.downsample_pipeline,
+                &chain.mip_bind_groups[mip - 1],
+                &chain.mip_views[mip],
+                wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+            );
+        }
+
+        // and accumulate the blurred mips back up
+        for mip in (1..mip_count).rev() {
+            self.draw_fullscreen(
+                encoder,
+                "Bloom Upsample",
+                &bloom_pipeline.upsample_pipeline,
+                &chain.mip_bind_groups[mip],
+                &chain.mip_views[mip - 1],
+                wgpu::LoadOp::Load,
+            );
+        }
+
+        self.draw_fullscreen(
+            encoder,
+            "Bloom Composite",
+            &bloom_pipeline.composite_pipeline,
+            &chain.mip_bind_groups[0],
+            &hdr_target.view,
+            wgpu::LoadOp::Load,
+        );
+    }
+}
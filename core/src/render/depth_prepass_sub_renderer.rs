@@ -0,0 +1,85 @@
+use bevy_ecs::{
+    system::{Query, Res, SystemState},
+    world::World,
+};
+
+use crate::{
+    ecs::{
+        components::{
+            gpu_bindings::model_bindings::ModelBindings,
+            materials::unlit_diffuse_material::UnlitDiffuseMaterial, mesh_filter::BasicMeshFilter,
+            visible::Visible,
+        },
+        resources::model_uniform_arena::ModelUniformArena,
+    },
+    gpu_resources::pipelines::{
+        depth_direction::DepthDirection, depth_prepass_pipeline::DepthPrepassPipeline,
+    },
+    utils::scissor::ScissorStack,
+};
+
+type DepthPrepassSubRendererSystemState = SystemState<(
+    Res<'static, DepthPrepassPipeline>,
+    Res<'static, ModelUniformArena>,
+    Query<
+        'static,
+        'static,
+        (
+            &'static ModelBindings,
+            &'static BasicMeshFilter,
+            &'static UnlitDiffuseMaterial,
+            Option<&'static Visible>,
+        ),
+    >,
+)>;
+
+/// Draws the depth of all opaque unlit geometry, skipping transparent materials
+pub struct DepthPrepassSubRenderer {
+    pub system_state: DepthPrepassSubRendererSystemState,
+}
+
+impl DepthPrepassSubRenderer {
+    pub fn new(world: &mut World) -> Self {
+        Self {
+            system_state: SystemState::new(world),
+        }
+    }
+
+    pub fn render<'a, 'w>(
+        &mut self,
+        world: &'w World,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        scissor_stack: &mut ScissorStack,
+        depth_direction: DepthDirection,
+    ) where
+        'w: 'a,
+    {
+        if scissor_stack.is_empty() {
+            return;
+        }
+
+        let (pipeline, model_arena, model_query) = self.system_state.get(world);
+        let model_arena = model_arena.into_inner();
+
+        let pipeline = pipeline.into_inner();
+        let mut current_cull_mode = None;
+
+        for (model_binding, mesh_filter, material, visible) in model_query.iter_inner() {
+            // cutout texels are discarded by the main pass, which the pre-pass can't do
+            if material.blend_mode.is_transparent()
+                || material.alpha_cutout
+                || !Visible::is_visible(visible)
+            {
+                continue;
+            }
+
+            if current_cull_mode != Some(material.cull_mode) {
+                render_pass
+                    .set_pipeline(pipeline.render_pipeline(depth_direction, material.cull_mode));
+                current_cull_mode = Some(material.cull_mode);
+            }
+            render_pass.set_bind_group(1, &model_arena.bind_group, &[model_binding.offset()]);
+            mesh_filter.filter.draw(render_pass);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{
+    gpu_resources::pipelines::depth_readback_pipeline::{
+        DEPTH_READBACK_FORMAT, DepthReadbackPipeline,
+    },
+    utils::{
+        buffer::{Buffer, BufferBuilder},
+        texture::Texture,
+    },
+};
+
+/// Reads single texels back from a depth texture.
+/// The texel is first rendered into a 1x1 `R32Float` target and then copied
+/// into a mappable buffer, since depth formats can't be copied directly on all backends.
+pub struct DepthReadback {
+    target: Texture,
+    pixel_buffer: Buffer<u32>,
+    readback_buffer: Buffer<u8>,
+}
+
+impl DepthReadback {
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self {
+            target: Texture::new_render_target(
+                device,
+                1,
+                1,
+                Some(DEPTH_READBACK_FORMAT),
+                Some("Depth Readback Target"),
+                None,
+                1,
+            ),
+            pixel_buffer: BufferBuilder::new(device)
+                .size(4)
+                .usage(wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST)
+                .label("Depth Readback Pixel Buffer")
+                .build()
+                .expect("Failed to create depth readback pixel buffer"),
+            readback_buffer: BufferBuilder::new(device)
+                .size(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize)
+                .usage(wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST)
+                .label("Depth Readback Buffer")
+                .build()
+                .expect("Failed to create depth readback buffer"),
+        }
+    }
+
+    /// Reads the depth value at pixel (`x`, `y`) of `depth_view`.
+    /// This blocks until the GPU has finished, so it is meant for occasional queries like picking.
+    /// Returns `None` if the readback could not be mapped (e.g. on wasm, where polling can't block).
+    pub fn read(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        pipeline: &DepthReadbackPipeline,
+        depth_view: &wgpu::TextureView,
+        x: u32,
+        y: u32,
+    ) -> Option<f32> {
+        self.pixel_buffer.update_all(queue, &[x, y, 0, 0]);
+
+        let bind_group = pipeline.create_bind_group(device, depth_view, &self.pixel_buffer.buffer);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Depth Readback Encoder"),
+        });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Depth Readback Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &self.target.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&pipeline.render_pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.target.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &self.readback_buffer.buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: Some(1),
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = self.readback_buffer.buffer.slice(..4);
+        let (sender, receiver) = crossbeam::channel::bounded(1);
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+
+        let Ok(Ok(())) = receiver.try_recv() else {
+            // Cancel the pending map so the next read can map the buffer again
+            self.readback_buffer.buffer.unmap();
+            return None;
+        };
+
+        let depth = {
+            let data = slice.get_mapped_range();
+            f32::from_le_bytes([data[0], data[1], data[2], data[3]])
+        };
+        self.readback_buffer.buffer.unmap();
+
+        Some(depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bevy_ecs::world::World;
+
+    use super::*;
+    use crate::{
+        gpu_resources::render_resources::RenderResources,
+        utils::{test_device::request_device, texture::TextureBuilder},
+    };
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn reads_back_the_cleared_depth() {
+        let (device, queue) = request_device();
+        let (device, queue) = (Arc::new(device), Arc::new(queue));
+        let mut world = World::new();
+        world.insert_resource(RenderResources::new(
+            device.clone(),
+            queue.clone(),
+            wgpu::TextureFormat::Rgba8Unorm,
+        ));
+        let pipeline = DepthReadbackPipeline::new(&world);
+
+        let depth_texture = TextureBuilder::new(&device)
+            .size(4, 4)
+            .depth_texture()
+            .build()
+            .unwrap();
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &depth_texture.view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0.25),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        queue.submit([encoder.finish()]);
+
+        let depth_readback = DepthReadback::new(&device);
+        let depth = depth_readback.read(&device, &queue, &pipeline, &depth_texture.view, 1, 2);
+        assert_eq!(depth, Some(0.25));
+    }
+}
@@ -0,0 +1,88 @@
+use std::cmp::Ordering;
+
+use glam::Vec3;
+
+use crate::ecs::components::render_order::RenderOrder;
+
+/// Sort key of a draw: the explicit `RenderOrder` first, then the distance to the camera
+#[derive(Debug, Clone, Copy)]
+pub struct DrawOrder {
+    order: i32,
+    distance_2: f32,
+}
+
+impl DrawOrder {
+    pub fn new(render_order: Option<&RenderOrder>, position: Vec3, camera_position: Vec3) -> Self {
+        Self {
+            order: RenderOrder::of(render_order),
+            distance_2: position.distance_squared(camera_position),
+        }
+    }
+
+    /// Breaks ties front to back, so the depth test rejects hidden opaque fragments early
+    pub fn front_to_back(&self, other: &Self) -> Ordering {
+        self.order
+            .cmp(&other.order)
+            .then_with(|| self.distance_2.total_cmp(&other.distance_2))
+    }
+
+    /// Breaks ties back to front, so blended geometry composites over what is behind it
+    pub fn back_to_front(&self, other: &Self) -> Ordering {
+        self.order
+            .cmp(&other.order)
+            .then_with(|| other.distance_2.total_cmp(&self.distance_2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sorts named draws the way the sub-renderers do and returns the names in draw order
+    fn sorted(
+        draws: &[(&'static str, Option<RenderOrder>, f32)],
+        compare: fn(&DrawOrder, &DrawOrder) -> Ordering,
+    ) -> Vec<&'static str> {
+        let mut draws: Vec<_> = draws
+            .iter()
+            .map(|(name, render_order, z)| {
+                let order =
+                    DrawOrder::new(render_order.as_ref(), Vec3::new(0.0, 0.0, *z), Vec3::ZERO);
+                (order, *name)
+            })
+            .collect();
+        draws.sort_by(|(a, _), (b, _)| compare(a, b));
+        draws.into_iter().map(|(_, name)| name).collect()
+    }
+
+    #[test]
+    fn explicit_orders_win_over_depth() {
+        // the nearest draw has the highest order and the farthest the lowest
+        let draws = [
+            ("near", Some(RenderOrder(2)), 1.0),
+            ("middle", None, 5.0),
+            ("far", Some(RenderOrder(-1)), 10.0),
+        ];
+
+        let expected = vec!["far", "middle", "near"];
+        assert_eq!(sorted(&draws, DrawOrder::front_to_back), expected);
+        assert_eq!(sorted(&draws, DrawOrder::back_to_front), expected);
+    }
+
+    #[test]
+    fn distance_breaks_ties() {
+        let draws = [
+            ("far", Some(RenderOrder(1)), 10.0),
+            ("near", Some(RenderOrder(1)), 1.0),
+        ];
+
+        assert_eq!(
+            sorted(&draws, DrawOrder::front_to_back),
+            vec!["near", "far"]
+        );
+        assert_eq!(
+            sorted(&draws, DrawOrder::back_to_front),
+            vec!["far", "near"]
+        );
+    }
+}
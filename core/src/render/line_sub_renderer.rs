@@ -0,0 +1,51 @@
+use bevy_ecs::{
+    system::{Query, Res, SystemState},
+    world::World,
+};
+
+use crate::{
+    ecs::components::{gpu_bindings::line_bindings::LineBindings, visible::Visible},
+    gpu_resources::pipelines::{depth_direction::DepthDirection, line_pipeline::LinePipeline},
+    utils::{profiling::profile_scope, scissor::ScissorStack},
+};
+
+type LineSubRendererSystemState = SystemState<(
+    Res<'static, LinePipeline>,
+    Query<'static, 'static, (&'static LineBindings, Option<&'static Visible>)>,
+)>;
+
+pub struct LineSubRenderer {
+    pub system_state: LineSubRendererSystemState,
+}
+
+impl LineSubRenderer {
+    pub fn new(world: &mut World) -> Self {
+        Self {
+            system_state: SystemState::new(world),
+        }
+    }
+
+    pub fn render<'a, 'w>(
+        &mut self,
+        world: &'w World,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        scissor_stack: &mut ScissorStack,
+        depth_direction: DepthDirection,
+    ) where
+        'w: 'a,
+    {
+        if scissor_stack.is_empty() {
+            return;
+        }
+
+        profile_scope!("lines");
+        let (pipeline, line_query) = self.system_state.get(world);
+
+        render_pass.set_pipeline(pipeline.into_inner().render_pipeline(depth_direction));
+        for (line_bindings, visible) in line_query.iter_inner() {
+            if Visible::is_visible(visible) {
+                line_bindings.draw(render_pass);
+            }
+        }
+    }
+}
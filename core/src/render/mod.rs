@@ -1,2 +1,12 @@
+mod bloom_pass;
+mod depth_prepass_sub_renderer;
+mod depth_readback;
+mod draw_order;
+mod line_sub_renderer;
+mod multi_texture_sub_renderer;
+mod occlusion_queries;
+mod particle_sub_renderer;
+mod post_process_stack;
 pub mod root_renderer;
 mod unlit_diffuse_sub_renderer;
+mod vertex_color_sub_renderer;
@@ -0,0 +1,274 @@
+use std::collections::HashSet;
+
+use bevy_ecs::entity::Entity;
+use crossbeam::channel::Receiver;
+
+use crate::utils::buffer::{Buffer, BufferBuilder};
+
+/// Most entities that can be occlusion tested in one frame. Extra entities are drawn untested.
+pub const MAX_OCCLUSION_QUERIES: u32 = 1024;
+
+/// Size of one resolved query result
+const QUERY_RESULT_SIZE: u64 = std::mem::size_of::<u64>() as u64;
+
+/// Where the last resolved results are on their way back from the GPU
+enum ReadbackState {
+    /// Nothing in flight, so this frame's results can be copied into the readback buffer
+    Idle,
+    /// Results for these entities were copied in a frame that has since been submitted
+    Copied(Vec<Entity>),
+    /// The readback buffer is being mapped for these entities' results
+    Mapping(Vec<Entity>, Receiver<Result<(), wgpu::BufferAsyncError>>),
+}
+
+/// Hands out occlusion query indices to entities and reads their results back without stalling.
+/// Results arrive a few frames late, so an entity that just became hidden is still drawn briefly.
+pub struct OcclusionQueries {
+    resolve_buffer: Buffer<u8>,
+    readback_buffer: Buffer<u8>,
+
+    /// Entities queried this frame, indexed by query
+    frame_entities: Vec<Entity>,
+    /// Entities whose last resolved query saw no samples
+    hidden: HashSet<Entity>,
+    readback_state: ReadbackState,
+}
+
+impl OcclusionQueries {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let size = (MAX_OCCLUSION_QUERIES as u64 * QUERY_RESULT_SIZE) as usize;
+
+        Self {
+            resolve_buffer: BufferBuilder::new(device)
+                .size(size)
+                .usage(wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC)
+                .label("Occlusion Query Resolve Buffer")
+                .build()
+                .expect("Failed to create occlusion query resolve buffer"),
+            readback_buffer: BufferBuilder::new(device)
+                .size(size)
+                .usage(wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST)
+                .label("Occlusion Query Readback Buffer")
+                .build()
+                .expect("Failed to create occlusion query readback buffer"),
+            frame_entities: Vec::new(),
+            hidden: HashSet::new(),
+            readback_state: ReadbackState::Idle,
+        }
+    }
+
+    /// The query set render passes need for `begin_occlusion_query`.
+    /// It lives outside of `OcclusionQueries` so a pass can borrow it while queries are handed out.
+    pub fn create_query_set(device: &wgpu::Device) -> wgpu::QuerySet {
+        device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Occlusion Query Set"),
+            ty: wgpu::QueryType::Occlusion,
+            count: MAX_OCCLUSION_QUERIES,
+        })
+    }
+
+    /// Advances the readback of earlier results. Call once per frame before any queries are handed out.
+    pub fn begin_frame(&mut self, device: &wgpu::Device) {
+        self.frame_entities.clear();
+
+        self.readback_state = match std::mem::replace(&mut self.readback_state, ReadbackState::Idle)
+        {
+            ReadbackState::Idle => ReadbackState::Idle,
+            // the frame that copied the results has been submitted by now, so it's safe to map
+            ReadbackState::Copied(entities) => {
+                let (sender, receiver) = crossbeam::channel::bounded(1);
+                self.readback_slice(entities.len())
+                    .map_async(wgpu::MapMode::Read, move |result| {
+                        let _ = sender.send(result);
+                    });
+                ReadbackState::Mapping(entities, receiver)
+            }
+            ReadbackState::Mapping(entities, receiver) => {
+                device.poll(wgpu::Maintain::Poll);
+                match receiver.try_recv() {
+                    Ok(Ok(())) => {
+                        self.read_results(&entities);
+                        self.readback_buffer.buffer.unmap();
+                        ReadbackState::Idle
+                    }
+                    Ok(Err(_)) => {
+                        self.readback_buffer.buffer.unmap();
+                        ReadbackState::Idle
+                    }
+                    Err(_) => ReadbackState::Mapping(entities, receiver),
+                }
+            }
+        };
+    }
+
+    /// Whether the entity's last resolved query saw any samples.
+    /// Entities that were never queried count as visible.
+    pub fn is_visible(&self, entity: Entity) -> bool {
+        !self.hidden.contains(&entity)
+    }
+
+    /// Allocates a query index for the entity this frame, or `None` if all queries are in use
+    pub fn next_query(&mut self, entity: Entity) -> Option<u32> {
+        let index = self.frame_entities.len() as u32;
+        if index >= MAX_OCCLUSION_QUERIES {
+            return None;
+        }
+
+        self.frame_entities.push(entity);
+        Some(index)
+    }
+
+    /// Resolves this frame's queries and copies them for readback, unless earlier results are still in flight
+    pub fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder, query_set: &wgpu::QuerySet) {
+        if self.frame_entities.is_empty() || !matches!(self.readback_state, ReadbackState::Idle) {
+            return;
+        }
+
+        let count = self.frame_entities.len() as u32;
+        let size = count as u64 * QUERY_RESULT_SIZE;
+
+        encoder.resolve_query_set(query_set, 0..count, &self.resolve_buffer.buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer.buffer,
+            0,
+            &self.readback_buffer.buffer,
+            0,
+            size,
+        );
+
+        self.readback_state = ReadbackState::Copied(std::mem::take(&mut self.frame_entities));
+    }
+
+    fn readback_slice(&self, count: usize) -> wgpu::BufferSlice {
+        self.readback_buffer
+            .buffer
+            .slice(..count as u64 * QUERY_RESULT_SIZE)
+    }
+
+    fn read_results(&mut self, entities: &[Entity]) {
+        let size = entities.len() as u64 * QUERY_RESULT_SIZE;
+        let data = self.readback_buffer.buffer.slice(..size).get_mapped_range();
+
+        self.hidden.clear();
+        for (entity, result) in entities
+            .iter()
+            .zip(data.chunks_exact(QUERY_RESULT_SIZE as usize))
+        {
+            let samples = u64::from_le_bytes(result.try_into().unwrap());
+            if samples == 0 {
+                self.hidden.insert(*entity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_device::request_device;
+
+    /// Covers the target with a triangle at a depth picked by the instance index
+    const SHADER: &str = "
+        @vertex
+        fn vs_main(@builtin(vertex_index) vertex: u32, @builtin(instance_index) instance: u32) -> @builtin(position) vec4<f32> {
+            var depths = array<f32, 3>(0.5, 0.75, 0.25);
+            let uv = vec2<f32>(f32((vertex << 1u) & 2u), f32(vertex & 2u));
+            return vec4<f32>(uv * 2.0 - 1.0, depths[instance], 1.0);
+        }
+    ";
+    const OCCLUDER: u32 = 0;
+    const BEHIND: u32 = 1;
+    const IN_FRONT: u32 = 2;
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn occluded_entities_are_hidden_once_their_results_resolve() {
+        let (device, queue) = request_device();
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: None,
+            primitive: Default::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: Default::default(),
+                bias: Default::default(),
+            }),
+            multisample: Default::default(),
+            multiview: None,
+        });
+        let depth = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let depth_view = depth.create_view(&Default::default());
+        let query_set = OcclusionQueries::create_query_set(&device);
+
+        let mut queries = OcclusionQueries::new(&device);
+        let (behind, in_front) = (Entity::from_raw(1), Entity::from_raw(2));
+
+        queries.begin_frame(&device);
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: Some(&query_set),
+            });
+            render_pass.set_pipeline(&pipeline);
+            render_pass.draw(0..3, OCCLUDER..OCCLUDER + 1);
+            for (entity, instance) in [(behind, BEHIND), (in_front, IN_FRONT)] {
+                render_pass.begin_occlusion_query(queries.next_query(entity).unwrap());
+                render_pass.draw(0..3, instance..instance + 1);
+                render_pass.end_occlusion_query();
+            }
+        }
+        queries.resolve(&mut encoder, &query_set);
+        queue.submit([encoder.finish()]);
+
+        // results only count once they are read back, a few frames later
+        assert!(queries.is_visible(behind));
+        for _ in 0..100 {
+            if matches!(queries.readback_state, ReadbackState::Idle) {
+                break;
+            }
+            queries.begin_frame(&device);
+            device.poll(wgpu::Maintain::Wait);
+        }
+
+        assert!(matches!(queries.readback_state, ReadbackState::Idle));
+        assert!(!queries.is_visible(behind));
+        assert!(queries.is_visible(in_front));
+    }
+}
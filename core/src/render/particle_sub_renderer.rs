@@ -0,0 +1,53 @@
+use bevy_ecs::{
+    system::{Query, Res, SystemState},
+    world::World,
+};
+
+use crate::{
+    ecs::components::{gpu_bindings::particle_bindings::ParticleBindings, visible::Visible},
+    gpu_resources::pipelines::{
+        depth_direction::DepthDirection, particle_pipeline::ParticlePipeline,
+    },
+    utils::{profiling::profile_scope, scissor::ScissorStack},
+};
+
+type ParticleSubRendererSystemState = SystemState<(
+    Res<'static, ParticlePipeline>,
+    Query<'static, 'static, (&'static ParticleBindings, Option<&'static Visible>)>,
+)>;
+
+pub struct ParticleSubRenderer {
+    pub system_state: ParticleSubRendererSystemState,
+}
+
+impl ParticleSubRenderer {
+    pub fn new(world: &mut World) -> Self {
+        Self {
+            system_state: SystemState::new(world),
+        }
+    }
+
+    pub fn render<'a, 'w>(
+        &mut self,
+        world: &'w World,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        scissor_stack: &mut ScissorStack,
+        depth_direction: DepthDirection,
+    ) where
+        'w: 'a,
+    {
+        if scissor_stack.is_empty() {
+            return;
+        }
+
+        profile_scope!("particles");
+        let (pipeline, particle_query) = self.system_state.get(world);
+
+        render_pass.set_pipeline(pipeline.into_inner().render_pipeline(depth_direction));
+        for (particle_bindings, visible) in particle_query.iter_inner() {
+            if Visible::is_visible(visible) {
+                particle_bindings.draw(render_pass);
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use bevy_ecs::world::World;
+use wgpu::util::DeviceExt;
+
+use crate::{
+    ecs::resources::post_process_settings::PostProcessSettings,
+    gpu_resources::{
+        layouts::texture_uniform_layout::TextureUniformLayout,
+        pipelines::tonemap_pipeline::TonemapPipeline,
+        render_resources::{HDR_FORMAT, RenderResources},
+        types::{gpu_type_macros::GpuUniformType, tonemap_settings::GpuTonemapSettings},
+    },
+    utils::texture::{SamplerConfig, Texture},
+};
+
+/// A pass that reads and writes the HDR scene target before it is tonemapped, e.g. bloom
+pub trait PostProcessPass {
+    /// Called when the pass is added and whenever the HDR target is recreated,
+    /// so resources that depend on the target or its size can follow
+    fn set_target(&mut self, world: &World, hdr_target: &Texture);
+
+    fn render(&mut self, world: &World, encoder: &mut wgpu::CommandEncoder, hdr_target: &Texture);
+}
+
+/// Owns the HDR target the scene is rendered into, runs the post-process passes over it
+/// in order and finally tonemaps it into the output view.
+pub struct PostProcessStack {
+    hdr_target: Texture,
+    hdr_bind_group: wgpu::BindGroup,
+    passes: Vec<Box<dyn PostProcessPass>>,
+
+    settings: Option<PostProcessSettings>,
+    settings_buffer: wgpu::Buffer,
+    settings_bind_group: wgpu::BindGroup,
+}
+
+impl PostProcessStack {
+    pub fn new(world: &World, width: u32, height: u32) -> Self {
+        let render_resources = world.get_resource::<RenderResources>().unwrap();
+        let device = &render_resources.device;
+        let tonemap_pipeline = world.get_resource::<TonemapPipeline>().unwrap();
+
+        let (hdr_target, hdr_bind_group) = Self::create_hdr_target(world, width, height);
+
+        let settings_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Tonemap Settings Buffer"),
+            contents: &GpuTonemapSettings::from_settings(
+                &PostProcessSettings::default(),
+                render_resources.surface_format,
+            )
+            .as_buffer(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let settings_bind_group =
+            tonemap_pipeline.create_settings_bind_group(device, &settings_buffer);
+
+        Self {
+            hdr_target,
+            hdr_bind_group,
+            passes: Vec::new(),
+            settings: None,
+            settings_buffer,
+            settings_bind_group,
+        }
+    }
+
+    /// Appends a pass that runs after the passes already in the stack
+    pub fn push_pass(&mut self, world: &World, mut pass: impl PostProcessPass + 'static) {
+        pass.set_target(world, &self.hdr_target);
+        self.passes.push(Box::new(pass));
+    }
+
+    /// The target the scene should be rendered into
+    pub fn hdr_view(&self) -> &wgpu::TextureView {
+        &self.hdr_target.view
+    }
+
+    pub fn set_size(&mut self, world: &World, width: u32, height: u32) {
+        (self.hdr_target, self.hdr_bind_group) = Self::create_hdr_target(world, width, height);
+
+        for pass in &mut self.passes {
+            pass.set_target(world, &self.hdr_target);
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        world: &World,
+        encoder: &mut wgpu::CommandEncoder,
+        output_view: &wgpu::TextureView,
+    ) {
+        let render_resources = world.get_resource::<RenderResources>().unwrap();
+        let tonemap_pipeline = world.get_resource::<TonemapPipeline>().unwrap();
+        let settings = *world.get_resource::<PostProcessSettings>().unwrap();
+
+        if self.settings != Some(settings) {
+            let gpu_settings =
+                GpuTonemapSettings::from_settings(&settings, render_resources.surface_format);
+            render_resources.queue.write_buffer(
+                &self.settings_buffer,
+                0,
+                &gpu_settings.as_buffer(),
+            );
+            self.settings = Some(settings);
+        }
+
+        // passes only make sense on tonemapped output, so skip them along with the tonemapping
+        if settings.enabled {
+            for pass in &mut self.passes {
+                pass.render(world, encoder, &self.hdr_target);
+            }
+        }
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Tonemap Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(&tonemap_pipeline.render_pipeline);
+        render_pass.set_bind_group(0, &self.hdr_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.settings_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    fn create_hdr_target(world: &World, width: u32, height: u32) -> (Texture, wgpu::BindGroup) {
+        let device = &world.get_resource::<RenderResources>().unwrap().device;
+        let texture_uniform_layout = world.get_resource::<TextureUniformLayout<1>>().unwrap();
+
+        let hdr_target = Texture::new_render_target(
+            device,
+            width,
+            height,
+            Some(HDR_FORMAT),
+            Some("HDR Target"),
+            // post-process passes read the target at lower resolutions
+            Some(SamplerConfig {
+                min_filter: Some("Linear".to_string()),
+                ..Default::default()
+            }),
+            1,
+        );
+        let hdr_bind_group =
+            texture_uniform_layout.create_bind_group_for_slot(device, &hdr_target, 0);
+
+        (hdr_target, hdr_bind_group)
+    }
+}
@@ -6,24 +6,58 @@ use bevy_ecs::{
 use wgpu::{CommandBuffer, TextureView};
 
 use crate::{
-    ecs::components::gpu_bindings::camera_bindings::CameraBindings,
-    gpu_resources::render_resources::RenderResources,
-    utils::texture::{Texture, TextureBuilder},
+    ecs::{
+        components::{camera::Camera, gpu_bindings::camera_bindings::CameraBindings},
+        resources::depth_prepass::DepthPrepass,
+    },
+    gpu_resources::{
+        pipelines::depth_readback_pipeline::DepthReadbackPipeline,
+        render_resources::RenderResources,
+    },
+    utils::{
+        frustum::Frustum,
+        profiling::profile_scope,
+        scissor::ScissorStack,
+        texture::{Texture, TextureBuilder},
+    },
 };
 
-use super::unlit_diffuse_sub_renderer::UnlitDiffuseSubRenderer;
+use super::{
+    bloom_pass::BloomPass, depth_prepass_sub_renderer::DepthPrepassSubRenderer,
+    depth_readback::DepthReadback, line_sub_renderer::LineSubRenderer,
+    multi_texture_sub_renderer::MultiTextureSubRenderer, occlusion_queries::OcclusionQueries,
+    particle_sub_renderer::ParticleSubRenderer, post_process_stack::PostProcessStack,
+    unlit_diffuse_sub_renderer::UnlitDiffuseSubRenderer,
+    vertex_color_sub_renderer::VertexColorSubRenderer,
+};
+
+/// A user render step run after the main pass, given the encoder and the HDR scene view.
+/// The view has `HDR_FORMAT` and is tonemapped afterwards, like the rest of the scene.
+pub type CustomPass = Box<dyn FnMut(&World, &mut wgpu::CommandEncoder, &TextureView)>;
 
 type RootRendererSystemState = SystemState<(
     Res<'static, RenderResources>,
-    Query<'static, 'static, (&'static CameraBindings,)>,
+    Res<'static, DepthPrepass>,
+    Query<'static, 'static, (&'static Camera, &'static CameraBindings)>,
 )>;
 
 pub struct RootRenderer {
     system_state: RootRendererSystemState,
 
+    depth_prepass_sub_renderer: DepthPrepassSubRenderer,
     unlit_diffuse_sub_renderer: UnlitDiffuseSubRenderer,
+    multi_texture_sub_renderer: MultiTextureSubRenderer,
+    vertex_color_sub_renderer: VertexColorSubRenderer,
+    particle_sub_renderer: ParticleSubRenderer,
+    line_sub_renderer: LineSubRenderer,
+    post_process_stack: PostProcessStack,
+    custom_passes: Vec<CustomPass>,
 
     depth_texture: Texture,
+    depth_readback: DepthReadback,
+
+    occlusion_query_set: wgpu::QuerySet,
+    occlusion_queries: OcclusionQueries,
 }
 
 impl std::fmt::Debug for RootRenderer {
@@ -34,53 +68,149 @@ impl std::fmt::Debug for RootRenderer {
 
 impl RootRenderer {
     pub fn new(world: &mut World, width: u32, height: u32) -> Self {
+        let depth_prepass_sub_renderer = DepthPrepassSubRenderer::new(world);
         let unlit_diffuse_sub_renderer = UnlitDiffuseSubRenderer::new(world);
+        let multi_texture_sub_renderer = MultiTextureSubRenderer::new(world);
+        let vertex_color_sub_renderer = VertexColorSubRenderer::new(world);
+        let particle_sub_renderer = ParticleSubRenderer::new(world);
+        let line_sub_renderer = LineSubRenderer::new(world);
         let system_state: RootRendererSystemState = SystemState::new(world);
+        let mut post_process_stack = PostProcessStack::new(world, width, height);
+        post_process_stack.push_pass(world, BloomPass::new(world));
 
         let render_resources = world.get_resource::<RenderResources>().unwrap();
         let device = &render_resources.device;
 
         let mut renderer = Self {
             system_state,
+            depth_prepass_sub_renderer,
             unlit_diffuse_sub_renderer,
+            multi_texture_sub_renderer,
+            vertex_color_sub_renderer,
+            particle_sub_renderer,
+            line_sub_renderer,
+            post_process_stack,
+            custom_passes: Vec::new(),
             depth_texture: TextureBuilder::new(device)
                 .size(width, height)
                 .depth_texture()
                 .label("Depth Texture")
                 .build()
                 .expect("Failed to create depth texture"),
+            depth_readback: DepthReadback::new(device),
+            occlusion_query_set: OcclusionQueries::create_query_set(device),
+            occlusion_queries: OcclusionQueries::new(device),
         };
 
-        renderer.set_size(device, width, height);
+        renderer.set_size(world, width, height);
         renderer
     }
 
-    pub fn set_size(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+    pub fn set_size(&mut self, world: &World, width: u32, height: u32) {
+        let device = &world.get_resource::<RenderResources>().unwrap().device;
+
         self.depth_texture = TextureBuilder::new(device)
             .size(width, height)
             .depth_texture()
             .label("Depth Texture")
             .build()
             .expect("Failed to create depth texture");
+
+        self.post_process_stack.set_size(world, width, height);
+    }
+
+    /// Appends a pass that runs every frame after the main pass and the passes already added
+    pub fn add_custom_pass(&mut self, pass: CustomPass) {
+        self.custom_passes.push(pass);
+    }
+
+    /// Size of the render target in pixels
+    pub fn render_size(&self) -> (u32, u32) {
+        self.depth_texture.dimensions
+    }
+
+    /// Reads back the depth of the last rendered frame at pixel (`x`, `y`).
+    /// Returns `None` if the pixel is outside the render target or the readback failed.
+    pub fn read_depth(&self, world: &World, x: u32, y: u32) -> Option<f32> {
+        let (width, height) = self.render_size();
+        if x >= width || y >= height {
+            return None;
+        }
+
+        let render_resources = world.get_resource::<RenderResources>()?;
+        let pipeline = world.get_resource::<DepthReadbackPipeline>()?;
+
+        self.depth_readback.read(
+            &render_resources.device,
+            &render_resources.queue,
+            pipeline,
+            &self.depth_texture.view,
+            x,
+            y,
+        )
     }
 
     pub fn render(&mut self, world: &World, output_view: &TextureView) -> CommandBuffer {
-        let (render_resources, camera_query) = self.system_state.get(world);
+        let (render_resources, depth_prepass, camera_query) = self.system_state.get(world);
         let device = &render_resources.device;
+        let depth_prepass = depth_prepass.0;
 
         // TODO: Support multiple cameras
-        let main_camera = camera_query.single().0;
+        let (camera, main_camera) = camera_query.single();
+        let depth_direction = camera.depth_direction();
+        let frustum = Frustum::from_view_proj(main_camera.view_proj());
+        let camera_position = main_camera.position();
+        let (width, height) = self.render_size();
+        let scissor = camera.scissor;
+
+        self.occlusion_queries.begin_frame(device);
 
         // set up command encoder for render pass
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Render Encoder"),
         });
 
+        if depth_prepass {
+            profile_scope!("depth_prepass");
+            let pass_descriptor = wgpu::RenderPassDescriptor {
+                label: Some("Depth Prepass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_texture.view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(depth_direction.clear_value()),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            };
+            let mut render_pass = encoder.begin_render_pass(&pass_descriptor);
+            render_pass.set_bind_group(0, &main_camera.bind_group, &[]);
+            let mut scissor_stack = ScissorStack::new(&mut render_pass, width, height, scissor);
+
+            self.depth_prepass_sub_renderer.render(
+                world,
+                &mut render_pass,
+                &mut scissor_stack,
+                depth_direction,
+            );
+        }
+
+        // keep the pre-pass depth instead of clearing it
+        let depth_load = if depth_prepass {
+            wgpu::LoadOp::Load
+        } else {
+            wgpu::LoadOp::Clear(depth_direction.clear_value())
+        };
+
         {
+            profile_scope!("main_pass");
             let pass_descriptor = wgpu::RenderPassDescriptor {
                 label: Some("Background Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: output_view,
+                    view: self.post_process_stack.hdr_view(),
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
@@ -90,21 +220,80 @@ impl RootRenderer {
                 depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
                     view: &self.depth_texture.view,
                     depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
+                        load: depth_load,
                         store: wgpu::StoreOp::Store,
                     }),
                     stencil_ops: None,
                 }),
                 timestamp_writes: None,
-                occlusion_query_set: None,
+                occlusion_query_set: Some(&self.occlusion_query_set),
             };
             let mut render_pass = encoder.begin_render_pass(&pass_descriptor);
             render_pass.set_bind_group(0, &main_camera.bind_group, &[]);
+            // sub-renderers may push nested rects, but leave the stack as they found it
+            let mut scissor_stack = ScissorStack::new(&mut render_pass, width, height, scissor);
+
+            // multi texture and vertex colored meshes are opaque, so they go before blended geometry
+            self.multi_texture_sub_renderer.render(
+                world,
+                &mut render_pass,
+                &mut scissor_stack,
+                depth_direction,
+                camera_position,
+            );
+            self.vertex_color_sub_renderer.render(
+                world,
+                &mut render_pass,
+                &mut scissor_stack,
+                depth_direction,
+                camera_position,
+            );
+
+            self.unlit_diffuse_sub_renderer.render(
+                world,
+                &mut render_pass,
+                &mut scissor_stack,
+                depth_prepass,
+                depth_direction,
+                &frustum,
+                camera_position,
+                &mut self.occlusion_queries,
+            );
 
-            self.unlit_diffuse_sub_renderer
-                .render(world, &mut render_pass);
+            // transparent particles draw last so they blend over the opaque scene
+            self.particle_sub_renderer.render(
+                world,
+                &mut render_pass,
+                &mut scissor_stack,
+                depth_direction,
+            );
+
+            // lines go on top of the scene so debug overlays stay readable
+            self.line_sub_renderer.render(
+                world,
+                &mut render_pass,
+                &mut scissor_stack,
+                depth_direction,
+            );
+        }
+
+        if !self.custom_passes.is_empty() {
+            profile_scope!("custom_passes", passes = self.custom_passes.len());
+            let hdr_view = self.post_process_stack.hdr_view();
+            for pass in &mut self.custom_passes {
+                pass(world, &mut encoder, hdr_view);
+            }
         }
 
+        // results are read back over the next frames to skip hidden entities
+        self.occlusion_queries
+            .resolve(&mut encoder, &self.occlusion_query_set);
+
+        // tonemap the HDR scene into the output
+        profile_scope!("post_process");
+        self.post_process_stack
+            .render(world, &mut encoder, output_view);
+
         encoder.finish()
     }
 }
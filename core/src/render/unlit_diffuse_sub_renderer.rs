@@ -1,25 +1,47 @@
 use bevy_ecs::{
+    entity::Entity,
     system::{Query, Res, SystemState},
     world::World,
 };
 
+use glam::Vec3;
+
 use crate::{
-    ecs::components::{
-        gpu_bindings::model_bindings::ModelBindings,
-        materials::unlit_diffuse_material::UnlitDiffuseMaterial, mesh_filter::BasicMeshFilter,
+    ecs::{
+        components::{
+            gpu_bindings::model_bindings::ModelBindings,
+            materials::unlit_diffuse_material::UnlitDiffuseMaterial, mesh_filter::BasicMeshFilter,
+            occlusion_culled::OcclusionCulled, render_order::RenderOrder, visible::Visible,
+        },
+        resources::{model_uniform_arena::ModelUniformArena, spatial_grid::SpatialGrid},
+    },
+    gpu_resources::pipelines::{
+        blend_mode::BlendMode,
+        depth_direction::DepthDirection,
+        occlusion_test_pipeline::OcclusionTestPipeline,
+        unlit_diffuse_pipeline::{UnlitDiffusePipeline, UnlitPipelineKey},
     },
-    gpu_resources::pipelines::unlit_diffuse_pipeline::UnlitDiffusePipeline,
+    utils::{frustum::Frustum, profiling::profile_scope, scissor::ScissorStack},
 };
 
+use super::{draw_order::DrawOrder, occlusion_queries::OcclusionQueries};
+
 type UnlitDiffuseSubRendererSystemState = SystemState<(
     Res<'static, UnlitDiffusePipeline>,
+    Res<'static, OcclusionTestPipeline>,
+    Res<'static, SpatialGrid>,
+    Res<'static, ModelUniformArena>,
     Query<
         'static,
         'static,
         (
+            Entity,
             &'static ModelBindings,
             &'static BasicMeshFilter,
             &'static UnlitDiffuseMaterial,
+            Option<&'static OcclusionCulled>,
+            Option<&'static Visible>,
+            Option<&'static RenderOrder>,
         ),
     >,
 )>;
@@ -35,18 +57,133 @@ impl UnlitDiffuseSubRenderer {
         }
     }
 
-    pub fn render<'a, 'w>(&mut self, world: &'w World, render_pass: &mut wgpu::RenderPass<'a>)
-    where
+    /// `depth_prepass` is whether opaque depth was already written by a pre-pass.
+    /// Entities in the spatial grid are skipped when their bounds are outside `frustum`.
+    /// Entities with `OcclusionCulled` are queried through `occlusion_queries`,
+    /// which needs the render pass to have been started with its query set.
+    /// Each blend mode batch is sorted by `RenderOrder`, then by distance to `camera_position`.
+    /// Nothing is drawn while the scissor rect is empty, e.g. for a camera whose rect is off screen.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render<'a, 'w>(
+        &mut self,
+        world: &'w World,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        scissor_stack: &mut ScissorStack,
+        depth_prepass: bool,
+        depth_direction: DepthDirection,
+        frustum: &Frustum,
+        camera_position: Vec3,
+        occlusion_queries: &mut OcclusionQueries,
+    ) where
         'w: 'a,
     {
-        let (pipeline, model_query) = self.system_state.get(world);
+        if scissor_stack.is_empty() {
+            return;
+        }
+
+        let (pipeline, occlusion_test_pipeline, spatial_grid, model_arena, model_query) =
+            self.system_state.get(world);
+        let model_arena = model_arena.into_inner();
+
+        let pipeline = pipeline.into_inner();
+        let in_view = spatial_grid.query_frustum(frustum);
+        let is_culled =
+            |entity: Entity| spatial_grid.contains(entity) && !in_view.contains(&entity);
+        let mut hidden = Vec::new();
+
+        // Draw each blend mode as a batch, opaque first so blended geometry lands on top
+        for blend_mode in BlendMode::ALL {
+            let mut draws: Vec<_> = model_query
+                .iter_inner()
+                .filter(|(entity, _, _, material, _, visible, _)| {
+                    material.blend_mode == blend_mode
+                        && Visible::is_visible(*visible)
+                        && !is_culled(*entity)
+                })
+                .map(
+                    |(entity, model_binding, mesh_filter, material, occlusion_culled, _, order)| {
+                        let order =
+                            DrawOrder::new(order, model_binding.position(), camera_position);
+                        (
+                            order,
+                            (
+                                entity,
+                                model_binding,
+                                mesh_filter,
+                                material,
+                                occlusion_culled,
+                            ),
+                        )
+                    },
+                )
+                .collect();
+
+            if draws.is_empty() {
+                continue;
+            }
+            if blend_mode == BlendMode::Opaque {
+                draws.sort_by(|(a, _), (b, _)| a.front_to_back(b));
+            } else {
+                draws.sort_by(|(a, _), (b, _)| a.back_to_front(b));
+            }
+            profile_scope!("unlit_diffuse", draws = draws.len());
+
+            // the sort order is kept, so the pipeline only changes between cull or cutout variants
+            let mut current_variant = None;
+            for (_, (entity, model_binding, mesh_filter, material, occlusion_culled)) in draws {
+                let variant = (material.cull_mode, material.alpha_cutout);
+                if current_variant != Some(variant) {
+                    render_pass.set_pipeline(pipeline.render_pipeline(UnlitPipelineKey {
+                        blend_mode,
+                        depth_prepass,
+                        depth_direction,
+                        cull_mode: material.cull_mode,
+                        alpha_cutout: material.alpha_cutout,
+                    }));
+                    current_variant = Some(variant);
+                }
+
+                let query = match occlusion_culled {
+                    Some(_) if !occlusion_queries.is_visible(entity) => {
+                        hidden.push((entity, model_binding, mesh_filter));
+                        continue;
+                    }
+                    Some(_) => occlusion_queries.next_query(entity),
+                    None => None,
+                };
+
+                render_pass.set_bind_group(1, &model_arena.bind_group, &[model_binding.offset()]);
+                render_pass.set_bind_group(2, &material.bind_group, &[]);
+
+                if let Some(query) = query {
+                    render_pass.begin_occlusion_query(query);
+                    mesh_filter.filter.draw(render_pass);
+                    render_pass.end_occlusion_query();
+                } else {
+                    mesh_filter.filter.draw(render_pass);
+                }
+            }
+        }
+
+        // Hidden entities are only depth tested, to find out when they come back into view
+        if hidden.is_empty() {
+            return;
+        }
 
-        render_pass.set_pipeline(&pipeline.into_inner().render_pipeline);
-        for (model_binding, mesh_filter, material) in model_query.iter_inner() {
-            render_pass.set_bind_group(1, &model_binding.bind_group, &[]);
-            render_pass.set_bind_group(2, &material.bind_group, &[]);
+        render_pass.set_pipeline(
+            occlusion_test_pipeline
+                .into_inner()
+                .render_pipeline(depth_direction),
+        );
+        for (entity, model_binding, mesh_filter) in hidden {
+            let Some(query) = occlusion_queries.next_query(entity) else {
+                break;
+            };
 
+            render_pass.set_bind_group(1, &model_arena.bind_group, &[model_binding.offset()]);
+            render_pass.begin_occlusion_query(query);
             mesh_filter.filter.draw(render_pass);
+            render_pass.end_occlusion_query();
         }
     }
 }
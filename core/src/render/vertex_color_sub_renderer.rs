@@ -0,0 +1,88 @@
+use bevy_ecs::{
+    system::{Query, Res, SystemState},
+    world::World,
+};
+
+use glam::Vec3;
+
+use crate::{
+    ecs::{
+        components::{
+            gpu_bindings::model_bindings::ModelBindings, mesh_filter::ColorMeshFilter,
+            render_order::RenderOrder, visible::Visible,
+        },
+        resources::model_uniform_arena::ModelUniformArena,
+    },
+    gpu_resources::pipelines::{
+        depth_direction::DepthDirection, vertex_color_pipeline::VertexColorPipeline,
+    },
+    utils::{profiling::profile_scope, scissor::ScissorStack},
+};
+
+use super::draw_order::DrawOrder;
+
+type VertexColorSubRendererSystemState = SystemState<(
+    Res<'static, VertexColorPipeline>,
+    Res<'static, ModelUniformArena>,
+    Query<
+        'static,
+        'static,
+        (
+            &'static ModelBindings,
+            &'static ColorMeshFilter,
+            Option<&'static Visible>,
+            Option<&'static RenderOrder>,
+        ),
+    >,
+)>;
+
+pub struct VertexColorSubRenderer {
+    pub system_state: VertexColorSubRendererSystemState,
+}
+
+impl VertexColorSubRenderer {
+    pub fn new(world: &mut World) -> Self {
+        Self {
+            system_state: SystemState::new(world),
+        }
+    }
+
+    pub fn render<'a, 'w>(
+        &mut self,
+        world: &'w World,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        scissor_stack: &mut ScissorStack,
+        depth_direction: DepthDirection,
+        camera_position: Vec3,
+    ) where
+        'w: 'a,
+    {
+        if scissor_stack.is_empty() {
+            return;
+        }
+
+        let (pipeline, model_arena, model_query) = self.system_state.get(world);
+        let model_arena = model_arena.into_inner();
+
+        let mut draws: Vec<_> = model_query
+            .iter_inner()
+            .filter(|(_, _, visible, _)| Visible::is_visible(*visible))
+            .map(|(model_binding, mesh_filter, _, render_order)| {
+                let order = DrawOrder::new(render_order, model_binding.position(), camera_position);
+                (order, (model_binding, mesh_filter))
+            })
+            .collect();
+        if draws.is_empty() {
+            return;
+        }
+        draws.sort_by(|(a, _), (b, _)| a.front_to_back(b));
+        profile_scope!("vertex_color", draws = draws.len());
+
+        render_pass.set_pipeline(pipeline.into_inner().render_pipeline(depth_direction));
+        for (_, (model_binding, mesh_filter)) in draws {
+            render_pass.set_bind_group(1, &model_arena.bind_group, &[model_binding.offset()]);
+
+            mesh_filter.filter.draw(render_pass);
+        }
+    }
+}
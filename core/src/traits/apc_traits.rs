@@ -18,3 +18,13 @@ pub trait ApcHandler: Send + Sync {
     /// Spawns an asynchronous procedure call.
     fn spawn_apc(&self, apc: Apc, sender: Sender<ApcCallback>);
 }
+
+/// An `ApcHandler` for headless cores with no async runtime.
+/// Spawned APCs are dropped without running.
+pub struct HeadlessApcHandler;
+
+impl ApcHandler for HeadlessApcHandler {
+    fn spawn_apc(&self, _apc: Apc, _sender: Sender<ApcCallback>) {
+        log::warn!("Dropping APC, headless cores have no async runtime");
+    }
+}
@@ -0,0 +1,23 @@
+/// Our unified trait for reading and writing the system clipboard.
+/// Platforms without a clipboard should return `None` and ignore writes instead of failing.
+pub trait ClipboardHandler: Send + Sync {
+    /// Returns the text on the clipboard, or `None` if it is empty, not text or unavailable.
+    fn clipboard_get(&self) -> Option<String>;
+
+    /// Replaces the clipboard contents with `text`.
+    fn clipboard_set(&self, text: &str);
+}
+
+/// A `ClipboardHandler` for cores without access to a system clipboard.
+/// It always reads as empty and drops writes.
+pub struct HeadlessClipboardHandler;
+
+impl ClipboardHandler for HeadlessClipboardHandler {
+    fn clipboard_get(&self) -> Option<String> {
+        None
+    }
+
+    fn clipboard_set(&self, _text: &str) {
+        log::warn!("Dropping clipboard write, no clipboard is available");
+    }
+}
@@ -17,3 +17,20 @@ pub trait HttpRequester: Send + Sync {
         url: &str,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, HttpError>> + Send>>;
 }
+
+/// An `HttpRequester` for headless cores with no network access.
+/// Every request fails immediately.
+pub struct HeadlessHttpRequester;
+
+impl HttpRequester for HeadlessHttpRequester {
+    fn make_web_request(
+        &self,
+        url: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, HttpError>> + Send>> {
+        let message = format!(
+            "Cannot request {}, headless cores have no network access",
+            url
+        );
+        Box::pin(async move { Err(HttpError::Other(message)) })
+    }
+}
@@ -1,2 +1,3 @@
 pub mod apc_traits;
+pub mod clipboard_traits;
 pub mod http_traits;
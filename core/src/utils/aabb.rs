@@ -0,0 +1,76 @@
+use glam::{Mat4, Vec3};
+
+/// An axis aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// The smallest box containing every point, or `None` if there are no points
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+
+        Some(points.fold(Self::new(first, first), |aabb, point| Self {
+            min: aabb.min.min(point),
+            max: aabb.max.max(point),
+        }))
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    /// The smallest box containing both boxes
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Whether the boxes overlap, touching faces included
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+
+    /// The axis aligned box around this box after it is transformed by `matrix`
+    pub fn transformed(&self, matrix: Mat4) -> Self {
+        let center = matrix.transform_point3(self.center());
+        let half_extents = self.half_extents();
+
+        // project the half extents onto each world axis through the absolute rotation-scale
+        let x = matrix.x_axis.truncate().abs() * half_extents.x;
+        let y = matrix.y_axis.truncate().abs() * half_extents.y;
+        let z = matrix.z_axis.truncate().abs() * half_extents.z;
+        let world_half_extents = x + y + z;
+
+        Self {
+            min: center - world_half_extents,
+            max: center + world_half_extents,
+        }
+    }
+
+    /// Distances along `direction` (in multiples of its length) where the ray enters and exits the box.
+    /// A ray starting inside the box enters at 0. Returns `None` if the box is missed or behind the ray.
+    pub fn ray_intersect(&self, origin: Vec3, direction: Vec3) -> Option<(f32, f32)> {
+        let inverse_direction = direction.recip();
+        let t1 = (self.min - origin) * inverse_direction;
+        let t2 = (self.max - origin) * inverse_direction;
+
+        let enter = t1.min(t2).max_element().max(0.0);
+        let exit = t1.max(t2).min_element();
+
+        (enter <= exit).then_some((enter, exit))
+    }
+}
@@ -177,6 +177,59 @@ impl<T: Pod + Zeroable> Buffer<T> {
     }
 }
 
+/// Draw arguments laid out like `wgpu::util::DrawIndexedIndirectArgs`, so a compute
+/// pass can write them into an indirect buffer for `draw_indexed_indirect`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+pub struct DrawIndexedIndirectArgs {
+    /// Number of indices to draw
+    pub index_count: u32,
+    /// Number of instances to draw
+    pub instance_count: u32,
+    /// First index within the index buffer
+    pub first_index: u32,
+    /// Value added to each index before reading from the vertex buffer
+    pub base_vertex: i32,
+    /// First instance to draw
+    pub first_instance: u32,
+}
+
+impl From<wgpu::util::DrawIndexedIndirectArgs> for DrawIndexedIndirectArgs {
+    fn from(args: wgpu::util::DrawIndexedIndirectArgs) -> Self {
+        Self {
+            index_count: args.index_count,
+            instance_count: args.instance_count,
+            first_index: args.first_index,
+            base_vertex: args.base_vertex,
+            first_instance: args.first_instance,
+        }
+    }
+}
+
+impl Buffer<DrawIndexedIndirectArgs> {
+    /// Creates an indirect draw buffer. It is also usable as a storage buffer so
+    /// compute passes can fill in the draw arguments
+    pub fn new_indirect(
+        device: &wgpu::Device,
+        args: &[DrawIndexedIndirectArgs],
+        label: &str,
+    ) -> Self {
+        Self::new(
+            device,
+            args,
+            wgpu::BufferUsages::INDIRECT
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST,
+            label,
+        )
+    }
+
+    /// Byte offset of the draw arguments at the given index
+    pub fn indirect_offset(index: usize) -> u64 {
+        (index * std::mem::size_of::<DrawIndexedIndirectArgs>()) as u64
+    }
+}
+
 /// Implementation of DynamicBuffer for updating data frequently
 pub struct DynamicBuffer<T: Pod + Zeroable> {
     /// Current buffer to read from
@@ -0,0 +1,331 @@
+use std::f32::consts::PI;
+
+/// Overshoot amount used by the back easing curves
+const BACK_OVERSHOOT: f32 = 1.70158;
+/// Overshoot amount used by `back_in_out`, scaled so the midpoint stays at 0.5
+const BACK_OVERSHOOT_IN_OUT: f32 = BACK_OVERSHOOT * 1.525;
+
+/// An easing curve that can be stored in components.
+/// Every curve maps a normalized time in 0..1 to an eased value where 0 -> 0 and 1 -> 1.
+/// Elastic and back curves overshoot outside of 0..1 in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    QuartIn,
+    QuartOut,
+    QuartInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+}
+
+impl Easing {
+    /// The easing function for this curve
+    pub fn function(&self) -> fn(f32) -> f32 {
+        match self {
+            Easing::Linear => linear,
+            Easing::QuadIn => quad_in,
+            Easing::QuadOut => quad_out,
+            Easing::QuadInOut => quad_in_out,
+            Easing::CubicIn => cubic_in,
+            Easing::CubicOut => cubic_out,
+            Easing::CubicInOut => cubic_in_out,
+            Easing::QuartIn => quart_in,
+            Easing::QuartOut => quart_out,
+            Easing::QuartInOut => quart_in_out,
+            Easing::SineIn => sine_in,
+            Easing::SineOut => sine_out,
+            Easing::SineInOut => sine_in_out,
+            Easing::ExpoIn => expo_in,
+            Easing::ExpoOut => expo_out,
+            Easing::ExpoInOut => expo_in_out,
+            Easing::ElasticIn => elastic_in,
+            Easing::ElasticOut => elastic_out,
+            Easing::ElasticInOut => elastic_in_out,
+            Easing::BounceIn => bounce_in,
+            Easing::BounceOut => bounce_out,
+            Easing::BounceInOut => bounce_in_out,
+            Easing::BackIn => back_in,
+            Easing::BackOut => back_out,
+            Easing::BackInOut => back_in_out,
+        }
+    }
+
+    /// Eases `t`, which is clamped to 0..1 first
+    pub fn apply(&self, t: f32) -> f32 {
+        (self.function())(t.clamp(0.0, 1.0))
+    }
+}
+
+pub fn linear(t: f32) -> f32 {
+    t
+}
+
+pub fn quad_in(t: f32) -> f32 {
+    t * t
+}
+
+pub fn quad_out(t: f32) -> f32 {
+    1.0 - (1.0 - t) * (1.0 - t)
+}
+
+pub fn quad_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        2.0 * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+    }
+}
+
+pub fn cubic_in(t: f32) -> f32 {
+    t * t * t
+}
+
+pub fn cubic_out(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+pub fn cubic_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+pub fn quart_in(t: f32) -> f32 {
+    t * t * t * t
+}
+
+pub fn quart_out(t: f32) -> f32 {
+    1.0 - (1.0 - t).powi(4)
+}
+
+pub fn quart_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        8.0 * t * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(4) / 2.0
+    }
+}
+
+pub fn sine_in(t: f32) -> f32 {
+    1.0 - (t * PI / 2.0).cos()
+}
+
+pub fn sine_out(t: f32) -> f32 {
+    (t * PI / 2.0).sin()
+}
+
+pub fn sine_in_out(t: f32) -> f32 {
+    -((PI * t).cos() - 1.0) / 2.0
+}
+
+pub fn expo_in(t: f32) -> f32 {
+    // 2^(10t - 10) doesn't quite reach 0, so pin the endpoint
+    if t <= 0.0 {
+        0.0
+    } else {
+        2.0_f32.powf(10.0 * t - 10.0)
+    }
+}
+
+pub fn expo_out(t: f32) -> f32 {
+    if t >= 1.0 {
+        1.0
+    } else {
+        1.0 - 2.0_f32.powf(-10.0 * t)
+    }
+}
+
+pub fn expo_in_out(t: f32) -> f32 {
+    if t <= 0.0 {
+        0.0
+    } else if t >= 1.0 {
+        1.0
+    } else if t < 0.5 {
+        2.0_f32.powf(20.0 * t - 10.0) / 2.0
+    } else {
+        (2.0 - 2.0_f32.powf(-20.0 * t + 10.0)) / 2.0
+    }
+}
+
+pub fn elastic_in(t: f32) -> f32 {
+    const C4: f32 = 2.0 * PI / 3.0;
+
+    if t <= 0.0 {
+        0.0
+    } else if t >= 1.0 {
+        1.0
+    } else {
+        -(2.0_f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * C4).sin()
+    }
+}
+
+pub fn elastic_out(t: f32) -> f32 {
+    const C4: f32 = 2.0 * PI / 3.0;
+
+    if t <= 0.0 {
+        0.0
+    } else if t >= 1.0 {
+        1.0
+    } else {
+        2.0_f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * C4).sin() + 1.0
+    }
+}
+
+pub fn elastic_in_out(t: f32) -> f32 {
+    const C5: f32 = 2.0 * PI / 4.5;
+
+    if t <= 0.0 {
+        0.0
+    } else if t >= 1.0 {
+        1.0
+    } else if t < 0.5 {
+        -(2.0_f32.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * C5).sin()) / 2.0
+    } else {
+        2.0_f32.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * C5).sin() / 2.0 + 1.0
+    }
+}
+
+pub fn bounce_in(t: f32) -> f32 {
+    1.0 - bounce_out(1.0 - t)
+}
+
+pub fn bounce_out(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+
+pub fn bounce_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0
+    } else {
+        (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0
+    }
+}
+
+pub fn back_in(t: f32) -> f32 {
+    (BACK_OVERSHOOT + 1.0) * t * t * t - BACK_OVERSHOOT * t * t
+}
+
+pub fn back_out(t: f32) -> f32 {
+    let t = t - 1.0;
+    1.0 + (BACK_OVERSHOOT + 1.0) * t * t * t + BACK_OVERSHOOT * t * t
+}
+
+pub fn back_in_out(t: f32) -> f32 {
+    const C: f32 = BACK_OVERSHOOT_IN_OUT;
+
+    if t < 0.5 {
+        ((2.0 * t).powi(2) * ((C + 1.0) * 2.0 * t - C)) / 2.0
+    } else {
+        ((2.0 * t - 2.0).powi(2) * ((C + 1.0) * (t * 2.0 - 2.0) + C) + 2.0) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 25] = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::QuartIn,
+        Easing::QuartOut,
+        Easing::QuartInOut,
+        Easing::SineIn,
+        Easing::SineOut,
+        Easing::SineInOut,
+        Easing::ExpoIn,
+        Easing::ExpoOut,
+        Easing::ExpoInOut,
+        Easing::ElasticIn,
+        Easing::ElasticOut,
+        Easing::ElasticInOut,
+        Easing::BounceIn,
+        Easing::BounceOut,
+        Easing::BounceInOut,
+        Easing::BackIn,
+        Easing::BackOut,
+        Easing::BackInOut,
+    ];
+
+    #[test]
+    fn every_curve_maps_the_endpoints_to_themselves() {
+        for easing in ALL {
+            assert!(easing.apply(0.0).abs() < 1e-5, "{:?} at 0", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-5, "{:?} at 1", easing);
+        }
+    }
+
+    #[test]
+    fn curves_without_overshoot_or_bounce_are_monotonic() {
+        let monotonic = ALL.into_iter().filter(|easing| {
+            !matches!(
+                easing,
+                Easing::ElasticIn
+                    | Easing::ElasticOut
+                    | Easing::ElasticInOut
+                    | Easing::BounceIn
+                    | Easing::BounceOut
+                    | Easing::BounceInOut
+                    | Easing::BackIn
+                    | Easing::BackOut
+                    | Easing::BackInOut
+            )
+        });
+
+        for easing in monotonic {
+            let mut previous = easing.apply(0.0);
+            for step in 1..=100 {
+                let value = easing.apply(step as f32 / 100.0);
+                assert!(value >= previous, "{:?} decreases at step {}", easing, step);
+                previous = value;
+            }
+        }
+    }
+
+    #[test]
+    fn apply_clamps_time() {
+        assert_eq!(Easing::QuadIn.apply(-1.0), 0.0);
+        assert_eq!(Easing::QuadIn.apply(2.0), 1.0);
+    }
+}
@@ -0,0 +1,51 @@
+use glam::{Mat4, Vec3, Vec4};
+
+use super::aabb::Aabb;
+
+/// The six planes bounding a camera's view volume, pointing inwards.
+/// Each plane is stored as `(normal, distance)` so a point is inside when `normal.dot(point) + distance >= 0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    planes: [Vec4; 6],
+}
+
+impl Frustum {
+    /// Extracts the planes from a view projection matrix with wgpu's 0..1 clip space depth.
+    /// Works for reversed depth as well, since near and far only swap places.
+    pub fn from_view_proj(view_proj: Mat4) -> Self {
+        let row_x = view_proj.row(0);
+        let row_y = view_proj.row(1);
+        let row_z = view_proj.row(2);
+        let row_w = view_proj.row(3);
+
+        let planes = [
+            row_w + row_x,
+            row_w - row_x,
+            row_w + row_y,
+            row_w - row_y,
+            row_z,
+            row_w - row_z,
+        ]
+        .map(|plane| plane / plane.truncate().length());
+
+        Self { planes }
+    }
+
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(point) + plane.w >= 0.0)
+    }
+
+    /// Whether any part of the box may be inside the frustum.
+    /// Boxes near the frustum's corners can be reported as inside when they aren't,
+    /// which is fine for culling since it only ever keeps too much.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            let normal = plane.truncate();
+            // the corner furthest along the plane normal
+            let positive_corner = Vec3::select(normal.cmpge(Vec3::ZERO), aabb.max, aabb.min);
+            normal.dot(positive_corner) + plane.w >= 0.0
+        })
+    }
+}
@@ -1,4 +1,14 @@
+pub mod aabb;
 pub mod buffer;
 pub mod degrees_and_radians;
+pub mod easing;
+pub mod frustum;
+pub mod noise;
 pub mod primitives;
+pub mod profiling;
+pub mod raycast;
+pub mod scissor;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_device;
 pub mod texture;
+pub mod tonemap;
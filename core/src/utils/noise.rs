@@ -0,0 +1,248 @@
+use crate::utils::texture::{SamplerConfig, Texture, TextureBuilder};
+
+/// Gradient directions used by `perlin_2d`, the 4 axes and 4 diagonals
+const GRADIENTS: [(f32, f32); 8] = [
+    (1.0, 0.0),
+    (-1.0, 0.0),
+    (0.0, 1.0),
+    (0.0, -1.0),
+    (
+        std::f32::consts::FRAC_1_SQRT_2,
+        std::f32::consts::FRAC_1_SQRT_2,
+    ),
+    (
+        -std::f32::consts::FRAC_1_SQRT_2,
+        std::f32::consts::FRAC_1_SQRT_2,
+    ),
+    (
+        std::f32::consts::FRAC_1_SQRT_2,
+        -std::f32::consts::FRAC_1_SQRT_2,
+    ),
+    (
+        -std::f32::consts::FRAC_1_SQRT_2,
+        -std::f32::consts::FRAC_1_SQRT_2,
+    ),
+];
+
+/// Settings for fractal brownian motion
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FbmSettings {
+    /// Number of noise layers summed together
+    pub octaves: u32,
+    /// Frequency multiplier between octaves
+    pub lacunarity: f32,
+    /// Amplitude multiplier between octaves
+    pub gain: f32,
+}
+
+impl Default for FbmSettings {
+    fn default() -> Self {
+        Self {
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+/// Texture formats `create_noise_texture` can fill
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoiseTextureFormat {
+    /// One byte per texel, noise remapped to 0..1
+    R8Unorm,
+    /// One float per texel, noise remapped to 0..1
+    R32Float,
+}
+
+impl NoiseTextureFormat {
+    fn texture_format(&self) -> wgpu::TextureFormat {
+        match self {
+            NoiseTextureFormat::R8Unorm => wgpu::TextureFormat::R8Unorm,
+            NoiseTextureFormat::R32Float => wgpu::TextureFormat::R32Float,
+        }
+    }
+}
+
+/// Hashes an integer lattice point and seed into a well mixed u32
+fn hash(x: i32, y: i32, seed: u32) -> u32 {
+    let mut h = seed.wrapping_mul(0x9E37_79B9)
+        ^ (x as u32).wrapping_mul(0x85EB_CA6B)
+        ^ (y as u32).wrapping_mul(0xC2B2_AE35);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7FEB_352D);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846C_A68B);
+    h ^= h >> 16;
+    h
+}
+
+/// Dot product of the lattice gradient at (`ix`, `iy`) with the offset to (`x`, `y`)
+fn gradient_dot(ix: i32, iy: i32, x: f32, y: f32, seed: u32) -> f32 {
+    let (gx, gy) = GRADIENTS[(hash(ix, iy, seed) & 7) as usize];
+    gx * (x - ix as f32) + gy * (y - iy as f32)
+}
+
+/// Quintic fade curve so the noise has continuous second derivatives
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// 2D Perlin gradient noise in the range -1..1.
+/// The same `seed` always produces the same noise field.
+pub fn perlin_2d(x: f32, y: f32, seed: u32) -> f32 {
+    let x0 = x.floor() as i32;
+    let y0 = y.floor() as i32;
+    let x1 = x0 + 1;
+    let y1 = y0 + 1;
+
+    let u = fade(x - x0 as f32);
+    let v = fade(y - y0 as f32);
+
+    let bottom = lerp(
+        gradient_dot(x0, y0, x, y, seed),
+        gradient_dot(x1, y0, x, y, seed),
+        u,
+    );
+    let top = lerp(
+        gradient_dot(x0, y1, x, y, seed),
+        gradient_dot(x1, y1, x, y, seed),
+        u,
+    );
+
+    // 2D Perlin noise peaks at sqrt(0.5), scale it out to the full -1..1 range
+    (lerp(bottom, top, v) * std::f32::consts::SQRT_2).clamp(-1.0, 1.0)
+}
+
+/// Fractal brownian motion: several octaves of `perlin_2d` summed together.
+/// The result is normalized back into the range -1..1.
+pub fn fbm(x: f32, y: f32, seed: u32, settings: &FbmSettings) -> f32 {
+    let mut frequency = 1.0;
+    let mut amplitude = 1.0;
+    let mut total = 0.0;
+    let mut total_amplitude = 0.0;
+
+    for octave in 0..settings.octaves {
+        // offset the seed per octave so layers aren't correlated
+        let octave_seed = seed.wrapping_add(octave.wrapping_mul(0x68E3_1DA4));
+        total += perlin_2d(x * frequency, y * frequency, octave_seed) * amplitude;
+        total_amplitude += amplitude;
+
+        frequency *= settings.lacunarity;
+        amplitude *= settings.gain;
+    }
+
+    if total_amplitude > 0.0 {
+        (total / total_amplitude).clamp(-1.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Samples `fbm` over a `width` x `height` grid, remapped to 0..1.
+/// `scale` is the number of texels per noise lattice cell.
+pub fn generate_noise_map(
+    width: u32,
+    height: u32,
+    scale: f32,
+    seed: u32,
+    settings: &FbmSettings,
+) -> Vec<f32> {
+    let scale = scale.max(f32::EPSILON);
+    let mut values = Vec::with_capacity((width * height) as usize);
+
+    for y in 0..height {
+        for x in 0..width {
+            let noise = fbm(x as f32 / scale, y as f32 / scale, seed, settings);
+            values.push(noise * 0.5 + 0.5);
+        }
+    }
+
+    values
+}
+
+/// Creates a single channel texture filled with fbm noise remapped to 0..1
+#[allow(clippy::too_many_arguments)]
+pub fn create_noise_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    width: u32,
+    height: u32,
+    format: NoiseTextureFormat,
+    scale: f32,
+    seed: u32,
+    settings: &FbmSettings,
+) -> Result<Texture, String> {
+    let values = generate_noise_map(width, height, scale, seed, settings);
+
+    let bytes: Vec<u8> = match format {
+        NoiseTextureFormat::R8Unorm => values
+            .iter()
+            .map(|value| (value * 255.0).round() as u8)
+            .collect(),
+        NoiseTextureFormat::R32Float => bytemuck::cast_slice(&values).to_vec(),
+    };
+
+    let mut builder = TextureBuilder::new(device)
+        .queue(queue)
+        .size(width, height)
+        .format(format.texture_format())
+        .label("noise_texture")
+        .raw_data(&bytes);
+
+    // 32 bit float textures aren't filterable without an optional feature
+    if format == NoiseTextureFormat::R32Float {
+        builder = builder.sampler_config(SamplerConfig {
+            mag_filter: Some("Nearest".to_string()),
+            min_filter: Some("Nearest".to_string()),
+            mipmap_filter: Some("Nearest".to_string()),
+            ..Default::default()
+        });
+    }
+
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noise_stays_in_range() {
+        let settings = FbmSettings::default();
+        for i in 0..1000 {
+            let x = i as f32 * 0.137 - 50.0;
+            let y = i as f32 * 0.291 - 80.0;
+            let perlin = perlin_2d(x, y, 7);
+            let fractal = fbm(x, y, 7, &settings);
+            assert!(
+                (-1.0..=1.0).contains(&perlin),
+                "perlin {} out of range",
+                perlin
+            );
+            assert!(
+                (-1.0..=1.0).contains(&fractal),
+                "fbm {} out of range",
+                fractal
+            );
+        }
+
+        let map = generate_noise_map(32, 32, 8.0, 7, &settings);
+        assert_eq!(map.len(), 32 * 32);
+        assert!(map.iter().all(|value| (0.0..=1.0).contains(value)));
+    }
+
+    #[test]
+    fn same_seed_reproduces_the_same_noise() {
+        let settings = FbmSettings::default();
+        let first = generate_noise_map(16, 16, 4.0, 42, &settings);
+        let second = generate_noise_map(16, 16, 4.0, 42, &settings);
+        assert_eq!(first, second);
+
+        let other_seed = generate_noise_map(16, 16, 4.0, 43, &settings);
+        assert_ne!(first, other_seed);
+    }
+}
@@ -1,9 +1,11 @@
-use std::f32::consts::PI;
+use std::{collections::HashMap, f32::consts::PI};
+
+use glam::{Vec3, Vec4};
 use wgpu::Device;
 
 use crate::{
-    ecs::components::mesh_filter::{BasicMeshFilter, MeshFilter},
-    gpu_resources::types::basic_vertex::BasicVertex,
+    ecs::components::mesh_filter::{BasicMeshFilter, ColorMeshFilter},
+    gpu_resources::types::{basic_vertex::BasicVertex, color_vertex::ColorVertex},
 };
 
 /// Creates a plane mesh on the XZ plane with a specified size.
@@ -21,6 +23,17 @@ pub fn create_plane(
     width_segments: u32,
     depth_segments: u32,
 ) -> BasicMeshFilter {
+    let (vertices, indices) = plane_data(width, depth, width_segments, depth_segments);
+    BasicMeshFilter::new(device, &vertices, &indices)
+}
+
+/// The vertices and indices of `create_plane`, without uploading them
+pub fn plane_data(
+    width: f32,
+    depth: f32,
+    width_segments: u32,
+    depth_segments: u32,
+) -> (Vec<BasicVertex>, Vec<u32>) {
     let width_half = width / 2.0;
     let depth_half = depth / 2.0;
 
@@ -44,6 +57,8 @@ pub fn create_plane(
             vertices.push(BasicVertex {
                 position: [x_pos, 0.0, z_pos].into(),
                 tex_coords: [x as f32 / grid_x as f32, z as f32 / grid_z as f32].into(),
+                normal: Vec3::Y,
+                tangent: Vec4::ZERO,
             });
         }
     }
@@ -58,18 +73,117 @@ pub fn create_plane(
 
             // Generate two triangles for each grid cell
             indices.push(a);
-            indices.push(c);
             indices.push(b);
+            indices.push(c);
 
             indices.push(b);
-            indices.push(c);
             indices.push(d);
+            indices.push(c);
         }
     }
 
-    BasicMeshFilter {
-        filter: MeshFilter::new(device, &vertices, &indices),
-    }
+    compute_tangents(&mut vertices, &indices);
+    (vertices, indices)
+}
+
+/// Creates a quad covering the whole screen in normalized device coordinates, for
+/// post-processing and UI passes that draw it without a camera transform.
+/// UVs span 0..1 with v pointing down, matching texture space.
+///
+/// # Arguments
+/// * `device` - The WGPU device to create buffers on
+pub fn create_screen_quad(device: &Device) -> BasicMeshFilter {
+    let (vertices, indices) = screen_quad_data();
+    BasicMeshFilter::new(device, &vertices, &indices)
+}
+
+/// The vertices and indices of `create_screen_quad`, without uploading them
+pub fn screen_quad_data() -> (Vec<BasicVertex>, Vec<u32>) {
+    // bottom left, bottom right, top right, top left
+    let corners = [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]];
+    let mut vertices = ndc_vertices(&corners);
+    let indices = vec![0, 1, 2, 0, 2, 3];
+
+    compute_tangents(&mut vertices, &indices);
+    (vertices, indices)
+}
+
+/// Creates a single triangle covering the whole screen in normalized device coordinates.
+/// It overshoots the screen so the visible part has UVs spanning 0..1, and unlike
+/// `create_screen_quad` it has no diagonal seam where fragments are shaded twice.
+///
+/// # Arguments
+/// * `device` - The WGPU device to create buffers on
+pub fn create_fullscreen_triangle(device: &Device) -> BasicMeshFilter {
+    let (vertices, indices) = fullscreen_triangle_data();
+    BasicMeshFilter::new(device, &vertices, &indices)
+}
+
+/// The vertices and indices of `create_fullscreen_triangle`, without uploading them
+pub fn fullscreen_triangle_data() -> (Vec<BasicVertex>, Vec<u32>) {
+    let corners = [[-1.0, -1.0], [3.0, -1.0], [-1.0, 3.0]];
+    let mut vertices = ndc_vertices(&corners);
+    let indices = vec![0, 1, 2];
+
+    compute_tangents(&mut vertices, &indices);
+    (vertices, indices)
+}
+
+/// Vertices at the given NDC xy positions, facing the viewer
+fn ndc_vertices(corners: &[[f32; 2]]) -> Vec<BasicVertex> {
+    corners
+        .iter()
+        .map(|&[x, y]| BasicVertex {
+            position: Vec3::new(x, y, 0.0),
+            // texture space has y pointing down
+            tex_coords: [(x + 1.0) / 2.0, (1.0 - y) / 2.0].into(),
+            normal: Vec3::NEG_Z,
+            tangent: Vec4::ZERO,
+        })
+        .collect()
+}
+
+/// Creates a vertex colored quad on the XY plane, facing -Z towards the default camera.
+///
+/// # Arguments
+/// * `device` - The WGPU device to create buffers on
+/// * `width` - The width of the quad along the X axis
+/// * `height` - The height of the quad along the Y axis
+/// * `colors` - Corner colors in the order bottom left, bottom right, top right, top left
+pub fn create_color_quad(
+    device: &Device,
+    width: f32,
+    height: f32,
+    colors: [Vec4; 4],
+) -> ColorMeshFilter {
+    let (vertices, indices) = color_quad_data(width, height, colors);
+    ColorMeshFilter::new(device, &vertices, &indices)
+}
+
+/// The vertices and indices of `create_color_quad`, without uploading them
+pub fn color_quad_data(width: f32, height: f32, colors: [Vec4; 4]) -> (Vec<ColorVertex>, Vec<u32>) {
+    let width_half = width / 2.0;
+    let height_half = height / 2.0;
+
+    let positions = [
+        [-width_half, -height_half, 0.0],
+        [width_half, -height_half, 0.0],
+        [width_half, height_half, 0.0],
+        [-width_half, height_half, 0.0],
+    ];
+
+    let vertices: Vec<ColorVertex> = positions
+        .into_iter()
+        .zip(colors)
+        .map(|(position, color)| ColorVertex {
+            position: position.into(),
+            color,
+        })
+        .collect();
+    // counter-clockwise as seen from -Z, the front face for `FrontFace::Ccw`
+    let indices = vec![0, 1, 2, 0, 2, 3];
+
+    (vertices, indices)
 }
 
 /// Creates a cube mesh with a specified size.
@@ -79,6 +193,12 @@ pub fn create_plane(
 /// * `size` - The size of the cube in all dimensions
 /// * `segments` - Number of segments along each edge
 pub fn create_cube(device: &Device, size: f32, segments: u32) -> BasicMeshFilter {
+    let (vertices, indices) = cube_data(size, segments);
+    BasicMeshFilter::new(device, &vertices, &indices)
+}
+
+/// The vertices and indices of `create_cube`, without uploading them
+pub fn cube_data(size: f32, segments: u32) -> (Vec<BasicVertex>, Vec<u32>) {
     let half_size = size / 2.0;
 
     let mut vertices = Vec::new();
@@ -125,6 +245,8 @@ pub fn create_cube(device: &Device, size: f32, segments: u32) -> BasicMeshFilter
                 vertices.push(BasicVertex {
                     position: [x, y, z].into(),
                     tex_coords: [u, v].into(),
+                    normal: normal.into(),
+                    tangent: Vec4::ZERO,
                 });
             }
         }
@@ -205,9 +327,8 @@ pub fn create_cube(device: &Device, size: f32, segments: u32) -> BasicMeshFilter
         base_index,
     );
 
-    BasicMeshFilter {
-        filter: MeshFilter::new(device, &vertices, &indices),
-    }
+    compute_tangents(&mut vertices, &indices);
+    (vertices, indices)
 }
 
 /// Creates a sphere mesh with a specified radius.
@@ -223,6 +344,16 @@ pub fn create_sphere(
     width_segments: u32,
     height_segments: u32,
 ) -> BasicMeshFilter {
+    let (vertices, indices) = sphere_data(radius, width_segments, height_segments);
+    BasicMeshFilter::new(device, &vertices, &indices)
+}
+
+/// The vertices and indices of `create_sphere`, without uploading them
+pub fn sphere_data(
+    radius: f32,
+    width_segments: u32,
+    height_segments: u32,
+) -> (Vec<BasicVertex>, Vec<u32>) {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
@@ -235,14 +366,14 @@ pub fn create_sphere(
             let u = x as f32 / width_segments as f32;
             let theta = u * 2.0 * PI;
 
-            // Calculate position on sphere
-            let x_pos = -radius * phi.sin() * theta.cos();
-            let y_pos = radius * phi.cos();
-            let z_pos = radius * phi.sin() * theta.sin();
+            // Calculate the direction from the center, which is also the normal
+            let normal = Vec3::new(-phi.sin() * theta.cos(), phi.cos(), phi.sin() * theta.sin());
 
             vertices.push(BasicVertex {
-                position: [x_pos, y_pos, z_pos].into(),
+                position: normal * radius,
                 tex_coords: [u, v].into(),
+                normal,
+                tangent: Vec4::ZERO,
             });
         }
     }
@@ -258,22 +389,132 @@ pub fn create_sphere(
             // For the first row, we only need one triangle per sector
             if y != 0 {
                 indices.push(a);
-                indices.push(c);
                 indices.push(b);
+                indices.push(c);
             }
 
             // For the last row, we only need one triangle per sector
             if y != height_segments - 1 {
                 indices.push(b);
-                indices.push(c);
                 indices.push(d);
+                indices.push(c);
             }
         }
     }
 
-    BasicMeshFilter {
-        filter: MeshFilter::new(device, &vertices, &indices),
+    compute_tangents(&mut vertices, &indices);
+    (vertices, indices)
+}
+
+/// Creates an icosphere mesh with a specified radius.
+///
+/// Starts from an icosahedron and splits every triangle into four per subdivision,
+/// pushing the new vertices out onto the sphere. Unlike `create_sphere` the triangles
+/// stay close to uniform in size, with no pinching at the poles.
+/// The mesh has `20 * 4^subdivisions` triangles and `10 * 4^subdivisions + 2` vertices.
+///
+/// # Arguments
+/// * `device` - The WGPU device to create buffers on
+/// * `radius` - The radius of the sphere
+/// * `subdivisions` - Number of times each triangle is split into four
+pub fn create_icosphere(device: &Device, radius: f32, subdivisions: u32) -> BasicMeshFilter {
+    let (vertices, indices) = icosphere_data(radius, subdivisions);
+    BasicMeshFilter::new(device, &vertices, &indices)
+}
+
+/// The vertices and indices of `create_icosphere`, without uploading them
+pub fn icosphere_data(radius: f32, subdivisions: u32) -> (Vec<BasicVertex>, Vec<u32>) {
+    let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+
+    // The 12 icosahedron vertices, normalized onto the unit sphere
+    let mut positions: Vec<Vec3> = [
+        [-1.0, t, 0.0],
+        [1.0, t, 0.0],
+        [-1.0, -t, 0.0],
+        [1.0, -t, 0.0],
+        [0.0, -1.0, t],
+        [0.0, 1.0, t],
+        [0.0, -1.0, -t],
+        [0.0, 1.0, -t],
+        [t, 0.0, -1.0],
+        [t, 0.0, 1.0],
+        [-t, 0.0, -1.0],
+        [-t, 0.0, 1.0],
+    ]
+    .iter()
+    .map(|p| Vec3::from_array(*p).normalize())
+    .collect();
+
+    let mut triangles: Vec<[u32; 3]> = vec![
+        [0, 5, 11],
+        [0, 1, 5],
+        [0, 7, 1],
+        [0, 10, 7],
+        [0, 11, 10],
+        [1, 9, 5],
+        [5, 4, 11],
+        [11, 2, 10],
+        [10, 6, 7],
+        [7, 8, 1],
+        [3, 4, 9],
+        [3, 2, 4],
+        [3, 6, 2],
+        [3, 8, 6],
+        [3, 9, 8],
+        [4, 5, 9],
+        [2, 11, 4],
+        [6, 10, 2],
+        [8, 7, 6],
+        [9, 1, 8],
+    ];
+
+    for _ in 0..subdivisions {
+        // Midpoints keyed by their edge so neighbouring triangles share them
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut midpoint = |a: u32, b: u32, positions: &mut Vec<Vec3>| -> u32 {
+            let key = if a < b { (a, b) } else { (b, a) };
+            *midpoints.entry(key).or_insert_with(|| {
+                let position = ((positions[a as usize] + positions[b as usize]) * 0.5).normalize();
+                positions.push(position);
+                positions.len() as u32 - 1
+            })
+        };
+
+        let mut subdivided = Vec::with_capacity(triangles.len() * 4);
+        for [a, b, c] in triangles {
+            let ab = midpoint(a, b, &mut positions);
+            let bc = midpoint(b, c, &mut positions);
+            let ca = midpoint(c, a, &mut positions);
+
+            subdivided.push([a, ab, ca]);
+            subdivided.push([b, bc, ab]);
+            subdivided.push([c, ca, bc]);
+            subdivided.push([ab, bc, ca]);
+        }
+        triangles = subdivided;
     }
+
+    // Spherical UVs matching the layout of `create_sphere`
+    let mut vertices: Vec<BasicVertex> = positions
+        .iter()
+        .map(|p| {
+            let u = p.z.atan2(-p.x) / (2.0 * PI);
+            let u = if u < 0.0 { u + 1.0 } else { u };
+            let v = p.y.clamp(-1.0, 1.0).acos() / PI;
+
+            BasicVertex {
+                position: *p * radius,
+                tex_coords: [u, v].into(),
+                normal: *p,
+                tangent: Vec4::ZERO,
+            }
+        })
+        .collect();
+
+    let indices: Vec<u32> = triangles.into_iter().flatten().collect();
+
+    compute_tangents(&mut vertices, &indices);
+    (vertices, indices)
 }
 
 /// Creates a capsule mesh with specified radius and height.
@@ -295,6 +536,24 @@ pub fn create_capsule(
     height_segments: u32,
     cap_segments: u32,
 ) -> BasicMeshFilter {
+    let (vertices, indices) = capsule_data(
+        radius,
+        height,
+        radial_segments,
+        height_segments,
+        cap_segments,
+    );
+    BasicMeshFilter::new(device, &vertices, &indices)
+}
+
+/// The vertices and indices of `create_capsule`, without uploading them
+pub fn capsule_data(
+    radius: f32,
+    height: f32,
+    radial_segments: u32,
+    height_segments: u32,
+    cap_segments: u32,
+) -> (Vec<BasicVertex>, Vec<u32>) {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
@@ -316,7 +575,9 @@ pub fn create_capsule(
 
             vertices.push(BasicVertex {
                 position: [x_pos, y_pos, z_pos].into(),
-                tex_coords: [u, v / 2.0].into(), // Map to top quarter of texture
+                tex_coords: [u, v * 0.25].into(), // Map to top quarter of texture
+                normal: Vec3::new(phi.sin() * theta.cos(), phi.cos(), phi.sin() * theta.sin()),
+                tangent: Vec4::ZERO,
             });
         }
     }
@@ -324,7 +585,8 @@ pub fn create_capsule(
     // Generate cylinder vertices
     for y in 0..=height_segments {
         let v = y as f32 / height_segments as f32;
-        let y_pos = (1.0 - v) * half_height - v * half_height;
+        // From the top hemisphere's equator down to the bottom one's
+        let y_pos = half_height - v * height;
 
         for x in 0..=radial_segments {
             let u = x as f32 / radial_segments as f32;
@@ -336,6 +598,8 @@ pub fn create_capsule(
             vertices.push(BasicVertex {
                 position: [x_pos, y_pos, z_pos].into(),
                 tex_coords: [u, 0.25 + v * 0.5].into(), // Map to middle half of texture
+                normal: Vec3::new(theta.cos(), 0.0, theta.sin()),
+                tangent: Vec4::ZERO,
             });
         }
     }
@@ -356,7 +620,9 @@ pub fn create_capsule(
 
             vertices.push(BasicVertex {
                 position: [x_pos, y_pos, z_pos].into(),
-                tex_coords: [u, 0.75 + v / 2.0].into(), // Map to bottom quarter of texture
+                tex_coords: [u, 0.75 + v * 0.25].into(), // Map to bottom quarter of texture
+                normal: Vec3::new(phi.sin() * theta.cos(), phi.cos(), phi.sin() * theta.sin()),
+                tangent: Vec4::ZERO,
             });
         }
     }
@@ -398,9 +664,8 @@ pub fn create_capsule(
     let bottom_start = cylinder_start + (height_segments + 1) * (radial_segments + 1);
     generate_grid_indices(bottom_start, radial_segments, cap_segments, &mut indices);
 
-    BasicMeshFilter {
-        filter: MeshFilter::new(device, &vertices, &indices),
-    }
+    compute_tangents(&mut vertices, &indices);
+    (vertices, indices)
 }
 
 /// Creates a cylinder mesh with a specified radius and height.
@@ -422,10 +687,32 @@ pub fn create_cylinder(
     height_segments: u32,
     open_ended: bool,
 ) -> BasicMeshFilter {
+    let (vertices, indices) = cylinder_data(
+        radius_top,
+        radius_bottom,
+        height,
+        radial_segments,
+        height_segments,
+        open_ended,
+    );
+    BasicMeshFilter::new(device, &vertices, &indices)
+}
+
+/// The vertices and indices of `create_cylinder`, without uploading them
+pub fn cylinder_data(
+    radius_top: f32,
+    radius_bottom: f32,
+    height: f32,
+    radial_segments: u32,
+    height_segments: u32,
+    open_ended: bool,
+) -> (Vec<BasicVertex>, Vec<u32>) {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
     let half_height = height / 2.0;
+    // The sides lean in by the radius change over the height, so their normals tilt along y
+    let normal_y = (radius_bottom - radius_top) / height;
 
     // Generate vertices for the sides of the cylinder
     for y in 0..=height_segments {
@@ -445,6 +732,8 @@ pub fn create_cylinder(
             vertices.push(BasicVertex {
                 position: [x_pos, y_pos, z_pos].into(),
                 tex_coords: [u, v].into(),
+                normal: Vec3::new(theta.cos(), normal_y, theta.sin()).normalize(),
+                tangent: Vec4::ZERO,
             });
         }
     }
@@ -458,12 +747,12 @@ pub fn create_cylinder(
             let d = c + 1;
 
             indices.push(a);
-            indices.push(c);
             indices.push(b);
+            indices.push(c);
 
             indices.push(b);
-            indices.push(c);
             indices.push(d);
+            indices.push(c);
         }
     }
 
@@ -472,12 +761,15 @@ pub fn create_cylinder(
         let mut add_cap = |top: bool| {
             let radius = if top { radius_top } else { radius_bottom };
             let y_pos = if top { half_height } else { -half_height };
+            let normal = if top { Vec3::Y } else { Vec3::NEG_Y };
             let center_index = vertices.len() as u32;
 
             // Add center vertex
             vertices.push(BasicVertex {
                 position: [0.0, y_pos, 0.0].into(),
                 tex_coords: [0.5, 0.5].into(),
+                normal,
+                tangent: Vec4::ZERO,
             });
 
             // Add perimeter vertices
@@ -491,6 +783,8 @@ pub fn create_cylinder(
                 vertices.push(BasicVertex {
                     position: [x_pos, y_pos, z_pos].into(),
                     tex_coords: [(theta.cos() + 1.0) / 2.0, (theta.sin() + 1.0) / 2.0].into(),
+                    normal,
+                    tangent: Vec4::ZERO,
                 });
             }
 
@@ -517,9 +811,8 @@ pub fn create_cylinder(
         add_cap(false);
     }
 
-    BasicMeshFilter {
-        filter: MeshFilter::new(device, &vertices, &indices),
-    }
+    compute_tangents(&mut vertices, &indices);
+    (vertices, indices)
 }
 
 /// Creates a torus mesh with specified radii.
@@ -537,6 +830,17 @@ pub fn create_torus(
     radial_segments: u32,
     tubular_segments: u32,
 ) -> BasicMeshFilter {
+    let (vertices, indices) = torus_data(radius, tube_radius, radial_segments, tubular_segments);
+    BasicMeshFilter::new(device, &vertices, &indices)
+}
+
+/// The vertices and indices of `create_torus`, without uploading them
+pub fn torus_data(
+    radius: f32,
+    tube_radius: f32,
+    radial_segments: u32,
+    tubular_segments: u32,
+) -> (Vec<BasicVertex>, Vec<u32>) {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
@@ -558,6 +862,9 @@ pub fn create_torus(
                     j as f32 / radial_segments as f32,
                 ]
                 .into(),
+                // points away from the center of the tube
+                normal: Vec3::new(v.cos() * u.cos(), v.sin(), v.cos() * u.sin()),
+                tangent: Vec4::ZERO,
             });
         }
     }
@@ -571,18 +878,17 @@ pub fn create_torus(
             let d = c + 1;
 
             indices.push(a);
-            indices.push(c);
             indices.push(b);
+            indices.push(c);
 
             indices.push(b);
-            indices.push(c);
             indices.push(d);
+            indices.push(c);
         }
     }
 
-    BasicMeshFilter {
-        filter: MeshFilter::new(device, &vertices, &indices),
-    }
+    compute_tangents(&mut vertices, &indices);
+    (vertices, indices)
 }
 
 /// Creates a cone mesh with a specified radius and height.
@@ -613,3 +919,279 @@ pub fn create_cone(
         open_ended,
     )
 }
+
+/// Fills in `tangent` for every vertex from the direction u increases across each triangle,
+/// for normal mapping. Each vertex averages the tangents of the triangles using it, which are
+/// then made perpendicular to its normal with Gram-Schmidt. `w` is the bitangent's handedness,
+/// so the bitangent is `cross(normal, tangent.xyz) * tangent.w`. Vertices whose triangles have
+/// degenerate UVs get an arbitrary tangent perpendicular to the normal.
+pub fn compute_tangents(vertices: &mut [BasicVertex], indices: &[u32]) {
+    let mut tangents = vec![Vec3::ZERO; vertices.len()];
+    let mut bitangents = vec![Vec3::ZERO; vertices.len()];
+
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| triangle[i] as usize);
+        let edge_1 = vertices[b].position - vertices[a].position;
+        let edge_2 = vertices[c].position - vertices[a].position;
+        let delta_uv_1 = vertices[b].tex_coords - vertices[a].tex_coords;
+        let delta_uv_2 = vertices[c].tex_coords - vertices[a].tex_coords;
+
+        let determinant = delta_uv_1.x * delta_uv_2.y - delta_uv_2.x * delta_uv_1.y;
+        if determinant.abs() <= f32::EPSILON {
+            continue;
+        }
+        let r = 1.0 / determinant;
+        let tangent = (edge_1 * delta_uv_2.y - edge_2 * delta_uv_1.y) * r;
+        let bitangent = (edge_2 * delta_uv_1.x - edge_1 * delta_uv_2.x) * r;
+
+        for index in [a, b, c] {
+            tangents[index] += tangent;
+            bitangents[index] += bitangent;
+        }
+    }
+
+    for ((vertex, tangent), bitangent) in vertices.iter_mut().zip(tangents).zip(bitangents) {
+        let normal = vertex.normal;
+        let tangent = (tangent - normal * normal.dot(tangent))
+            .try_normalize()
+            .unwrap_or_else(|| normal.any_orthonormal_vector());
+        let handedness = if normal.cross(tangent).dot(bitangent) < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+
+        vertex.tangent = tangent.extend(handedness);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Mat4, Vec2};
+
+    use super::*;
+
+    #[test]
+    fn icosphere_has_20_times_4_to_the_n_triangles() {
+        for subdivisions in 0..=2 {
+            let (_, indices) = icosphere_data(1.0, subdivisions);
+            assert_eq!(indices.len() / 3, 20 * 4usize.pow(subdivisions));
+        }
+    }
+
+    #[test]
+    fn icosphere_shares_midpoints_and_keeps_its_radius() {
+        let radius = 1.5;
+        for subdivisions in 0..=3 {
+            let (vertices, _) = icosphere_data(radius, subdivisions);
+
+            // every edge is split once, however many triangles share it
+            assert_eq!(vertices.len(), 10 * 4usize.pow(subdivisions) + 2);
+            for vertex in vertices {
+                assert!((vertex.position.length() - radius).abs() < 1e-5);
+            }
+        }
+    }
+
+    /// Whether the triangle is counter-clockwise on screen for a camera at the origin looking
+    /// down +Z, i.e. front facing for `FrontFace::Ccw`
+    fn is_front_facing(positions: [Vec3; 3]) -> bool {
+        let view_proj = Mat4::perspective_lh(60.0_f32.to_radians(), 1.0, 0.1, 100.0)
+            * Mat4::from_translation(Vec3::new(0.0, 0.0, 5.0));
+        let [a, b, c] = positions.map(|position| view_proj.project_point3(position).truncate());
+        (b - a).perp_dot(c - a) > 0.0
+    }
+
+    #[test]
+    fn color_quad_faces_the_default_camera() {
+        let (vertices, indices) = color_quad_data(2.0, 1.0, [Vec4::ONE; 4]);
+
+        assert_eq!(indices.len(), 6);
+        for triangle in indices.chunks(3) {
+            let positions = [0, 1, 2].map(|corner| vertices[triangle[corner] as usize].position);
+            assert!(is_front_facing(positions), "{:?} is back facing", triangle);
+        }
+    }
+
+    #[test]
+    fn capsule_spans_its_height_plus_both_caps() {
+        let (radius, height) = (0.5, 2.0);
+        let (vertices, _) = capsule_data(radius, height, 8, 3, 4);
+
+        let top = vertices
+            .iter()
+            .map(|vertex| vertex.position.y)
+            .fold(f32::MIN, f32::max);
+        let bottom = vertices
+            .iter()
+            .map(|vertex| vertex.position.y)
+            .fold(f32::MAX, f32::min);
+        assert!((top - (height / 2.0 + radius)).abs() < 1e-5);
+        assert!((bottom + height / 2.0 + radius).abs() < 1e-5);
+        assert!((top - bottom - (height + 2.0 * radius)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn capsule_rings_meet_at_the_seams() {
+        let (radial_segments, height_segments, cap_segments) = (8, 3, 4);
+        let (vertices, _) = capsule_data(0.5, 2.0, radial_segments, height_segments, cap_segments);
+
+        let ring = (radial_segments + 1) as usize;
+        let cylinder_start = (cap_segments as usize + 1) * ring;
+        let bottom_start = cylinder_start + (height_segments as usize + 1) * ring;
+        // the last ring of each section lies on the first ring of the next
+        for (end, start) in [
+            (cylinder_start - ring, cylinder_start),
+            (bottom_start - ring, bottom_start),
+        ] {
+            for i in 0..ring {
+                let (a, b) = (&vertices[end + i], &vertices[start + i]);
+                assert!(a.position.distance(b.position) < 1e-5);
+                assert!(a.normal.distance(b.normal) < 1e-5);
+                assert!((a.tex_coords.y - b.tex_coords.y).abs() < 1e-5);
+            }
+        }
+    }
+
+    /// Asserts every triangle is counter-clockwise as seen from the side its vertex normals
+    /// point to, so back face culling keeps the outside of the mesh
+    fn assert_outside_is_front_facing(
+        name: &str,
+        (vertices, indices): (Vec<BasicVertex>, Vec<u32>),
+    ) {
+        for triangle in indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|corner| &vertices[triangle[corner] as usize]);
+            let winding = (b.position - a.position).cross(c.position - a.position);
+            // slivers at poles and apexes have no winding to check
+            if winding.length() < 1e-6 {
+                continue;
+            }
+
+            // in left-handed space, a triangle the viewer sees counter-clockwise has its
+            // winding pointing away from the viewer
+            let normal = a.normal + b.normal + c.normal;
+            assert!(
+                winding.dot(normal) < 0.0,
+                "{} triangle {:?} is wound inside out",
+                name,
+                triangle
+            );
+        }
+    }
+
+    #[test]
+    fn every_primitive_winds_its_outside_counter_clockwise() {
+        assert_outside_is_front_facing("plane", plane_data(2.0, 2.0, 2, 2));
+        assert_outside_is_front_facing("cube", cube_data(1.0, 2));
+        assert_outside_is_front_facing("sphere", sphere_data(1.0, 8, 6));
+        assert_outside_is_front_facing("icosphere", icosphere_data(1.0, 1));
+        assert_outside_is_front_facing("capsule", capsule_data(0.5, 1.0, 8, 2, 3));
+        assert_outside_is_front_facing("cylinder", cylinder_data(0.5, 1.0, 2.0, 8, 2, false));
+        assert_outside_is_front_facing("cone", cylinder_data(0.0, 1.0, 2.0, 8, 2, false));
+        assert_outside_is_front_facing("torus", torus_data(1.0, 0.25, 8, 6));
+    }
+
+    #[test]
+    fn sphere_normals_are_unit_length_and_point_outward() {
+        let radius = 2.0;
+        let (vertices, _) = sphere_data(radius, 12, 8);
+
+        for vertex in vertices {
+            assert!((vertex.normal.length() - 1.0).abs() < 1e-5);
+            assert!(
+                vertex.normal.abs_diff_eq(vertex.position / radius, 1e-5),
+                "normal {} at {}",
+                vertex.normal,
+                vertex.position
+            );
+        }
+    }
+
+    /// A quad facing -Z with u running along `u_direction` on x and v pointing down
+    fn tangent_quad(u_direction: f32) -> Vec<BasicVertex> {
+        let mut vertices: Vec<BasicVertex> = [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]]
+            .map(|[x, y]| BasicVertex {
+                position: Vec3::new(x, y, 0.0),
+                tex_coords: [(u_direction * x + 1.0) / 2.0, (1.0 - y) / 2.0].into(),
+                normal: Vec3::NEG_Z,
+                tangent: Vec4::ZERO,
+            })
+            .into();
+        compute_tangents(&mut vertices, &[0, 1, 2, 0, 2, 3]);
+        vertices
+    }
+
+    #[test]
+    fn tangents_follow_u_and_mirrored_uvs_flip_handedness() {
+        for vertex in tangent_quad(1.0) {
+            assert_eq!(vertex.tangent, Vec4::new(1.0, 0.0, 0.0, 1.0));
+        }
+        for vertex in tangent_quad(-1.0) {
+            assert_eq!(vertex.tangent, Vec4::new(-1.0, 0.0, 0.0, -1.0));
+        }
+    }
+
+    #[test]
+    fn sphere_tangents_are_perpendicular_unit_vectors() {
+        let (vertices, _) = sphere_data(1.0, 12, 8);
+
+        for vertex in vertices {
+            let tangent = vertex.tangent.truncate();
+            assert!((tangent.length() - 1.0).abs() < 1e-5);
+            assert!(tangent.dot(vertex.normal).abs() < 1e-5);
+            assert_eq!(vertex.tangent.w.abs(), 1.0);
+        }
+    }
+
+    /// The texture coordinates at an NDC position inside a triangle of a screen-space mesh
+    fn uv_at(vertices: &[BasicVertex], triangle: &[u32], point: Vec2) -> Option<Vec2> {
+        let [a, b, c] = [0, 1, 2].map(|corner| &vertices[triangle[corner] as usize]);
+        let area = (b.position - a.position)
+            .truncate()
+            .perp_dot((c.position - a.position).truncate());
+        let weight = |from: &BasicVertex, to: &BasicVertex| {
+            (to.position.truncate() - from.position.truncate())
+                .perp_dot(point - from.position.truncate())
+                / area
+        };
+        let [weight_a, weight_b, weight_c] = [weight(b, c), weight(c, a), weight(a, b)];
+        let inside = [weight_a, weight_b, weight_c]
+            .iter()
+            .all(|weight| *weight >= -1e-6);
+
+        inside.then(|| weight_a * a.tex_coords + weight_b * b.tex_coords + weight_c * c.tex_coords)
+    }
+
+    #[test]
+    fn screen_meshes_cover_the_screen_with_0_to_1_uvs() {
+        let (quad_vertices, quad_indices) = screen_quad_data();
+        assert_eq!((quad_vertices.len(), quad_indices.len()), (4, 6));
+        let (triangle_vertices, triangle_indices) = fullscreen_triangle_data();
+        assert_eq!((triangle_vertices.len(), triangle_indices.len()), (3, 3));
+
+        // texture space has v pointing down
+        let corners = [
+            (Vec2::new(-1.0, 1.0), Vec2::new(0.0, 0.0)),
+            (Vec2::new(1.0, 1.0), Vec2::new(1.0, 0.0)),
+            (Vec2::new(-1.0, -1.0), Vec2::new(0.0, 1.0)),
+            (Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0)),
+        ];
+        for (vertices, indices) in [
+            (quad_vertices, quad_indices),
+            (triangle_vertices, triangle_indices),
+        ] {
+            for (corner, uv) in corners {
+                let found = indices
+                    .chunks(3)
+                    .find_map(|triangle| uv_at(&vertices, triangle, corner));
+                assert!(
+                    found.is_some_and(|found| found.abs_diff_eq(uv, 1e-6)),
+                    "{} has uv {:?} instead of {}",
+                    corner,
+                    found,
+                    uv
+                );
+            }
+        }
+    }
+}
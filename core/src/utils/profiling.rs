@@ -0,0 +1,63 @@
+/// Opens a `tracing` span that lasts until the end of the enclosing scope.
+/// Only compiled with the `profiling` feature, otherwise the name and fields aren't evaluated.
+///
+/// `profile_scope!("update", entities = world.entities().len());`
+macro_rules! profile_scope {
+    ($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "profiling")]
+        let _profile_span = tracing::info_span!($name $(, $field = $value)*).entered();
+    };
+}
+
+pub(crate) use profile_scope;
+
+#[cfg(all(test, feature = "profiling"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::{Event, Metadata, Subscriber, span};
+
+    /// The name and field names of every span opened while it's the default subscriber
+    type OpenedSpans = Arc<Mutex<Vec<(&'static str, Vec<&'static str>)>>>;
+
+    struct SpanRecorder(OpenedSpans);
+
+    impl Subscriber for SpanRecorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+            let metadata = span.metadata();
+            let fields = metadata.fields().iter().map(|field| field.name()).collect();
+            let mut spans = self.0.lock().unwrap();
+            spans.push((metadata.name(), fields));
+            span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, _span: &span::Id) {}
+
+        fn exit(&self, _span: &span::Id) {}
+    }
+
+    #[test]
+    fn profile_scope_opens_a_span_with_its_fields() {
+        let spans = OpenedSpans::default();
+
+        tracing::subscriber::with_default(SpanRecorder(spans.clone()), || {
+            profile_scope!("update", entities = 3);
+            profile_scope!("render");
+        });
+
+        assert_eq!(
+            *spans.lock().unwrap(),
+            vec![("update", vec!["entities"]), ("render", vec![])]
+        );
+    }
+}
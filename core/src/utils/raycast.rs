@@ -0,0 +1,158 @@
+use glam::{Mat4, Vec3};
+
+use crate::gpu_resources::types::basic_vertex::BasicVertex;
+
+/// Triangles this close to parallel with the ray are treated as misses
+const PARALLEL_EPSILON: f32 = 1e-7;
+
+/// Intersects a ray with a single triangle using the Möller–Trumbore algorithm.
+/// Returns the distance along `direction` (in multiples of its length) to the hit.
+/// Both faces of the triangle are hit, matching the unculled pipelines.
+pub fn ray_triangle_intersect(
+    origin: Vec3,
+    direction: Vec3,
+    a: Vec3,
+    b: Vec3,
+    c: Vec3,
+) -> Option<f32> {
+    let edge_ab = b - a;
+    let edge_ac = c - a;
+
+    let p = direction.cross(edge_ac);
+    let determinant = edge_ab.dot(p);
+    if determinant.abs() < PARALLEL_EPSILON {
+        return None;
+    }
+    let inverse_determinant = 1.0 / determinant;
+
+    let to_origin = origin - a;
+    let u = to_origin.dot(p) * inverse_determinant;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = to_origin.cross(edge_ab);
+    let v = direction.dot(q) * inverse_determinant;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = edge_ac.dot(q) * inverse_determinant;
+    (t >= 0.0).then_some(t)
+}
+
+/// Intersects a world space ray with an indexed triangle mesh placed by `model_matrix`.
+/// Returns the distance along `direction` to the nearest hit, in world units when
+/// `direction` is normalized.
+pub fn ray_mesh_intersect(
+    origin: Vec3,
+    direction: Vec3,
+    vertices: &[BasicVertex],
+    indices: &[u32],
+    model_matrix: Mat4,
+) -> Option<f32> {
+    // Move the ray into model space instead of transforming every vertex.
+    // The transform is affine, so distances along the ray are unchanged.
+    let inverse_model = model_matrix.inverse();
+    let local_origin = inverse_model.transform_point3(origin);
+    let local_direction = inverse_model.transform_vector3(direction);
+
+    indices
+        .chunks_exact(3)
+        .filter_map(|triangle| {
+            let a = vertices.get(triangle[0] as usize)?.position;
+            let b = vertices.get(triangle[1] as usize)?.position;
+            let c = vertices.get(triangle[2] as usize)?.position;
+            ray_triangle_intersect(local_origin, local_direction, a, b, c)
+        })
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+/// Intersects a ray with a sphere, returning the distance to the first hit.
+/// A ray starting inside the sphere hits at distance 0.
+pub fn ray_sphere_intersect(
+    origin: Vec3,
+    direction: Vec3,
+    center: Vec3,
+    radius: f32,
+) -> Option<f32> {
+    let to_origin = origin - center;
+    let a = direction.length_squared();
+    let half_b = to_origin.dot(direction);
+    let c = to_origin.length_squared() - radius * radius;
+
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+
+    let discriminant = half_b * half_b - a * c;
+    if discriminant < 0.0 || a == 0.0 {
+        return None;
+    }
+
+    let t = (-half_b - discriminant.sqrt()) / a;
+    (t >= 0.0).then_some(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::{Vec2, Vec4};
+
+    const A: Vec3 = Vec3::new(-1.0, -1.0, 0.0);
+    const B: Vec3 = Vec3::new(1.0, -1.0, 0.0);
+    const C: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+
+    #[test]
+    fn ray_hits_a_known_triangle_at_its_distance() {
+        let hit = ray_triangle_intersect(Vec3::new(0.0, 0.0, -5.0), Vec3::Z, A, B, C);
+        assert!((hit.unwrap() - 5.0).abs() < 1e-5);
+
+        // from behind, since both faces are hit
+        let hit = ray_triangle_intersect(Vec3::new(0.0, 0.0, 3.0), Vec3::NEG_Z, A, B, C);
+        assert!((hit.unwrap() - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ray_misses_beside_behind_or_parallel_to_the_triangle() {
+        let beside = ray_triangle_intersect(Vec3::new(5.0, 0.0, -5.0), Vec3::Z, A, B, C);
+        let behind = ray_triangle_intersect(Vec3::new(0.0, 0.0, 5.0), Vec3::Z, A, B, C);
+        let parallel = ray_triangle_intersect(Vec3::new(0.0, 0.0, -5.0), Vec3::X, A, B, C);
+        assert_eq!(beside, None);
+        assert_eq!(behind, None);
+        assert_eq!(parallel, None);
+    }
+
+    #[test]
+    fn mesh_hit_accounts_for_the_model_matrix() {
+        let vertices: Vec<BasicVertex> = [A, B, C]
+            .into_iter()
+            .map(|position| BasicVertex {
+                position,
+                tex_coords: Vec2::ZERO,
+                normal: Vec3::NEG_Z,
+                tangent: Vec4::ZERO,
+            })
+            .collect();
+        let model_matrix = Mat4::from_translation(Vec3::new(0.0, 0.0, 2.0));
+
+        let hit = ray_mesh_intersect(
+            Vec3::new(0.0, 0.0, -5.0),
+            Vec3::Z,
+            &vertices,
+            &[0, 1, 2],
+            model_matrix,
+        );
+        assert!((hit.unwrap() - 7.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ray_starting_inside_a_sphere_hits_at_zero() {
+        assert_eq!(
+            ray_sphere_intersect(Vec3::ZERO, Vec3::X, Vec3::ZERO, 1.0),
+            Some(0.0)
+        );
+        let hit = ray_sphere_intersect(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, Vec3::ZERO, 1.0);
+        assert!((hit.unwrap() - 4.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+
+/// A pixel rectangle that rendering is clipped to, with the origin at the top left
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScissorRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScissorRect {
+    pub const EMPTY: Self = Self::new(0, 0, 0, 0);
+
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// A rect covering the whole render target
+    pub fn full(target_width: u32, target_height: u32) -> Self {
+        Self::new(0, 0, target_width, target_height)
+    }
+
+    /// Clips the rect to the render target, since wgpu rejects scissors that extend past it.
+    /// Returns `None` when nothing of the rect lies inside the target.
+    pub fn clamped(&self, target_width: u32, target_height: u32) -> Option<Self> {
+        self.intersection(&Self::full(target_width, target_height))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// The overlap of two rects, or `None` if they don't overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self
+            .x
+            .saturating_add(self.width)
+            .min(other.x.saturating_add(other.width));
+        let bottom = self
+            .y
+            .saturating_add(self.height)
+            .min(other.y.saturating_add(other.height));
+
+        if right <= x || bottom <= y {
+            return None;
+        }
+
+        Some(Self::new(x, y, right - x, bottom - y))
+    }
+
+    pub fn apply(&self, render_pass: &mut wgpu::RenderPass) {
+        render_pass.set_scissor_rect(self.x, self.y, self.width, self.height);
+    }
+}
+
+/// Nested scissor rects for a render pass. Each pushed rect is clipped to the one below it,
+/// so UI elements drawn by sub-renderers can't spill out of their parent panel.
+/// Rects that don't overlap their parent become empty, which wgpu accepts and draws nothing.
+pub struct ScissorStack {
+    stack: Vec<ScissorRect>,
+}
+
+impl ScissorStack {
+    /// Starts a stack for a pass on a target of the given size, optionally clipped to `base`
+    pub fn new(
+        render_pass: &mut wgpu::RenderPass,
+        target_width: u32,
+        target_height: u32,
+        base: Option<ScissorRect>,
+    ) -> Self {
+        let full = ScissorRect::full(target_width, target_height);
+        let rect = match base {
+            Some(base) => base.intersection(&full).unwrap_or(ScissorRect::EMPTY),
+            None => full,
+        };
+
+        let stack = Self { stack: vec![rect] };
+        stack.apply(render_pass);
+        stack
+    }
+
+    /// The rect currently applied to the pass
+    pub fn current(&self) -> ScissorRect {
+        *self.stack.last().unwrap()
+    }
+
+    /// Whether the current rect is empty, in which case draws can be skipped
+    pub fn is_empty(&self) -> bool {
+        self.current().is_empty()
+    }
+
+    pub fn push(&mut self, render_pass: &mut wgpu::RenderPass, rect: ScissorRect) {
+        let rect = rect
+            .intersection(&self.current())
+            .unwrap_or(ScissorRect::EMPTY);
+        self.stack.push(rect);
+        self.apply(render_pass);
+    }
+
+    /// Restores the previous rect. The base rect is never popped.
+    pub fn pop(&mut self, render_pass: &mut wgpu::RenderPass) {
+        if self.stack.len() <= 1 {
+            log::warn!("Tried to pop the base scissor rect");
+            return;
+        }
+
+        self.stack.pop();
+        self.apply(render_pass);
+    }
+
+    fn apply(&self, render_pass: &mut wgpu::RenderPass) {
+        self.current().apply(render_pass);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_device::request_device;
+
+    #[test]
+    fn intersection_is_the_overlap() {
+        let a = ScissorRect::new(0, 0, 10, 10);
+        let b = ScissorRect::new(5, 2, 10, 4);
+
+        assert_eq!(a.intersection(&b), Some(ScissorRect::new(5, 2, 5, 4)));
+        assert_eq!(b.intersection(&a), a.intersection(&b));
+    }
+
+    #[test]
+    fn touching_or_separate_rects_dont_intersect() {
+        let a = ScissorRect::new(0, 0, 10, 10);
+
+        assert_eq!(a.intersection(&ScissorRect::new(10, 0, 5, 5)), None);
+        assert_eq!(a.intersection(&ScissorRect::new(20, 20, 5, 5)), None);
+        assert_eq!(a.intersection(&ScissorRect::EMPTY), None);
+    }
+
+    #[test]
+    fn clamped_clips_to_the_target() {
+        let rect = ScissorRect::new(50, 20, 100, 100);
+
+        assert_eq!(rect.clamped(80, 60), Some(ScissorRect::new(50, 20, 30, 40)));
+        assert_eq!(rect.clamped(40, 60), None);
+        // huge rects don't overflow
+        assert_eq!(
+            ScissorRect::new(1, 1, u32::MAX, u32::MAX).clamped(8, 8),
+            Some(ScissorRect::new(1, 1, 7, 7))
+        );
+    }
+
+    const SIZE: u32 = 8;
+    /// Rows copied out of a texture must be padded to this many bytes
+    const PADDED_ROW_BYTES: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+    /// Covers the target with instance 0 drawing gray and every other instance white
+    const FILL_SHADER: &str = r#"
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) @interpolate(flat) instance: u32,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex: u32, @builtin(instance_index) instance: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex << 1u) & 2u), f32(vertex & 2u));
+    return VertexOutput(vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0), instance);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return select(vec4<f32>(1.0), vec4<f32>(0.5, 0.5, 0.5, 1.0), in.instance == 0u);
+}
+"#;
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn nested_rects_clip_draws_when_read_back() {
+        let (device, queue) = request_device();
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(FILL_SHADER.into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                compilation_options: Default::default(),
+                targets: &[Some(format.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: SIZE,
+                height: SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&Default::default());
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (PADDED_ROW_BYTES * SIZE) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&pipeline);
+
+            let base = ScissorRect::new(0, 0, 6, 6);
+            let mut scissor_stack = ScissorStack::new(&mut render_pass, SIZE, SIZE, Some(base));
+            render_pass.draw(0..3, 0..1);
+
+            // only the corner shared with the base rect is drawn
+            scissor_stack.push(&mut render_pass, ScissorRect::new(4, 4, 4, 4));
+            assert_eq!(scissor_stack.current(), ScissorRect::new(4, 4, 2, 2));
+            render_pass.draw(0..3, 1..2);
+
+            scissor_stack.pop(&mut render_pass);
+            scissor_stack.pop(&mut render_pass);
+            assert_eq!(scissor_stack.current(), base);
+        }
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(PADDED_ROW_BYTES),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+        queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        device.poll(wgpu::Maintain::Wait);
+        let texels = slice.get_mapped_range();
+
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let red = texels[(y * PADDED_ROW_BYTES + x * 4) as usize];
+                let expected = match (x, y) {
+                    (4..6, 4..6) => 255,
+                    (0..6, 0..6) => 128,
+                    _ => 0,
+                };
+                assert!(
+                    red.abs_diff(expected) <= 1,
+                    "pixel ({}, {}) is {} instead of {}",
+                    x,
+                    y,
+                    red,
+                    expected
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Fixtures for tests that need a GPU. Those tests are `#[ignore]`d so machines without
+//! an adapter report them as skipped, run them with `cargo test -- --include-ignored`.
+
+use std::sync::Arc;
+
+use crate::core::Core;
+
+/// Any adapter wgpu can find
+pub fn request_adapter() -> wgpu::Adapter {
+    let instance = wgpu::Instance::default();
+    futures::executor::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        .expect("GPU tests need an adapter")
+}
+
+/// A device with every limit the adapter offers
+pub fn request_device() -> (wgpu::Device, wgpu::Queue) {
+    request_device_with_limits(|adapter| adapter.limits())
+}
+
+/// Like `request_device`, with limits picked from the adapter
+pub fn request_device_with_limits(
+    limits: impl FnOnce(&wgpu::Adapter) -> wgpu::Limits,
+) -> (wgpu::Device, wgpu::Queue) {
+    let adapter = request_adapter();
+    let descriptor = wgpu::DeviceDescriptor {
+        label: Some("test_device"),
+        required_features: wgpu::Features::empty(),
+        required_limits: limits(&adapter),
+    };
+    futures::executor::block_on(adapter.request_device(&descriptor, None))
+        .expect("Failed to create test device")
+}
+
+/// A headless core rendering into an Rgba8Unorm target of the given size
+pub fn headless_core(width: u32, height: u32) -> Core {
+    let (device, queue) = request_device();
+    Core::new_headless(
+        Arc::new(device),
+        Arc::new(queue),
+        width,
+        height,
+        wgpu::TextureFormat::Rgba8Unorm,
+    )
+}
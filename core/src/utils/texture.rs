@@ -1,17 +1,23 @@
 use image::{GenericImageView, ImageResult};
 use serde::Deserialize;
 
-// Default sampler configuration when no TOML is provided
+use crate::gpu_resources::pipelines::mipmap_pipeline::MipmapPipeline;
+
+/// Default upper LOD clamp, high enough to reach the last mip of any texture wgpu can create
+pub const DEFAULT_LOD_MAX_CLAMP: f32 = 32.0;
+
+// Default sampler configuration when no TOML is provided.
+// Filters trilinearly across the whole mip chain, textures without mips just sample mip 0.
 const DEFAULT_SAMPLER_DESCRIPTOR: wgpu::SamplerDescriptor = wgpu::SamplerDescriptor {
     label: Some("default_sampler"),
     address_mode_u: wgpu::AddressMode::ClampToEdge,
     address_mode_v: wgpu::AddressMode::ClampToEdge,
     address_mode_w: wgpu::AddressMode::ClampToEdge,
     mag_filter: wgpu::FilterMode::Linear,
-    min_filter: wgpu::FilterMode::Nearest,
-    mipmap_filter: wgpu::FilterMode::Nearest,
+    min_filter: wgpu::FilterMode::Linear,
+    mipmap_filter: wgpu::FilterMode::Linear,
     lod_min_clamp: 0.0,
-    lod_max_clamp: 0.0,
+    lod_max_clamp: DEFAULT_LOD_MAX_CLAMP,
     compare: None,
     anisotropy_clamp: 1,
     border_color: None,
@@ -78,6 +84,7 @@ pub struct TextureBuilder<'a> {
     usage_type: TextureUsageType,
     sampler_config: Option<SamplerConfig>,
     data: Option<&'a [u8]>,
+    raw_data: Option<&'a [u8]>,
 }
 
 impl<'a> TextureBuilder<'a> {
@@ -94,6 +101,7 @@ impl<'a> TextureBuilder<'a> {
             usage_type: TextureUsageType::Standard,
             sampler_config: None,
             data: None,
+            raw_data: None,
         }
     }
 
@@ -168,6 +176,12 @@ impl<'a> TextureBuilder<'a> {
         self
     }
 
+    /// Texel data already laid out in the texture's format, written without decoding
+    pub fn raw_data(mut self, raw_data: &'a [u8]) -> Self {
+        self.raw_data = Some(raw_data);
+        self
+    }
+
     // Factory methods for common texture types
     pub fn compute_output(mut self) -> Self {
         self.usage_type = TextureUsageType::ComputeOutput;
@@ -193,14 +207,25 @@ impl<'a> TextureBuilder<'a> {
             depth_or_array_layers: 1,
         };
 
+        // Lower mip levels are rendered from the uploaded image, which not every format allows
+        let generates_mipmaps = self.mip_level_count > 1
+            && self.queue.is_some()
+            && (self.data.is_some() || self.raw_data.is_some());
+        if generates_mipmaps && !MipmapPipeline::supports_format(self.device, self.format) {
+            return Err(format!(
+                "Can't generate mipmaps for {:?}, it isn't filterable and renderable",
+                self.format
+            ));
+        }
+
         // Determine usage and sample count based on usage type
         let (usage, sample_count) = match self.usage_type {
             TextureUsageType::Standard => {
                 let mut usage = wgpu::TextureUsages::TEXTURE_BINDING;
-                if self.data.is_some() {
+                if self.data.is_some() || self.raw_data.is_some() {
                     usage |= wgpu::TextureUsages::COPY_DST;
                 }
-                if self.mip_level_count > 1 {
+                if generates_mipmaps {
                     usage |= wgpu::TextureUsages::RENDER_ATTACHMENT;
                 }
                 (usage, 1)
@@ -256,23 +281,57 @@ impl<'a> TextureBuilder<'a> {
                     },
                     size,
                 );
-
-                // Generate mipmaps if needed
-                if self.mip_level_count > 1 {
-                    generate_mipmaps(
-                        self.device,
-                        queue,
-                        &texture,
-                        self.format,
-                        self.mip_level_count,
-                        size,
-                    );
-                }
             } else {
                 return Err("Failed to load image data".to_string());
             }
         }
 
+        // Write raw texel data if provided
+        if let (Some(raw_data), Some(queue)) = (self.raw_data, self.queue) {
+            let block_size = self
+                .format
+                .block_copy_size(None)
+                .ok_or_else(|| format!("Raw data is not supported for {:?}", self.format))?;
+
+            let expected_size = (block_size * self.width * self.height) as usize;
+            if raw_data.len() != expected_size {
+                return Err(format!(
+                    "Raw data is {} bytes but a {}x{} {:?} texture needs {}",
+                    raw_data.len(),
+                    self.width,
+                    self.height,
+                    self.format,
+                    expected_size
+                ));
+            }
+
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                raw_data,
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(block_size * self.width),
+                    rows_per_image: Some(self.height),
+                },
+                size,
+            );
+        }
+
+        // Fill the lower mip levels from the uploaded image
+        if generates_mipmaps && let Some(queue) = self.queue {
+            MipmapPipeline::new(self.device, self.format).generate(
+                self.device,
+                queue,
+                &texture,
+                self.mip_level_count,
+            );
+        }
+
         // Create view
         let view = texture.create_view(&wgpu::TextureViewDescriptor {
             label: Some(&format!("{}_view", self.label)),
@@ -394,6 +453,24 @@ impl Texture {
         builder.build().expect("Failed to create depth texture")
     }
 
+    /// Creates a depth texture with a comparison sampler, for rendering and sampling shadow maps.
+    /// Samples return 1.0 where the reference depth is less than or equal to the stored depth,
+    /// and linear filtering blends neighbouring comparisons for cheap PCF.
+    pub fn new_shadow_map(device: &wgpu::Device, size: u32, label: Option<&str>) -> Self {
+        TextureBuilder::new(device)
+            .size(size, size)
+            .depth_texture()
+            .label(label.unwrap_or("shadow_map"))
+            .sampler_config(SamplerConfig {
+                mag_filter: Some("Linear".to_string()),
+                min_filter: Some("Linear".to_string()),
+                compare: Some("LessEqual".to_string()),
+                ..Default::default()
+            })
+            .build()
+            .expect("Failed to create shadow map")
+    }
+
     pub fn new_render_target(
         device: &wgpu::Device,
         width: u32,
@@ -422,19 +499,6 @@ impl Texture {
     }
 }
 
-// Helper functions (with minimal changes from original)
-fn generate_mipmaps(
-    _device: &wgpu::Device,
-    _queue: &wgpu::Queue,
-    _texture: &wgpu::Texture,
-    _format: wgpu::TextureFormat,
-    _mip_level_count: u32,
-    _size: wgpu::Extent3d,
-) {
-    // Same placeholder implementation as before
-    println!("Note: Mipmap generation requested but not fully implemented.");
-}
-
 // Helper functions for parsing values from strings (unchanged)
 fn parse_texture_format(format_str: &Option<String>) -> wgpu::TextureFormat {
     match format_str {
@@ -520,6 +584,7 @@ fn create_sampler_descriptor<'a>(
         if let Some(clamp) = config.anisotropy_clamp {
             descriptor.anisotropy_clamp = clamp;
         }
+        apply_anisotropy_requirements(&mut descriptor);
 
         // Parse border color
         if let Some(color) = &config.border_color {
@@ -530,6 +595,38 @@ fn create_sampler_descriptor<'a>(
     descriptor
 }
 
+/// Largest anisotropy clamp wgpu accepts. Backends clamp it further to what the hardware supports.
+const MAX_ANISOTROPY_CLAMP: u16 = 16;
+
+/// wgpu rejects samplers with an anisotropy clamp above 1 unless every filter is linear,
+/// and clamps outside 1..=16. Coerce the descriptor into a valid one instead of panicking.
+fn apply_anisotropy_requirements(descriptor: &mut wgpu::SamplerDescriptor) {
+    let clamp = descriptor.anisotropy_clamp.clamp(1, MAX_ANISOTROPY_CLAMP);
+    if clamp != descriptor.anisotropy_clamp {
+        log::warn!(
+            "Anisotropy clamp {} is outside 1..={}, using {}",
+            descriptor.anisotropy_clamp,
+            MAX_ANISOTROPY_CLAMP,
+            clamp
+        );
+        descriptor.anisotropy_clamp = clamp;
+    }
+
+    if descriptor.anisotropy_clamp > 1
+        && (descriptor.mag_filter != wgpu::FilterMode::Linear
+            || descriptor.min_filter != wgpu::FilterMode::Linear
+            || descriptor.mipmap_filter != wgpu::FilterMode::Linear)
+    {
+        log::warn!(
+            "Anisotropic filtering requires linear filters, forcing {:?} sampler to Linear",
+            descriptor.label
+        );
+        descriptor.mag_filter = wgpu::FilterMode::Linear;
+        descriptor.min_filter = wgpu::FilterMode::Linear;
+        descriptor.mipmap_filter = wgpu::FilterMode::Linear;
+    }
+}
+
 // The rest of the helper functions (unchanged)
 fn parse_address_mode(mode: &str) -> wgpu::AddressMode {
     match mode {
@@ -622,3 +719,251 @@ macro_rules! include_texture {
         }
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_device::request_device;
+
+    #[test]
+    fn anisotropy_forces_linear_filters_and_clamps_to_16() {
+        let config = SamplerConfig {
+            mag_filter: Some("Nearest".to_string()),
+            min_filter: Some("Nearest".to_string()),
+            anisotropy_clamp: Some(32),
+            ..Default::default()
+        };
+
+        let descriptor = create_sampler_descriptor(None, &Some(config));
+
+        assert_eq!(descriptor.anisotropy_clamp, 16);
+        assert_eq!(descriptor.mag_filter, wgpu::FilterMode::Linear);
+        assert_eq!(descriptor.min_filter, wgpu::FilterMode::Linear);
+        assert_eq!(descriptor.mipmap_filter, wgpu::FilterMode::Linear);
+    }
+
+    #[test]
+    fn nearest_filters_are_kept_without_anisotropy() {
+        let config = SamplerConfig {
+            mag_filter: Some("Nearest".to_string()),
+            anisotropy_clamp: Some(0),
+            ..Default::default()
+        };
+
+        let descriptor = create_sampler_descriptor(None, &Some(config));
+
+        assert_eq!(descriptor.anisotropy_clamp, 1);
+        assert_eq!(descriptor.mag_filter, wgpu::FilterMode::Nearest);
+    }
+
+    #[test]
+    fn default_sampler_reaches_every_mip() {
+        let descriptor = create_sampler_descriptor(None, &None);
+
+        // a full chain for the largest texture the default limits allow, down to 1x1
+        let max_dimension = wgpu::Limits::default().max_texture_dimension_2d;
+        let mip_level_count = u32::BITS - max_dimension.leading_zeros();
+        assert!(descriptor.lod_max_clamp >= (mip_level_count - 1) as f32);
+        assert_eq!(descriptor.min_filter, wgpu::FilterMode::Linear);
+        assert_eq!(descriptor.mipmap_filter, wgpu::FilterMode::Linear);
+    }
+
+    #[test]
+    fn lod_max_clamp_can_still_be_overridden() {
+        let config = SamplerConfig {
+            lod_max_clamp: Some(0.0),
+            ..Default::default()
+        };
+
+        let descriptor = create_sampler_descriptor(None, &Some(config));
+
+        assert_eq!(descriptor.lod_max_clamp, 0.0);
+    }
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    /// Rows copied out of a texture must be padded to this many bytes
+    const PADDED_ROW_BYTES: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+    /// Copies a mip level into a render target, since sampled textures can't be copied from
+    const COPY_SHADER: &str = r#"
+@group(0) @binding(0)
+var source: texture_2d<f32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((vertex << 1u) & 2u), f32(vertex & 2u));
+    return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    return textureLoad(source, vec2<i32>(position.xy), 0);
+}
+"#;
+
+    /// Reads back the texels of one mip level of an Rgba8Unorm texture, row by row
+    fn read_mip(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+        mip_level: u32,
+    ) -> Vec<[u8; 4]> {
+        let size = texture
+            .size()
+            .mip_level_size(mip_level, texture.dimension());
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(COPY_SHADER.into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: None,
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                compilation_options: Default::default(),
+                targets: &[Some(format.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let source_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            base_mip_level: mip_level,
+            mip_level_count: Some(1),
+            ..Default::default()
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&source_view),
+            }],
+        });
+
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let target_view = target.create_view(&Default::default());
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (PADDED_ROW_BYTES * size.height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &target_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+        encoder.copy_texture_to_buffer(
+            target.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(PADDED_ROW_BYTES),
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+        queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.unwrap());
+        device.poll(wgpu::Maintain::Wait);
+        let bytes = slice.get_mapped_range();
+
+        (0..size.height)
+            .flat_map(|y| (0..size.width).map(move |x| (y * PADDED_ROW_BYTES + x * 4) as usize))
+            .map(|offset| bytes[offset..offset + 4].try_into().unwrap())
+            .collect()
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn generated_mips_average_the_level_above() {
+        let (device, queue) = request_device();
+
+        // the left half is red and the right half blue
+        let texels: Vec<u8> = (0..8 * 8)
+            .flat_map(|i| if i % 8 < 4 { RED } else { BLUE })
+            .collect();
+        let texture = TextureBuilder::new(&device)
+            .queue(&queue)
+            .size(8, 8)
+            .format(wgpu::TextureFormat::Rgba8Unorm)
+            .mip_level_count(4)
+            .raw_data(&texels)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            read_mip(&device, &queue, &texture.texture, 1)[..4],
+            [RED, RED, BLUE, BLUE]
+        );
+        assert_eq!(
+            read_mip(&device, &queue, &texture.texture, 2),
+            [RED, BLUE, RED, BLUE]
+        );
+
+        // the last level blends both halves
+        let [last] = read_mip(&device, &queue, &texture.texture, 3)[..] else {
+            panic!("the last mip isn't 1x1");
+        };
+        for (channel, expected) in last.into_iter().zip([128, 0, 128, 255]) {
+            assert!(channel.abs_diff(expected) <= 1, "last mip is {:?}", last);
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn unfilterable_formats_cant_generate_mips() {
+        let (device, queue) = request_device();
+
+        let result = TextureBuilder::new(&device)
+            .queue(&queue)
+            .size(8, 8)
+            .format(wgpu::TextureFormat::Rgba8Uint)
+            .mip_level_count(4)
+            .raw_data(&[0; 8 * 8 * 4])
+            .build();
+        assert!(result.is_err());
+    }
+}
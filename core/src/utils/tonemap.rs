@@ -0,0 +1,85 @@
+use glam::Vec3;
+
+/// Curve used to map HDR scene colors into the 0..1 range of the output.
+/// These match the operators in `tonemap.wgsl`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TonemapOperator {
+    /// Filmic curve with a toe and a soft shoulder
+    #[default]
+    Aces,
+    /// `c / (c + 1)`, never fully reaches white
+    Reinhard,
+}
+
+impl TonemapOperator {
+    /// Applies the operator after scaling `color` by `exposure`
+    pub fn apply(&self, color: Vec3, exposure: f32) -> Vec3 {
+        let color = color * exposure;
+        match self {
+            TonemapOperator::Aces => aces(color),
+            TonemapOperator::Reinhard => reinhard(color),
+        }
+    }
+}
+
+pub fn reinhard(color: Vec3) -> Vec3 {
+    color / (color + Vec3::ONE)
+}
+
+/// Narkowicz's fit of the ACES filmic curve
+pub fn aces(color: Vec3) -> Vec3 {
+    const A: f32 = 2.51;
+    const B: f32 = 0.03;
+    const C: f32 = 2.43;
+    const D: f32 = 0.59;
+    const E: f32 = 0.14;
+
+    ((color * (A * color + B)) / (color * (C * color + D) + E)).clamp(Vec3::ZERO, Vec3::ONE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scene luminances from black up to very bright highlights, doubling each step
+    fn hdr_sweep() -> impl Iterator<Item = f32> {
+        std::iter::once(0.0).chain((-10..=12).map(|exponent| 2f32.powi(exponent)))
+    }
+
+    #[test]
+    fn aces_stays_in_range_and_never_darkens_brighter_input() {
+        let mut previous = 0.0;
+        for value in hdr_sweep() {
+            let mapped = aces(Vec3::splat(value));
+            assert!(
+                mapped.cmpge(Vec3::ZERO).all() && mapped.cmple(Vec3::ONE).all(),
+                "aces({}) = {}",
+                value,
+                mapped
+            );
+            assert!(
+                mapped.x >= previous,
+                "aces({}) = {} < {}",
+                value,
+                mapped.x,
+                previous
+            );
+            previous = mapped.x;
+        }
+        assert_eq!(aces(Vec3::ZERO), Vec3::ZERO);
+        assert_eq!(aces(Vec3::splat(4096.0)), Vec3::ONE);
+    }
+
+    #[test]
+    fn reinhard_approaches_but_never_reaches_white() {
+        for value in hdr_sweep() {
+            let mapped = reinhard(Vec3::splat(value)).x;
+            assert!(
+                (0.0..1.0).contains(&mapped),
+                "reinhard({}) = {}",
+                value,
+                mapped
+            );
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod native_apc_handler;
+pub mod native_clipboard_handler;
 pub mod native_http_requester;
 pub mod native_winit_handler;
+pub mod window_geometry;
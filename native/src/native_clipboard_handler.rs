@@ -0,0 +1,77 @@
+use std::sync::Mutex;
+
+use demo_core::traits::clipboard_traits::ClipboardHandler;
+
+/// Clipboard access through `arboard`.
+/// If the system clipboard can't be opened (e.g. no display server), reads return `None`.
+pub struct NativeClipboardHandler {
+    clipboard: Mutex<Option<arboard::Clipboard>>,
+}
+
+impl NativeClipboardHandler {
+    pub fn new() -> Self {
+        let clipboard = arboard::Clipboard::new()
+            .map_err(|e| log::warn!("Clipboard unavailable: {}", e))
+            .ok();
+
+        Self {
+            clipboard: Mutex::new(clipboard),
+        }
+    }
+}
+
+impl Default for NativeClipboardHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClipboardHandler for NativeClipboardHandler {
+    fn clipboard_get(&self) -> Option<String> {
+        let mut clipboard = self.clipboard.lock().ok()?;
+        clipboard.as_mut()?.get_text().ok()
+    }
+
+    fn clipboard_set(&self, text: &str) {
+        let Ok(mut clipboard) = self.clipboard.lock() else {
+            return;
+        };
+        let Some(clipboard) = clipboard.as_mut() else {
+            return;
+        };
+
+        if let Err(e) = clipboard.set_text(text) {
+            log::warn!("Failed to set clipboard text: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_round_trips_through_the_clipboard() {
+        let handler = NativeClipboardHandler::new();
+        if handler.clipboard.lock().unwrap().is_none() {
+            // no display server to own the clipboard
+            return;
+        }
+
+        handler.clipboard_set("copied from the demo");
+        assert_eq!(
+            handler.clipboard_get().as_deref(),
+            Some("copied from the demo")
+        );
+    }
+
+    #[test]
+    fn missing_clipboard_reads_nothing() {
+        let handler = NativeClipboardHandler {
+            clipboard: Mutex::new(None),
+        };
+
+        handler.clipboard_set("dropped");
+        assert_eq!(handler.clipboard_get(), None);
+    }
+}
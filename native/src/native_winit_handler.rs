@@ -1,9 +1,17 @@
-use demo_core::traits::{apc_traits::ApcHandler, http_traits::HttpRequester};
+use demo_core::traits::{
+    apc_traits::ApcHandler, clipboard_traits::ClipboardHandler, http_traits::HttpRequester,
+};
 
-use demo_winit::traits::DemoWinitHandler;
-use winit::{dpi::LogicalSize, window::WindowAttributes};
+use demo_winit::{icon::icon_from_bytes, traits::DemoWinitHandler};
+use winit::{
+    dpi::LogicalSize,
+    window::{Icon, Window, WindowAttributes},
+};
 
-use crate::{native_apc_handler::NativeApcHandler, native_http_requester::NativeHttpRequester};
+use crate::{
+    native_apc_handler::NativeApcHandler, native_clipboard_handler::NativeClipboardHandler,
+    native_http_requester::NativeHttpRequester, window_geometry::WindowGeometry,
+};
 
 // Struct to hold the clients list and implement the callback
 pub struct NativeWinitHandler {}
@@ -13,10 +21,19 @@ impl DemoWinitHandler for NativeWinitHandler {
         &mut self,
         event_loop: &winit::event_loop::ActiveEventLoop,
     ) -> Result<winit::window::Window, String> {
-        let attributes = WindowAttributes::default()
+        let mut attributes = WindowAttributes::default()
             .with_title("Skyshark")
             .with_inner_size(LogicalSize::new(800.0, 600.0));
 
+        // reopen where the window was last closed, unless that is no longer on any monitor
+        match WindowGeometry::default_path().and_then(|path| WindowGeometry::load(&path)) {
+            Ok(geometry) if geometry.is_on_screen(event_loop) => {
+                attributes = geometry.apply(attributes);
+            }
+            Ok(geometry) => log::info!("Ignoring off-screen window geometry {:?}", geometry),
+            Err(e) => log::debug!("{}", e),
+        }
+
         let window = event_loop
             .create_window(attributes)
             .map_err(|e| format!("Failed to create window: {}", e))?;
@@ -24,6 +41,21 @@ impl DemoWinitHandler for NativeWinitHandler {
         Ok(window)
     }
 
+    fn on_exit(&self, window: &Window) {
+        let Some(geometry) = WindowGeometry::from_window(window) else {
+            return;
+        };
+        if let Err(e) = WindowGeometry::default_path().and_then(|path| geometry.save(&path)) {
+            log::warn!("{}", e);
+        }
+    }
+
+    fn window_icon(&self) -> Option<Icon> {
+        icon_from_bytes(include_bytes!("assets/icon.png"))
+            .map_err(|e| log::warn!("{}", e))
+            .ok()
+    }
+
     fn build_apc_handler() -> Box<dyn ApcHandler> {
         Box::new(NativeApcHandler)
     }
@@ -31,4 +63,8 @@ impl DemoWinitHandler for NativeWinitHandler {
     fn build_http_requester() -> Box<dyn HttpRequester> {
         Box::new(NativeHttpRequester)
     }
+
+    fn build_clipboard_handler() -> Box<dyn ClipboardHandler> {
+        Box::new(NativeClipboardHandler::new())
+    }
 }
@@ -0,0 +1,151 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event_loop::ActiveEventLoop,
+    window::{Window, WindowAttributes},
+};
+
+/// Directory under the user's config directory, e.g. `~/.config/skyshark` on Linux
+const CONFIG_DIRECTORY: &str = "skyshark";
+/// File the geometry is saved to, inside `CONFIG_DIRECTORY`
+const WINDOW_GEOMETRY_FILE: &str = "window.toml";
+
+/// Size and position of the window in physical pixels, saved on exit and restored on launch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowGeometry {
+    /// The current geometry of the window, or `None` if it is minimized or has no position
+    pub fn from_window(window: &Window) -> Option<Self> {
+        if window.is_minimized().unwrap_or(false) {
+            return None;
+        }
+
+        let position = window.outer_position().ok()?;
+        let size = window.inner_size();
+        if size.width == 0 || size.height == 0 {
+            return None;
+        }
+
+        Some(Self {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        })
+    }
+
+    /// Default location of the saved geometry, in the per-user config directory
+    pub fn default_path() -> Result<PathBuf, String> {
+        dirs::config_dir()
+            .map(|dir| dir.join(CONFIG_DIRECTORY).join(WINDOW_GEOMETRY_FILE))
+            .ok_or_else(|| "No config directory to save the window geometry in".to_string())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        toml::from_str(&contents).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = toml::to_string(self)
+            .map_err(|e| format!("Failed to serialize window geometry: {}", e))?;
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)
+                .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+        }
+        std::fs::write(path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Whether the window's title bar area would land on one of the connected monitors,
+    /// so a geometry saved with a since disconnected monitor isn't restored off-screen
+    pub fn is_on_screen(&self, event_loop: &ActiveEventLoop) -> bool {
+        // the top strip of the window is what the user needs to grab it
+        const GRAB_HEIGHT: i32 = 32;
+        const MIN_VISIBLE_WIDTH: i32 = 64;
+
+        event_loop.available_monitors().any(|monitor| {
+            let position = monitor.position();
+            let size = monitor.size();
+            let left = self.x.max(position.x);
+            let right = (self.x + self.width as i32).min(position.x + size.width as i32);
+            let top = self.y.max(position.y);
+            let bottom = (self.y + GRAB_HEIGHT).min(position.y + size.height as i32);
+            right - left >= MIN_VISIBLE_WIDTH && bottom > top
+        })
+    }
+
+    /// Applies the size and position to the window attributes
+    pub fn apply(&self, attributes: WindowAttributes) -> WindowAttributes {
+        attributes
+            .with_inner_size(PhysicalSize::new(self.width, self.height))
+            .with_position(PhysicalPosition::new(self.x, self.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a window on a monitor left of the primary one has a negative x
+    const GEOMETRY: WindowGeometry = WindowGeometry {
+        x: -1200,
+        y: 40,
+        width: 1024,
+        height: 768,
+    };
+
+    #[test]
+    fn geometry_round_trips_through_toml() {
+        let contents = toml::to_string(&GEOMETRY).unwrap();
+
+        assert_eq!(toml::from_str::<WindowGeometry>(&contents), Ok(GEOMETRY));
+    }
+
+    #[test]
+    fn saved_geometry_loads_back() {
+        let path = std::env::temp_dir().join(format!("window_{}.toml", std::process::id()));
+
+        GEOMETRY.save(&path).unwrap();
+        let loaded = WindowGeometry::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, Ok(GEOMETRY));
+    }
+
+    #[test]
+    fn saving_creates_the_config_directory() {
+        let directory = std::env::temp_dir().join(format!("skyshark_{}", std::process::id()));
+        let path = directory.join(WINDOW_GEOMETRY_FILE);
+
+        let saved = GEOMETRY.save(&path);
+        let loaded = WindowGeometry::load(&path);
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(saved, Ok(()));
+        assert_eq!(loaded, Ok(GEOMETRY));
+    }
+
+    #[test]
+    fn default_path_is_outside_the_executable_directory() {
+        let path = WindowGeometry::default_path().unwrap();
+        let executable = std::env::current_exe().unwrap();
+
+        assert!(path.ends_with(Path::new(CONFIG_DIRECTORY).join(WINDOW_GEOMETRY_FILE)));
+        assert!(!path.starts_with(executable.parent().unwrap()));
+    }
+
+    #[test]
+    fn missing_fields_fail_to_load() {
+        assert!(toml::from_str::<WindowGeometry>("x = 0\ny = 0").is_err());
+    }
+}
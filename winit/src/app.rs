@@ -2,12 +2,15 @@ use std::sync::Arc;
 
 use demo_core::{
     core::Core,
-    traits::{apc_traits::ApcHandler, http_traits::HttpRequester},
+    traits::{
+        apc_traits::ApcHandler, clipboard_traits::ClipboardHandler, http_traits::HttpRequester,
+    },
 };
 use log::info;
 use wgpu::TextureFormat;
 use winit::{
     application::ApplicationHandler,
+    dpi::PhysicalSize,
     event::{ElementState, KeyEvent, WindowEvent},
     event_loop::ControlFlow,
     keyboard::{Key, KeyCode, NamedKey, PhysicalKey},
@@ -23,6 +26,55 @@ use web_time::{Duration, Instant};
 
 use crate::{traits::DemoWinitHandler, user_event::DemoWinitEvent};
 
+/// How often the event loop wakes up while the window is hidden and updates are paused
+const HIDDEN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Time between frames for a target frame rate, or `None` when 0 asks for an uncapped rate.
+/// Computed in nanoseconds since whole milliseconds would turn 60 fps into 62.5.
+pub fn frame_interval(target_fps: u32) -> Option<Duration> {
+    if target_fps == 0 {
+        return None;
+    }
+
+    Some(Duration::from_nanos(1_000_000_000 / target_fps as u64))
+}
+
+/// Whether the window can be seen. Updates and rendering pause while it can't.
+#[derive(Debug, Default, Clone, Copy)]
+struct WindowVisibility {
+    /// The window was resized to zero, which is how most platforms report minimizing
+    minimized: bool,
+    /// The window is fully hidden behind other windows or on another workspace
+    occluded: bool,
+}
+
+impl WindowVisibility {
+    /// Whether nothing of the window can be seen, so rendering can be skipped
+    fn is_hidden(&self) -> bool {
+        self.minimized || self.occluded
+    }
+
+    /// Records the window's new size and returns whether the render targets should follow it.
+    /// While minimized the old targets are kept instead of shrinking them to nothing.
+    fn resized(&mut self, size: PhysicalSize<u32>) -> bool {
+        self.minimized = size.width == 0 || size.height == 0;
+        !self.minimized
+    }
+}
+
+/// Handles a resize or scale factor change up to resizing the render targets.
+/// The scale factor is applied even while minimized, so it is current once the window is restored.
+/// Returns whether the render targets should be resized to `size`.
+fn apply_window_size(
+    demo_core: &mut Core,
+    visibility: &mut WindowVisibility,
+    size: PhysicalSize<u32>,
+    scale_factor: f64,
+) -> bool {
+    demo_core.set_scale_factor(scale_factor);
+    visibility.resized(size)
+}
+
 #[derive(Debug)]
 struct DemoWinitAppUninit<H> {
     demo_handler: H,
@@ -33,6 +85,8 @@ struct DemoWinitAppInit<H> {
     window: Arc<winit::window::Window>,
     surface: wgpu::Surface<'static>,
     surface_config: wgpu::SurfaceConfiguration,
+    /// Present modes the surface accepts, to validate runtime changes against
+    supported_present_modes: Vec<wgpu::PresentMode>,
     demo_handler: H,
 
     device: Arc<wgpu::Device>,
@@ -43,6 +97,8 @@ struct DemoWinitAppInit<H> {
 
     time_of_last_update: Instant,
 
+    visibility: WindowVisibility,
+
     #[cfg(target_arch = "wasm32")]
     frame_count: u32,
 
@@ -96,6 +152,58 @@ impl<H> DemoWinitApp<H> {
 }
 
 impl<H: DemoWinitHandler> DemoWinitApp<H> {
+    /// Updates the demo core and requests a redraw, then schedules the next frame
+    fn run_frame(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        let demo_winit = self.assume_init();
+        let now = Instant::now();
+
+        if demo_winit.visibility.is_hidden() && !demo_winit.demo_handler.update_while_hidden() {
+            // don't let the hidden time show up as one huge delta once restored
+            demo_winit.time_of_last_update = now;
+            event_loop.set_control_flow(ControlFlow::WaitUntil(now + HIDDEN_POLL_INTERVAL));
+            return;
+        }
+
+        demo_winit.demo_handler.on_pre_update();
+        demo_winit
+            .demo_core
+            .update((now - demo_winit.time_of_last_update).as_secs_f32());
+
+        #[cfg(target_arch = "wasm32")]
+        DemoWinitAppInit::<H>::resize_surface_if_needed(
+            &mut demo_winit.target_buffer_width,
+            &mut demo_winit.target_buffer_height,
+            &demo_winit.surface,
+            &demo_winit.device,
+            &mut demo_winit.surface_config,
+            &demo_winit.frame_count,
+        );
+
+        demo_winit.demo_handler.on_post_update();
+        demo_winit.apply_surface_preferences();
+
+        if let Some(title) = demo_winit.demo_core.take_window_title() {
+            demo_winit.window.set_title(&title);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            demo_winit.frame_count += 1;
+        }
+
+        demo_winit.time_of_last_update = now;
+        if !demo_winit.visibility.is_hidden() {
+            demo_winit.window.request_redraw();
+        }
+
+        // a target of 0 runs uncapped, polling for the next frame straight away
+        let control_flow = match frame_interval(demo_winit.demo_handler.target_fps()) {
+            Some(interval) => ControlFlow::WaitUntil(now + interval),
+            None => ControlFlow::Poll,
+        };
+        event_loop.set_control_flow(control_flow);
+    }
+
     fn init_if_ready(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
         let mut uninit = match &self.inner {
             DemoWinitAppInner::ReadyToInit(_) => match core::mem::take(&mut self.inner) {
@@ -107,6 +215,8 @@ impl<H: DemoWinitHandler> DemoWinitApp<H> {
 
         // build window
         let window = uninit.demo_handler.build_window(event_loop).unwrap();
+        #[cfg(not(target_arch = "wasm32"))]
+        window.set_window_icon(uninit.demo_handler.window_icon());
         let window = Arc::new(window);
 
         // cerate instance
@@ -125,21 +235,19 @@ impl<H: DemoWinitHandler> DemoWinitApp<H> {
 
         // cinfigur surface
         let size = window.inner_size();
-        let surface_config = H::configure_surface(&surface, &adapter, size);
+        let surface_config = uninit
+            .demo_handler
+            .configure_surface(&surface, &adapter, size);
+        let supported_present_modes = surface.get_capabilities(&adapter).present_modes;
 
         surface.configure(&device, &surface_config);
 
         let physical_size = window.inner_size();
 
-        #[cfg(not(target_arch = "wasm32"))]
-        let target_buffer_width = physical_size.width;
-        #[cfg(not(target_arch = "wasm32"))]
-        let target_buffer_height = physical_size.height;
-
-        #[cfg(target_arch = "wasm32")]
-        let target_buffer_width = physical_size.width.clamp(1, 2048);
-        #[cfg(target_arch = "wasm32")]
-        let target_buffer_height = physical_size.height.clamp(1, 2048);
+        // the core applies any user override on later resizes
+        let max_dimension = device.limits().max_texture_dimension_2d.max(1);
+        let target_buffer_width = physical_size.width.clamp(1, max_dimension);
+        let target_buffer_height = physical_size.height.clamp(1, max_dimension);
 
         info!("width: {}", target_buffer_width);
         info!("height: {}", target_buffer_height);
@@ -149,21 +257,25 @@ impl<H: DemoWinitHandler> DemoWinitApp<H> {
 
         let apc_handler = Arc::<dyn ApcHandler>::from(H::build_apc_handler());
         let http_requester = Arc::<dyn HttpRequester>::from(H::build_http_requester());
+        let clipboard_handler = Arc::<dyn ClipboardHandler>::from(H::build_clipboard_handler());
 
-        let demo_core = Core::new(
+        let mut demo_core = Core::new(
             device.clone(),
             queue.clone(),
             apc_handler.clone(),
             http_requester.clone(),
+            clipboard_handler,
             target_buffer_width,
             target_buffer_height,
             surface_config.format,
         );
+        demo_core.set_scale_factor(window.scale_factor());
 
         let init = DemoWinitAppInit {
             window,
             surface,
             surface_config,
+            supported_present_modes,
             demo_handler: uninit.demo_handler,
             device,
             queue,
@@ -173,6 +285,7 @@ impl<H: DemoWinitHandler> DemoWinitApp<H> {
             #[cfg(target_arch = "wasm32")]
             frame_count: 0,
             time_of_last_update: Instant::now(),
+            visibility: WindowVisibility::default(),
 
             #[cfg(feature = "debug-renderdoc")]
             renderdoc: RenderDoc::<V141>::new().expect("Failed to initialize RenderDoc"),
@@ -182,6 +295,25 @@ impl<H: DemoWinitHandler> DemoWinitApp<H> {
     }
 }
 
+impl<H: DemoWinitHandler> DemoWinitAppInit<H> {
+    /// Reconfigures the surface when the handler asks for a different latency or present mode
+    fn apply_surface_preferences(&mut self) {
+        let max_frame_latency = self.demo_handler.max_frame_latency();
+        let present_mode = crate::traits::select_present_mode(
+            self.demo_handler.present_mode(),
+            &self.supported_present_modes,
+        );
+
+        if max_frame_latency != self.surface_config.desired_maximum_frame_latency
+            || present_mode != self.surface_config.present_mode
+        {
+            self.surface_config.desired_maximum_frame_latency = max_frame_latency;
+            self.surface_config.present_mode = present_mode;
+            self.surface.configure(&self.device, &self.surface_config);
+        }
+    }
+}
+
 impl<H> DemoWinitAppInit<H> {
     #[cfg(target_arch = "wasm32")]
     fn resize_surface_if_needed(
@@ -234,14 +366,15 @@ impl<H> DemoWinitAppInit<H> {
     #[cfg(not(target_arch = "wasm32"))]
     pub fn resize(
         physical_size: winit::dpi::PhysicalSize<u32>,
+        max_dimension: u32,
         target_buffer_width: &mut u32,
         target_buffer_height: &mut u32,
         surface: &wgpu::Surface<'static>,
         device: &Arc<wgpu::Device>,
         surface_config: &mut wgpu::SurfaceConfiguration,
     ) {
-        let width = std::cmp::max(1, physical_size.width);
-        let height = std::cmp::max(1, physical_size.height);
+        let width = physical_size.width.clamp(1, max_dimension);
+        let height = physical_size.height.clamp(1, max_dimension);
 
         *target_buffer_width = width;
         *target_buffer_height = height;
@@ -258,17 +391,12 @@ impl<H> DemoWinitAppInit<H> {
     #[cfg(target_arch = "wasm32")]
     pub fn resize(
         physical_size: winit::dpi::PhysicalSize<u32>,
+        max_dimension: u32,
         target_buffer_width: &mut u32,
         target_buffer_height: &mut u32,
     ) {
-        let actual_width = std::cmp::max(1, physical_size.width);
-        let actual_height = std::cmp::max(1, physical_size.height);
-
-        let target_width = std::cmp::min(2048, actual_width);
-        let target_height = std::cmp::min(2048, actual_height);
-
-        *target_buffer_width = target_width;
-        *target_buffer_height = target_height;
+        *target_buffer_width = physical_size.width.clamp(1, max_dimension);
+        *target_buffer_height = physical_size.height.clamp(1, max_dimension);
     }
 
     pub fn render_and_present(&mut self) {
@@ -345,25 +473,35 @@ impl<H: DemoWinitHandler + 'static> ApplicationHandler<DemoWinitEvent> for DemoW
             },
             WindowEvent::MouseWheel { delta, .. } => {
                 let pixels_per_line = 38.0;
+                // keep the full magnitude so trackpads scroll smoothly
                 match delta {
                     winit::event::MouseScrollDelta::PixelDelta(delta) => {
-                        demo_winit
-                            .demo_core
-                            .mouse_scroll(0.0, delta.y.signum() * pixels_per_line);
+                        demo_winit.demo_core.mouse_scroll(delta.x, delta.y);
                     }
-                    winit::event::MouseScrollDelta::LineDelta(_x, y) => {
+                    winit::event::MouseScrollDelta::LineDelta(x, y) => {
                         demo_winit
                             .demo_core
-                            .mouse_scroll(0.0, y.signum() as f64 * pixels_per_line);
+                            .mouse_scroll(x as f64 * pixels_per_line, y as f64 * pixels_per_line);
                     }
                 }
             }
             WindowEvent::Resized(..) | WindowEvent::ScaleFactorChanged { .. } => {
                 let physical_size = demo_winit.window.inner_size();
+                if !apply_window_size(
+                    &mut demo_winit.demo_core,
+                    &mut demo_winit.visibility,
+                    physical_size,
+                    demo_winit.window.scale_factor(),
+                ) {
+                    return;
+                }
+
+                let max_dimension = demo_winit.demo_core.max_render_dimension();
 
                 #[cfg(not(target_arch = "wasm32"))]
                 DemoWinitAppInit::<H>::resize(
                     physical_size,
+                    max_dimension,
                     &mut demo_winit.target_buffer_width,
                     &mut demo_winit.target_buffer_height,
                     &demo_winit.surface,
@@ -374,21 +512,39 @@ impl<H: DemoWinitHandler + 'static> ApplicationHandler<DemoWinitEvent> for DemoW
                 #[cfg(target_arch = "wasm32")]
                 DemoWinitAppInit::<H>::resize(
                     physical_size,
+                    max_dimension,
                     &mut demo_winit.target_buffer_width,
                     &mut demo_winit.target_buffer_height,
                 );
 
-                demo_winit
-                    .demo_core
-                    .resize(physical_size.width, physical_size.height);
+                demo_winit.demo_core.resize(
+                    demo_winit.target_buffer_width,
+                    demo_winit.target_buffer_height,
+                );
 
                 demo_winit.window.request_redraw();
             }
+            #[cfg(not(target_arch = "wasm32"))]
+            WindowEvent::DroppedFile(path) => {
+                // winit sends one event per file when several are dropped at once
+                demo_winit.demo_handler.on_file_dropped(&path);
+                demo_winit.demo_core.load_file(path);
+            }
+            WindowEvent::Occluded(occluded) => {
+                demo_winit.visibility.occluded = occluded;
+                if !demo_winit.visibility.is_hidden() {
+                    demo_winit.window.request_redraw();
+                }
+            }
             WindowEvent::CloseRequested => {
                 // do other cleanup here
                 event_loop.exit();
             }
             WindowEvent::RedrawRequested => {
+                if demo_winit.visibility.is_hidden() {
+                    return;
+                }
+
                 demo_winit.demo_handler.on_pre_draw();
                 demo_winit.render_and_present();
                 demo_winit.demo_handler.on_post_draw();
@@ -407,44 +563,12 @@ impl<H: DemoWinitHandler + 'static> ApplicationHandler<DemoWinitEvent> for DemoW
                 self.ready_init();
                 event_loop.set_control_flow(ControlFlow::WaitUntil(Instant::now()));
             }
-            winit::event::StartCause::ResumeTimeReached { .. } => {
-                let demo_winit = self.assume_init();
-                let now = Instant::now();
-                demo_winit.demo_handler.on_pre_update();
-                demo_winit
-                    .demo_core
-                    .update((now - demo_winit.time_of_last_update).as_secs_f32());
-
-                #[cfg(target_arch = "wasm32")]
-                DemoWinitAppInit::<H>::resize_surface_if_needed(
-                    &mut demo_winit.target_buffer_width,
-                    &mut demo_winit.target_buffer_height,
-                    &demo_winit.surface,
-                    &demo_winit.device,
-                    &mut demo_winit.surface_config,
-                    &demo_winit.frame_count,
-                );
-
-                demo_winit.demo_handler.on_post_update();
-
-                #[cfg(target_arch = "wasm32")]
-                {
-                    demo_winit.frame_count += 1;
-                }
-
-                demo_winit.time_of_last_update = now;
-                demo_winit.window.request_redraw();
-
-                event_loop.set_control_flow(ControlFlow::WaitUntil(
-                    now + Duration::from_millis(1000 / 60),
-                ));
+            winit::event::StartCause::ResumeTimeReached { .. } | winit::event::StartCause::Poll => {
+                self.run_frame(event_loop);
             }
             winit::event::StartCause::WaitCancelled { .. } => {
                 let _ = event_loop;
             }
-            winit::event::StartCause::Poll => {
-                unreachable!()
-            }
         }
     }
 
@@ -470,6 +594,76 @@ impl<H: DemoWinitHandler + 'static> ApplicationHandler<DemoWinitEvent> for DemoW
 
     fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
         let demo_winit = self.assume_init();
-        demo_winit.demo_handler.on_exit();
+        demo_winit.demo_handler.on_exit(&demo_winit.window);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use demo_core::test_device::headless_core;
+
+    use super::*;
+
+    #[test]
+    fn common_frame_rates_have_exact_intervals() {
+        assert_eq!(frame_interval(30), Some(Duration::from_nanos(33_333_333)));
+        assert_eq!(frame_interval(60), Some(Duration::from_nanos(16_666_666)));
+        assert_eq!(frame_interval(144), Some(Duration::from_nanos(6_944_444)));
+    }
+
+    #[test]
+    fn sixty_intervals_add_up_to_a_second() {
+        let second = frame_interval(60).unwrap() * 60;
+        assert!(Duration::from_secs(1) - second < Duration::from_micros(1));
+    }
+
+    #[test]
+    fn zero_runs_uncapped() {
+        assert_eq!(frame_interval(0), None);
+    }
+
+    #[test]
+    fn minimizing_or_occluding_pauses_until_both_clear() {
+        let mut visibility = WindowVisibility::default();
+        assert!(!visibility.is_hidden());
+
+        assert!(!visibility.resized(PhysicalSize::new(0, 0)));
+        assert!(visibility.is_hidden());
+        visibility.occluded = true;
+        // restoring the size alone isn't enough while another window still covers it
+        assert!(visibility.resized(PhysicalSize::new(800, 600)));
+        assert!(visibility.is_hidden());
+        visibility.occluded = false;
+        assert!(!visibility.is_hidden());
+
+        // a zero height minimizes just like a zero width
+        assert!(!visibility.resized(PhysicalSize::new(800, 0)));
+        assert!(visibility.is_hidden());
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn scale_factor_applies_while_minimized() {
+        let mut demo_core = headless_core(64, 64);
+        let mut visibility = WindowVisibility::default();
+
+        // e.g. the minimized window was dragged onto a high DPI monitor
+        let resize = apply_window_size(
+            &mut demo_core,
+            &mut visibility,
+            PhysicalSize::new(0, 0),
+            2.0,
+        );
+        assert!(!resize);
+        assert_eq!(demo_core.scale_factor(), 2.0);
+
+        let resize = apply_window_size(
+            &mut demo_core,
+            &mut visibility,
+            PhysicalSize::new(1600, 1200),
+            2.0,
+        );
+        assert!(resize);
+        assert!(!visibility.is_hidden());
     }
 }
@@ -0,0 +1,59 @@
+use winit::window::Icon;
+
+/// Decodes an image (e.g. PNG bytes from `include_bytes!`) into a window icon
+pub fn icon_from_bytes(bytes: &[u8]) -> Result<Icon, String> {
+    let (rgba, width, height) = decode_rgba(bytes)?;
+
+    Icon::from_rgba(rgba, width, height).map_err(|e| format!("Failed to create window icon: {}", e))
+}
+
+/// The icon's RGBA pixels with its width and height, since an `Icon` can't be inspected
+fn decode_rgba(bytes: &[u8]) -> Result<(Vec<u8>, u32, u32), String> {
+    let image = image::load_from_memory(bytes)
+        .map_err(|e| format!("Failed to decode window icon: {}", e))?
+        .into_rgba8();
+    let (width, height) = image.dimensions();
+
+    Ok((image.into_raw(), width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let image = image::RgbaImage::from_pixel(width, height, image::Rgba([40, 80, 160, 255]));
+        let mut bytes = Vec::new();
+        image::DynamicImage::ImageRgba8(image)
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageOutputFormat::Png,
+            )
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn png_decodes_to_its_size() {
+        let (rgba, width, height) = decode_rgba(&png_bytes(16, 8)).unwrap();
+
+        assert_eq!((width, height), (16, 8));
+        assert_eq!(rgba.len(), 16 * 8 * 4);
+        assert_eq!(&rgba[..4], &[40, 80, 160, 255]);
+        assert!(icon_from_bytes(&png_bytes(16, 8)).is_ok());
+    }
+
+    #[test]
+    fn bad_bytes_are_an_error() {
+        let error = icon_from_bytes(b"not a png").unwrap_err();
+        assert!(
+            error.starts_with("Failed to decode window icon"),
+            "{}",
+            error
+        );
+
+        // a PNG cut off halfway
+        let png = png_bytes(16, 8);
+        assert!(icon_from_bytes(&png[..png.len() / 2]).is_err());
+    }
+}
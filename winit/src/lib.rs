@@ -1,3 +1,4 @@
 pub mod app;
+pub mod icon;
 pub mod traits;
 pub mod user_event;
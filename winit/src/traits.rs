@@ -1,21 +1,39 @@
 use std::sync::Arc;
 
-use demo_core::traits::{apc_traits::ApcHandler, http_traits::HttpRequester};
-use winit::{event_loop::ActiveEventLoop, window::Window};
+use demo_core::traits::{
+    apc_traits::ApcHandler,
+    clipboard_traits::{ClipboardHandler, HeadlessClipboardHandler},
+    http_traits::HttpRequester,
+};
+use winit::{
+    event_loop::ActiveEventLoop,
+    window::{Icon, Window},
+};
 
 /// A trait for configuring our winit window.
 pub trait DemoWinitHandler {
     /// Construct the window from the active event loop.
     fn build_window(&mut self, event_loop: &ActiveEventLoop) -> Result<Window, String>;
 
+    /// The icon set on the window after it is built, see `icon::icon_from_bytes`.
+    /// Ignored on wasm, where the page's favicon is used.
+    fn window_icon(&self) -> Option<Icon> {
+        None
+    }
+
     /// Create an apc handler.
     fn build_apc_handler() -> Box<dyn ApcHandler>;
 
     /// Create an http requester.
     fn build_http_requester() -> Box<dyn HttpRequester>;
 
-    /// Window cleanup.
-    fn on_exit(&self) {}
+    /// Create a clipboard handler. Defaults to one without a clipboard.
+    fn build_clipboard_handler() -> Box<dyn ClipboardHandler> {
+        Box::new(HeadlessClipboardHandler)
+    }
+
+    /// Window cleanup. The window is still open, so its final size and position can be saved.
+    fn on_exit(&self, _window: &Window) {}
 
     /// Called before the demo core is updated.
     fn on_pre_update(&self) {}
@@ -23,12 +41,26 @@ pub trait DemoWinitHandler {
     /// Called after demo core is updated.
     fn on_post_update(&self) {}
 
+    /// Whether demo core keeps updating while the window is minimized or occluded.
+    /// Rendering always stops while hidden; by default updates pause too.
+    fn update_while_hidden(&self) -> bool {
+        false
+    }
+
+    /// Frames per second the event loop aims for. 0 runs uncapped.
+    fn target_fps(&self) -> u32 {
+        60
+    }
+
     /// Called before demo core is rendered.
     fn on_pre_draw(&self) {}
 
     /// Called after demo core is rendered.
     fn on_post_draw(&self) {}
 
+    /// Called when a file is dropped onto the window, before demo core loads it.
+    fn on_file_dropped(&self, _path: &std::path::Path) {}
+
     fn create_instance(&self) -> wgpu::Instance {
         wgpu::Instance::new(wgpu::InstanceDescriptor {
             backends: wgpu::Backends::all(),
@@ -81,45 +113,252 @@ pub trait DemoWinitHandler {
         std::process::exit(1);
     }
 
+    /// Device features to ask for, e.g. `POLYGON_MODE_LINE` or `TIMESTAMP_QUERY`.
+    /// Features the adapter doesn't support are left out instead of failing device creation.
+    fn required_features(&self) -> wgpu::Features {
+        wgpu::Features::empty()
+    }
+
+    /// Device limits to ask for. Defaults to WebGL2 limits, with the largest texture size the
+    /// adapter offers so high-DPI windows aren't downscaled, and its storage buffer limits so
+    /// `StorageBufferLayout` works wherever the adapter has storage buffers. WebGL2 has none.
+    fn required_limits(&self, adapter: &wgpu::Adapter) -> wgpu::Limits {
+        let adapter_limits = adapter.limits();
+        wgpu::Limits {
+            max_texture_dimension_2d: adapter_limits.max_texture_dimension_2d,
+            max_storage_buffers_per_shader_stage: adapter_limits
+                .max_storage_buffers_per_shader_stage,
+            max_storage_buffer_binding_size: adapter_limits.max_storage_buffer_binding_size,
+            ..wgpu::Limits::downlevel_webgl2_defaults()
+        }
+    }
+
+    /// The descriptor `request_device` passes to the adapter, built from `required_features`
+    /// and `required_limits` and trimmed to what `adapter_features` supports
+    fn device_descriptor(
+        &self,
+        adapter: &wgpu::Adapter,
+        adapter_features: wgpu::Features,
+    ) -> wgpu::DeviceDescriptor<'static> {
+        let requested = self.required_features();
+        let granted = requested & adapter_features;
+        if granted != requested {
+            log::warn!(
+                "Adapter doesn't support requested features {:?}",
+                requested - granted
+            );
+        }
+        if !granted.is_empty() {
+            log::info!("Enabled device features {:?}", granted);
+        }
+
+        wgpu::DeviceDescriptor {
+            label: None,
+            required_features: granted,
+            required_limits: self.required_limits(adapter),
+        }
+    }
+
     /// Given your adapter, spin up device + queue
     fn request_device(&self, adapter: &wgpu::Adapter) -> (wgpu::Device, wgpu::Queue) {
-        futures::executor::block_on(adapter.request_device(
-            &wgpu::DeviceDescriptor {
-                label: None,
-                required_features: Default::default(),
-                required_limits: wgpu::Limits {
-                    #[cfg(not(target_arch = "wasm32"))]
-                    max_texture_dimension_2d: 8192,
-                    ..wgpu::Limits::downlevel_webgl2_defaults()
-                },
-            },
-            None,
-        ))
-        .unwrap()
+        let descriptor = self.device_descriptor(adapter, adapter.features());
+        futures::executor::block_on(adapter.request_device(&descriptor, None)).unwrap()
+    }
+
+    /// How many frames the GPU may queue ahead of the one on screen. 1 gives the lowest input
+    /// latency, 3 the most throughput. Changes are applied on the next frame.
+    fn max_frame_latency(&self) -> u32 {
+        2
+    }
+
+    /// Present mode to use when the surface supports it, e.g. `Mailbox` for low latency
+    /// without tearing or `AutoNoVsync` for uncapped frames. `None` uses the surface's
+    /// preferred mode. Changes are applied on the next frame.
+    fn present_mode(&self) -> Option<wgpu::PresentMode> {
+        None
     }
 
     /// Given the surface, device, queue, and window size, produce a `SurfaceConfiguration`
     fn configure_surface(
+        &self,
         surface: &wgpu::Surface,
         adapter: &wgpu::Adapter,
         size: winit::dpi::PhysicalSize<u32>,
     ) -> wgpu::SurfaceConfiguration {
         let caps = surface.get_capabilities(adapter);
+        let max_dimension = adapter.limits().max_texture_dimension_2d;
+        self.surface_configuration(&caps, max_dimension, size)
+    }
+
+    /// The configuration `configure_surface` picks from the surface's capabilities,
+    /// with the size clamped to `max_dimension`
+    fn surface_configuration(
+        &self,
+        caps: &wgpu::SurfaceCapabilities,
+        max_dimension: u32,
+        size: winit::dpi::PhysicalSize<u32>,
+    ) -> wgpu::SurfaceConfiguration {
         let format = caps
             .formats
             .iter()
             .copied()
             .find(wgpu::TextureFormat::is_srgb)
             .unwrap_or(caps.formats[0]);
+        let max_dimension = max_dimension.max(1);
         wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format,
-            width: size.width.clamp(1, 8192),
-            height: size.height.clamp(1, 8192),
-            present_mode: caps.present_modes[0],
+            width: size.width.clamp(1, max_dimension),
+            height: size.height.clamp(1, max_dimension),
+            present_mode: select_present_mode(self.present_mode(), &caps.present_modes),
             alpha_mode: caps.alpha_modes[0],
             view_formats: vec![],
-            desired_maximum_frame_latency: 2,
+            desired_maximum_frame_latency: self.max_frame_latency(),
+        }
+    }
+}
+
+/// The requested present mode if the surface supports it, otherwise the surface's preferred one.
+/// The `Auto` modes are always accepted since wgpu resolves them to a supported mode.
+pub fn select_present_mode(
+    requested: Option<wgpu::PresentMode>,
+    supported: &[wgpu::PresentMode],
+) -> wgpu::PresentMode {
+    match requested {
+        Some(mode @ (wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync)) => mode,
+        Some(mode) if supported.contains(&mode) => mode,
+        Some(mode) => {
+            log::warn!(
+                "Present mode {:?} isn't supported, using {:?}",
+                mode,
+                supported[0]
+            );
+            supported[0]
+        }
+        None => supported[0],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use demo_core::test_device::request_adapter;
+
+    use super::*;
+
+    /// Asks for line polygons and timestamp queries on top of the defaults
+    struct FeatureHandler;
+
+    impl DemoWinitHandler for FeatureHandler {
+        fn build_window(&mut self, _event_loop: &ActiveEventLoop) -> Result<Window, String> {
+            unimplemented!()
+        }
+
+        fn build_apc_handler() -> Box<dyn ApcHandler> {
+            unimplemented!()
+        }
+
+        fn build_http_requester() -> Box<dyn HttpRequester> {
+            unimplemented!()
+        }
+
+        fn required_features(&self) -> wgpu::Features {
+            wgpu::Features::POLYGON_MODE_LINE | wgpu::Features::TIMESTAMP_QUERY
+        }
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn required_features_are_trimmed_to_the_adapter() {
+        let adapter = request_adapter();
+
+        let descriptor = FeatureHandler.device_descriptor(
+            &adapter,
+            wgpu::Features::POLYGON_MODE_LINE | wgpu::Features::DEPTH_CLIP_CONTROL,
+        );
+        assert_eq!(
+            descriptor.required_features,
+            wgpu::Features::POLYGON_MODE_LINE
+        );
+
+        let descriptor = FeatureHandler.device_descriptor(&adapter, wgpu::Features::empty());
+        assert!(descriptor.required_features.is_empty());
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn requested_device_has_the_supported_features() {
+        let adapter = request_adapter();
+
+        let (device, _queue) = FeatureHandler.request_device(&adapter);
+
+        let requested = FeatureHandler.required_features();
+        assert_eq!(
+            device.features() & requested,
+            adapter.features() & requested
+        );
+    }
+
+    #[test]
+    fn unsupported_present_modes_fall_back_to_the_preferred_one() {
+        let supported = [wgpu::PresentMode::Fifo, wgpu::PresentMode::Mailbox];
+
+        assert_eq!(
+            select_present_mode(Some(wgpu::PresentMode::Mailbox), &supported),
+            wgpu::PresentMode::Mailbox
+        );
+        assert_eq!(
+            select_present_mode(Some(wgpu::PresentMode::Immediate), &supported),
+            wgpu::PresentMode::Fifo
+        );
+        assert_eq!(
+            select_present_mode(Some(wgpu::PresentMode::AutoNoVsync), &supported),
+            wgpu::PresentMode::AutoNoVsync
+        );
+        assert_eq!(
+            select_present_mode(None, &supported),
+            wgpu::PresentMode::Fifo
+        );
+    }
+
+    /// Trades throughput for the lowest input latency
+    struct LowLatencyHandler;
+
+    impl DemoWinitHandler for LowLatencyHandler {
+        fn build_window(&mut self, _event_loop: &ActiveEventLoop) -> Result<Window, String> {
+            unimplemented!()
+        }
+
+        fn build_apc_handler() -> Box<dyn ApcHandler> {
+            unimplemented!()
         }
+
+        fn build_http_requester() -> Box<dyn HttpRequester> {
+            unimplemented!()
+        }
+
+        fn max_frame_latency(&self) -> u32 {
+            1
+        }
+    }
+
+    #[test]
+    fn max_frame_latency_reaches_the_surface_configuration() {
+        let caps = wgpu::SurfaceCapabilities {
+            formats: vec![
+                wgpu::TextureFormat::Bgra8Unorm,
+                wgpu::TextureFormat::Bgra8UnormSrgb,
+            ],
+            present_modes: vec![wgpu::PresentMode::Fifo],
+            alpha_modes: vec![wgpu::CompositeAlphaMode::Opaque],
+            usages: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        };
+        let size = winit::dpi::PhysicalSize::new(800, 600);
+
+        let config = LowLatencyHandler.surface_configuration(&caps, 8192, size);
+        assert_eq!(config.desired_maximum_frame_latency, 1);
+        assert_eq!(config.format, wgpu::TextureFormat::Bgra8UnormSrgb);
+
+        let config = FeatureHandler.surface_configuration(&caps, 8192, size);
+        assert_eq!(config.desired_maximum_frame_latency, 2);
     }
 }